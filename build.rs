@@ -1,5 +1,18 @@
+use std::fmt::Write as _;
+use std::path::Path;
+use std::process::Command;
+
+/// Bitmap fonts converted to proportional glyph tables: (source, generated name, letter spacing).
+const FONTS: [(&str, &str, u32); 3] = [
+    ("assets/fonts/10x20.bdf", "REGULAR", 2),
+    ("assets/fonts/9x18B.bdf", "BOLD", 2),
+    ("assets/fonts/6x13.bdf", "SMALL", 1),
+];
+
 fn main() {
     linker_be_nice();
+    generate_fonts();
+    embed_git_hash();
     // make sure linkall.x is the last linker script (otherwise might cause problems with flip-link)
     println!("cargo:rustc-link-arg=-Tlinkall.x");
 }
@@ -38,3 +51,212 @@ fn linker_be_nice() {
         std::env::current_exe().unwrap().display()
     );
 }
+
+/// Expose the commit the firmware is built from as `LILKA_GIT_HASH`, shown on the info screen.
+/// Builds from a tree with uncommitted changes get a `-dirty` suffix.
+fn embed_git_hash() {
+    for path in [".git/HEAD", ".git/refs/heads", ".git/index"] {
+        println!("cargo:rerun-if-changed={}", path);
+    }
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+    let hash = git(&["rev-parse", "--short=8", "HEAD"]).unwrap_or_else(|| "unknown".into());
+    let dirty = git(&["status", "--porcelain", "--untracked-files=no"])
+        .is_some_and(|changes| !changes.is_empty());
+    let suffix = if dirty { "-dirty" } else { "" };
+    println!("cargo:rustc-env=LILKA_GIT_HASH={}{}", hash, suffix);
+}
+
+/// Convert the BDF fonts in `FONTS` into `$OUT_DIR/fonts.rs`, included by `src/ui/font.rs`.
+///
+/// The misc-fixed sources are monospace; every glyph is trimmed to its ink and gets
+/// an advance of ink width plus letter spacing, which makes the text proportional.
+fn generate_fonts() {
+    let mut out = String::new();
+    for (path, name, spacing) in FONTS {
+        println!("cargo:rerun-if-changed={}", path);
+        let source = std::fs::read_to_string(path)
+            .unwrap_or_else(|e| panic!("failed to read {}: {}", path, e));
+        let font = parse_bdf(&source, spacing);
+        write_font(&mut out, name, &font);
+    }
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    std::fs::write(Path::new(&out_dir).join("fonts.rs"), out).unwrap();
+}
+
+struct BdfFont {
+    ascent: i32,
+    descent: i32,
+    glyphs: Vec<BdfGlyph>,
+    bits: Vec<bool>,
+}
+
+struct BdfGlyph {
+    code: u32,
+    advance: i32,
+    /// Offset of the top ink row from the baseline, negative is above it.
+    top: i32,
+    width: usize,
+    height: usize,
+    offset: usize,
+}
+
+fn parse_bdf(source: &str, spacing: u32) -> BdfFont {
+    let mut font = BdfFont {
+        ascent: 0,
+        descent: 0,
+        glyphs: Vec::new(),
+        bits: Vec::new(),
+    };
+
+    let mut lines = source.lines();
+    while let Some(line) = lines.next() {
+        let mut fields = line.split_whitespace();
+        match fields.next() {
+            Some("FONT_ASCENT") => font.ascent = fields.next().unwrap().parse().unwrap(),
+            Some("FONT_DESCENT") => font.descent = fields.next().unwrap().parse().unwrap(),
+            Some("STARTCHAR") => {
+                let (mut code, mut dwidth, mut bbx) = (0u32, 0i32, [0i32; 4]);
+                let mut rows: Vec<Vec<bool>> = Vec::new();
+                for line in lines.by_ref() {
+                    let mut fields = line.split_whitespace();
+                    match fields.next() {
+                        Some("ENCODING") => code = fields.next().unwrap().parse().unwrap(),
+                        Some("DWIDTH") => dwidth = fields.next().unwrap().parse().unwrap(),
+                        Some("BBX") => {
+                            for value in bbx.iter_mut() {
+                                *value = fields.next().unwrap().parse().unwrap();
+                            }
+                        }
+                        Some("BITMAP") => {}
+                        Some("ENDCHAR") => break,
+                        Some(hex) if !rows.is_empty() || bbx[1] > 0 => {
+                            let value = u32::from_str_radix(hex, 16).unwrap();
+                            let bits = hex.len() * 4;
+                            rows.push(
+                                (0..bbx[0] as usize)
+                                    .map(|x| value & (1 << (bits - 1 - x)) != 0)
+                                    .collect(),
+                            );
+                        }
+                        _ => {}
+                    }
+                }
+                add_glyph(&mut font, code, dwidth, bbx, &rows, spacing);
+            }
+            _ => {}
+        }
+    }
+
+    font.glyphs.sort_by_key(|glyph| glyph.code);
+    assert!(
+        font.glyphs.len() < 2048,
+        "glyph index must fit the cache entry"
+    );
+    font
+}
+
+fn add_glyph(
+    font: &mut BdfFont,
+    code: u32,
+    dwidth: i32,
+    bbx: [i32; 4],
+    rows: &[Vec<bool>],
+    spacing: u32,
+) {
+    let [_, height, _, y_offset] = bbx;
+    let ink_cols: Vec<usize> = (0..rows.first().map_or(0, |row| row.len()))
+        .filter(|&x| rows.iter().any(|row| row[x]))
+        .collect();
+    let ink_rows: Vec<usize> = (0..rows.len())
+        .filter(|&y| rows[y].iter().any(|&b| b))
+        .collect();
+
+    let (Some(&left), Some(&right), Some(&top), Some(&bottom)) = (
+        ink_cols.first(),
+        ink_cols.last(),
+        ink_rows.first(),
+        ink_rows.last(),
+    ) else {
+        // Blank glyphs such as the space keep half of their cell as the advance
+        font.glyphs.push(BdfGlyph {
+            code,
+            advance: (dwidth / 2).max(2),
+            top: 0,
+            width: 0,
+            height: 0,
+            offset: font.bits.len(),
+        });
+        return;
+    };
+
+    let offset = font.bits.len();
+    for row in &rows[top..=bottom] {
+        font.bits.extend_from_slice(&row[left..=right]);
+    }
+    let width = right - left + 1;
+    font.glyphs.push(BdfGlyph {
+        code,
+        advance: width as i32 + spacing as i32,
+        // Row 0 of the bitmap is `y_offset + height - 1` pixels above the baseline
+        top: top as i32 - (y_offset + height - 1),
+        width,
+        height: bottom - top + 1,
+        offset,
+    });
+}
+
+fn write_font(out: &mut String, name: &str, font: &BdfFont) {
+    let replacement = font
+        .glyphs
+        .iter()
+        .position(|glyph| glyph.code == 0xFFFD)
+        .or_else(|| {
+            font.glyphs
+                .iter()
+                .position(|glyph| glyph.code == '?' as u32)
+        })
+        .expect("font has no replacement glyph");
+
+    writeln!(out, "pub static {}: BitmapFont = BitmapFont {{", name).unwrap();
+    writeln!(out, "    ascent: {},", font.ascent).unwrap();
+    writeln!(out, "    descent: {},", font.descent).unwrap();
+    writeln!(out, "    replacement: {},", replacement).unwrap();
+    writeln!(out, "    cache: &{}_CACHE,", name).unwrap();
+    writeln!(out, "    glyphs: &[").unwrap();
+    for glyph in &font.glyphs {
+        writeln!(
+            out,
+            "        Glyph {{ code: {:#x}, advance: {}, top: {}, width: {}, height: {}, offset: {} }},",
+            glyph.code, glyph.advance, glyph.top, glyph.width, glyph.height, glyph.offset
+        )
+        .unwrap();
+    }
+    writeln!(out, "    ],").unwrap();
+
+    let bytes: Vec<String> = font
+        .bits
+        .chunks(8)
+        .map(|chunk| {
+            let byte = chunk
+                .iter()
+                .enumerate()
+                .fold(0u8, |acc, (i, &bit)| acc | ((bit as u8) << (7 - i)));
+            format!("{:#04x}", byte)
+        })
+        .collect();
+    writeln!(out, "    bitmap: &[{}],", bytes.join(", ")).unwrap();
+    writeln!(out, "}};").unwrap();
+    writeln!(
+        out,
+        "static {}_CACHE: GlyphCache = GlyphCache::new();\n",
+        name
+    )
+    .unwrap();
+}
@@ -0,0 +1,228 @@
+//! Battery gauge math: charge level from the voltage, filtering and charge detection.
+
+/// Typical single-cell Li-ion open-circuit discharge curve: (millivolts, percent),
+/// sorted by voltage. Values in between are interpolated linearly.
+///
+/// The top point sits just under the ~4.12 V the ADC can read behind the divider,
+/// so a full cell still reaches 100%.
+const DISCHARGE_CURVE: [(u32, u8); 11] = [
+    (3200, 0),
+    (3450, 5),
+    (3600, 10),
+    (3700, 20),
+    (3750, 30),
+    (3790, 40),
+    (3830, 50),
+    (3870, 60),
+    (3950, 70),
+    (4050, 85),
+    (4100, 100),
+];
+
+// A rise of this much over the detector window counts as charging
+const CHARGE_RISE_MV: u32 = 30;
+
+/// Map a battery voltage to a state-of-charge percentage.
+pub fn percent_from_mv(mv: u32) -> u8 {
+    let (first_mv, first_pct) = DISCHARGE_CURVE[0];
+    if mv <= first_mv {
+        return first_pct;
+    }
+
+    for window in DISCHARGE_CURVE.windows(2) {
+        let (lo_mv, lo_pct) = window[0];
+        let (hi_mv, hi_pct) = window[1];
+        if mv <= hi_mv {
+            let span = (hi_pct - lo_pct) as u32;
+            return lo_pct + ((mv - lo_mv) * span / (hi_mv - lo_mv)) as u8;
+        }
+    }
+
+    100
+}
+
+/// Moving average over the last `N` readings.
+pub struct MovingAverage<const N: usize> {
+    samples: [u32; N],
+    len: usize,
+    next: usize,
+}
+
+impl<const N: usize> MovingAverage<N> {
+    pub const fn new() -> Self {
+        Self {
+            samples: [0; N],
+            len: 0,
+            next: 0,
+        }
+    }
+
+    /// Add a reading and return the current average.
+    pub fn push(&mut self, sample: u32) -> u32 {
+        self.samples[self.next] = sample;
+        self.next = (self.next + 1) % N;
+        self.len = (self.len + 1).min(N);
+        self.average()
+    }
+
+    pub fn average(&self) -> u32 {
+        if self.len == 0 {
+            return 0;
+        }
+        self.samples[..self.len].iter().sum::<u32>() / self.len as u32
+    }
+}
+
+impl<const N: usize> Default for MovingAverage<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Infers whether the battery is charging from the filtered voltage,
+/// as the board has no dedicated charger status line.
+///
+/// Only a rising voltage counts. With 11 dB attenuation the ADC tops out around 3.1 V
+/// at the pin, about 4.12 V at the battery behind the divider, so the charge voltage
+/// itself can't be told apart from a full cell.
+pub struct ChargeDetector<const N: usize> {
+    history: [u32; N],
+    len: usize,
+    next: usize,
+}
+
+impl<const N: usize> ChargeDetector<N> {
+    pub const fn new() -> Self {
+        Self {
+            history: [0; N],
+            len: 0,
+            next: 0,
+        }
+    }
+
+    /// Feed a filtered reading and return whether the battery looks like it is charging.
+    pub fn update(&mut self, mv: u32) -> bool {
+        let oldest = if self.len == N {
+            Some(self.history[self.next])
+        } else {
+            None
+        };
+
+        self.history[self.next] = mv;
+        self.next = (self.next + 1) % N;
+        self.len = (self.len + 1).min(N);
+
+        oldest.is_some_and(|oldest| mv >= oldest + CHARGE_RISE_MV)
+    }
+}
+
+impl<const N: usize> Default for ChargeDetector<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percent_at_curve_endpoints() {
+        assert_eq!(percent_from_mv(3200), 0);
+        assert_eq!(percent_from_mv(4100), 100);
+    }
+
+    #[test]
+    fn percent_clamped_outside_curve() {
+        assert_eq!(percent_from_mv(0), 0);
+        assert_eq!(percent_from_mv(3000), 0);
+        assert_eq!(percent_from_mv(4300), 100);
+    }
+
+    #[test]
+    fn percent_at_curve_points() {
+        assert_eq!(percent_from_mv(3700), 20);
+        assert_eq!(percent_from_mv(4050), 85);
+    }
+
+    #[test]
+    fn percent_interpolated_between_points() {
+        assert_eq!(percent_from_mv(3850), 55);
+        assert_eq!(percent_from_mv(4075), 92);
+        // Rounded down
+        assert_eq!(percent_from_mv(3325), 2);
+    }
+
+    #[test]
+    fn full_at_adc_limit() {
+        assert_eq!(percent_from_mv(4120), 100);
+    }
+
+    #[test]
+    fn percent_never_decreases_with_voltage() {
+        let mut last = 0;
+        for mv in (3000..4300).step_by(5) {
+            let percent = percent_from_mv(mv);
+            assert!(percent >= last, "{} mV: {}% after {}%", mv, percent, last);
+            last = percent;
+        }
+    }
+
+    #[test]
+    fn average_empty() {
+        assert_eq!(MovingAverage::<4>::new().average(), 0);
+    }
+
+    #[test]
+    fn average_over_readings_so_far_while_warming_up() {
+        let mut filter = MovingAverage::<4>::new();
+        assert_eq!(filter.push(4000), 4000);
+        assert_eq!(filter.push(3800), 3900);
+        assert_eq!(filter.push(3600), 3800);
+    }
+
+    #[test]
+    fn average_drops_oldest_reading() {
+        let mut filter = MovingAverage::<3>::new();
+        filter.push(3000);
+        filter.push(3600);
+        filter.push(3600);
+        assert_eq!(filter.push(3900), 3700);
+        assert_eq!(filter.push(3900), 3800);
+    }
+
+    #[test]
+    fn no_charging_until_window_filled() {
+        let mut detector = ChargeDetector::<3>::new();
+        assert!(!detector.update(3700));
+        assert!(!detector.update(3800));
+        assert!(!detector.update(3900));
+        assert!(detector.update(3900));
+    }
+
+    #[test]
+    fn charging_on_rise_over_window() {
+        let mut detector = ChargeDetector::<3>::new();
+        for mv in [3700, 3705, 3710] {
+            detector.update(mv);
+        }
+        assert!(!detector.update(3729));
+        assert!(detector.update(3735));
+    }
+
+    #[test]
+    fn no_charging_when_flat_or_falling() {
+        let mut detector = ChargeDetector::<3>::new();
+        for mv in [4120, 4120, 4110, 4100, 4100, 4090] {
+            assert!(!detector.update(mv));
+        }
+    }
+
+    #[test]
+    fn full_cell_at_adc_limit_is_not_charging() {
+        let mut detector = ChargeDetector::<3>::new();
+        for _ in 0..6 {
+            assert!(!detector.update(4120));
+        }
+    }
+}
@@ -0,0 +1,155 @@
+//! Crash reports as kept across resets, and their layout in storage.
+
+use core::fmt;
+
+use embassy_time::Duration;
+use heapless::{String, Vec};
+
+const MESSAGE_LEN: usize = 128;
+const LOCATION_LEN: usize = 64;
+/// Return addresses kept from the backtrace.
+pub const MAX_FRAMES: usize = 8;
+
+/// Leads every stored report; erasing it is enough to clear one.
+pub const MAGIC: [u8; 4] = *b"LLKC";
+// Magic, message, location and frame counts, uptime in seconds, frames, texts, checksum
+const HEADER_SIZE: usize = MAGIC.len() + 4 + 4;
+pub const RECORD_SIZE: usize = HEADER_SIZE + MAX_FRAMES * 4 + MESSAGE_LEN + LOCATION_LEN + 1;
+
+/// What is known about a panic. Texts that don't fit are cut off.
+#[derive(Clone, Debug)]
+pub struct CrashReport {
+    pub message: String<MESSAGE_LEN>,
+    /// `file:line:column` of the panic, empty when unknown.
+    pub location: String<LOCATION_LEN>,
+    /// Time since boot when it happened.
+    pub uptime: Duration,
+    /// Return addresses, innermost first. `addr2line -e <elf>` turns them into lines.
+    pub backtrace: Vec<u32, MAX_FRAMES>,
+}
+
+impl CrashReport {
+    /// The report as stored in RTC memory and flash.
+    pub fn encode(&self) -> [u8; RECORD_SIZE] {
+        let mut record = [0xFF; RECORD_SIZE];
+        record[..MAGIC.len()].copy_from_slice(&MAGIC);
+        record[4] = self.message.len() as u8;
+        record[5] = self.location.len() as u8;
+        record[6] = self.backtrace.len() as u8;
+        record[8..12].copy_from_slice(&(self.uptime.as_secs() as u32).to_le_bytes());
+
+        let mut at = HEADER_SIZE;
+        for pc in &self.backtrace {
+            record[at..at + 4].copy_from_slice(&pc.to_le_bytes());
+            at += 4;
+        }
+        at = HEADER_SIZE + MAX_FRAMES * 4;
+        record[at..at + self.message.len()].copy_from_slice(self.message.as_bytes());
+        at += MESSAGE_LEN;
+        record[at..at + self.location.len()].copy_from_slice(self.location.as_bytes());
+
+        record[RECORD_SIZE - 1] = checksum(&record[..RECORD_SIZE - 1]);
+        record
+    }
+
+    /// `None` if `record` holds no report, e.g. erased flash or RTC memory after power-on.
+    pub fn decode(record: &[u8; RECORD_SIZE]) -> Option<Self> {
+        if record[..MAGIC.len()] != MAGIC
+            || record[RECORD_SIZE - 1] != checksum(&record[..RECORD_SIZE - 1])
+        {
+            return None;
+        }
+        let message_len = record[4] as usize;
+        let location_len = record[5] as usize;
+        let frames = record[6] as usize;
+        if message_len > MESSAGE_LEN || location_len > LOCATION_LEN || frames > MAX_FRAMES {
+            return None;
+        }
+        let uptime = u32::from_le_bytes(record[8..12].try_into().ok()?);
+
+        let backtrace = record[HEADER_SIZE..HEADER_SIZE + frames * 4]
+            .chunks_exact(4)
+            .map(|pc| u32::from_le_bytes([pc[0], pc[1], pc[2], pc[3]]))
+            .collect();
+        let at = HEADER_SIZE + MAX_FRAMES * 4;
+        let message = core::str::from_utf8(&record[at..at + message_len]).ok()?;
+        let at = at + MESSAGE_LEN;
+        let location = core::str::from_utf8(&record[at..at + location_len]).ok()?;
+
+        Some(Self {
+            message: message.try_into().ok()?,
+            location: location.try_into().ok()?,
+            uptime: Duration::from_secs(uptime as u64),
+            backtrace,
+        })
+    }
+}
+
+impl fmt::Display for CrashReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let secs = self.uptime.as_secs();
+        writeln!(
+            f,
+            "crash after {}:{:02}:{:02}: {}",
+            secs / 3600,
+            secs / 60 % 60,
+            secs % 60,
+            self.message
+        )?;
+        if !self.location.is_empty() {
+            writeln!(f, "  at {}", self.location)?;
+        }
+        write!(f, "  backtrace:")?;
+        for pc in &self.backtrace {
+            write!(f, " 0x{:08x}", pc)?;
+        }
+        writeln!(f)
+    }
+}
+
+fn checksum(bytes: &[u8]) -> u8 {
+    bytes
+        .iter()
+        .fold(0xA5u8, |acc, byte| acc.rotate_left(1) ^ byte)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> CrashReport {
+        CrashReport {
+            message: "index out of bounds".try_into().unwrap(),
+            location: "src/main.rs:10:5".try_into().unwrap(),
+            uptime: Duration::from_secs(3725),
+            backtrace: [0x4200_1234, 0x4200_5678].into_iter().collect(),
+        }
+    }
+
+    #[test]
+    fn decode_reads_back_encode() {
+        let decoded = CrashReport::decode(&report().encode()).unwrap();
+        assert_eq!(decoded.message, "index out of bounds");
+        assert_eq!(decoded.location, "src/main.rs:10:5");
+        assert_eq!(decoded.uptime, Duration::from_secs(3725));
+        assert_eq!(decoded.backtrace, report().backtrace);
+    }
+
+    #[test]
+    fn decode_rejects_damaged_records() {
+        assert!(CrashReport::decode(&[0xFF; RECORD_SIZE]).is_none());
+        assert!(CrashReport::decode(&[0; RECORD_SIZE]).is_none());
+        let mut record = report().encode();
+        record[HEADER_SIZE] ^= 1;
+        assert!(CrashReport::decode(&record).is_none());
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            report().to_string(),
+            "crash after 1:02:05: index out of bounds\n  at src/main.rs:10:5\n  \
+             backtrace: 0x42001234 0x42005678\n"
+        );
+    }
+}
@@ -0,0 +1,209 @@
+//! Runtime health figures as measured by the firmware's diagnostics service.
+
+use core::fmt;
+
+/// The tasks whose polls are counted.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Task {
+    Ui,
+    Input,
+    Tick,
+    Audio,
+    Battery,
+    Network,
+    Ntp,
+    Settings,
+    Shell,
+    Diagnostics,
+    Supervisor,
+}
+
+pub const TASK_COUNT: usize = 11;
+
+impl Task {
+    pub const ALL: [Task; TASK_COUNT] = [
+        Task::Ui,
+        Task::Input,
+        Task::Tick,
+        Task::Audio,
+        Task::Battery,
+        Task::Network,
+        Task::Ntp,
+        Task::Settings,
+        Task::Shell,
+        Task::Diagnostics,
+        Task::Supervisor,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Task::Ui => "ui",
+            Task::Input => "input",
+            Task::Tick => "tick",
+            Task::Audio => "audio",
+            Task::Battery => "battery",
+            Task::Network => "network",
+            Task::Ntp => "ntp",
+            Task::Settings => "settings",
+            Task::Shell => "shell",
+            Task::Diagnostics => "diag",
+            Task::Supervisor => "supervisor",
+        }
+    }
+}
+
+/// Heap usage in bytes. `used` and `free` cover internal RAM and PSRAM together,
+/// fragmentation only internal RAM, where it hurts.
+#[derive(Copy, Clone, Debug)]
+pub struct HeapReport {
+    pub used: usize,
+    pub free: usize,
+    pub internal_free: usize,
+    /// The largest block that could be allocated in internal RAM, measured when the
+    /// report is read.
+    pub largest_free: usize,
+}
+
+impl HeapReport {
+    /// Share of the free internal RAM that is not in the largest block, in percent.
+    pub fn fragmentation(&self) -> u32 {
+        if self.internal_free == 0 {
+            return 0;
+        }
+        (100 - self.largest_free.min(self.internal_free) as u64 * 100 / self.internal_free as u64)
+            as u32
+    }
+}
+
+/// Main stack usage in bytes.
+#[derive(Copy, Clone, Debug)]
+pub struct StackReport {
+    /// Deepest use since boot, 0 when the stack could not be painted.
+    pub peak: usize,
+    pub size: usize,
+}
+
+/// How late the executor woke a timer, in microseconds.
+#[derive(Copy, Clone, Debug)]
+pub struct LatencyReport {
+    pub avg_us: u32,
+    pub max_us: u32,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct TaskReport {
+    pub task: Task,
+    /// Polls since boot.
+    pub polls: u32,
+    /// Time spent in polls during the last second, in 1/1000.
+    pub load: u32,
+    /// Longest poll since boot.
+    pub max_poll_us: u32,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct ChannelReport {
+    pub name: &'static str,
+    pub len: usize,
+    pub capacity: usize,
+    /// Highest fill seen by the samples since boot.
+    pub peak: usize,
+}
+
+/// Everything measured, refreshed once a second by `diagnostics_task`.
+#[derive(Copy, Clone, Debug)]
+pub struct Report {
+    pub heap: HeapReport,
+    pub stack: StackReport,
+    pub latency: LatencyReport,
+    pub tasks: [TaskReport; TASK_COUNT],
+    pub channels: [ChannelReport; 2],
+}
+
+impl Report {
+    pub const EMPTY: Self = Self {
+        heap: HeapReport {
+            used: 0,
+            free: 0,
+            internal_free: 0,
+            largest_free: 0,
+        },
+        stack: StackReport { peak: 0, size: 0 },
+        latency: LatencyReport {
+            avg_us: 0,
+            max_us: 0,
+        },
+        tasks: {
+            let mut tasks = [TaskReport {
+                task: Task::Ui,
+                polls: 0,
+                load: 0,
+                max_poll_us: 0,
+            }; TASK_COUNT];
+            let mut idx = 0;
+            while idx < TASK_COUNT {
+                tasks[idx].task = Task::ALL[idx];
+                idx += 1;
+            }
+            tasks
+        },
+        channels: [
+            ChannelReport {
+                name: "ui",
+                len: 0,
+                capacity: 0,
+                peak: 0,
+            },
+            ChannelReport {
+                name: "audio",
+                len: 0,
+                capacity: 0,
+                peak: 0,
+            },
+        ],
+    };
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let heap = self.heap;
+        writeln!(
+            f,
+            "heap: {} used, {} free, {} internal with largest block {}, {}% fragmented",
+            heap.used,
+            heap.free,
+            heap.internal_free,
+            heap.largest_free,
+            heap.fragmentation()
+        )?;
+        writeln!(
+            f,
+            "stack: {} of {} bytes used at peak",
+            self.stack.peak, self.stack.size
+        )?;
+        writeln!(
+            f,
+            "timer latency: {} us average, {} us max",
+            self.latency.avg_us, self.latency.max_us
+        )?;
+        for task in &self.tasks {
+            writeln!(
+                f,
+                "task {}: {} polls, load {}.{}%, longest poll {} us",
+                task.task.name(),
+                task.polls,
+                task.load / 10,
+                task.load % 10,
+                task.max_poll_us
+            )?;
+        }
+        for channel in &self.channels {
+            writeln!(
+                f,
+                "queue {}: {}/{} (peak {})",
+                channel.name, channel.len, channel.capacity, channel.peak
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,13 @@
+//! The parts of the firmware that don't touch any hardware, kept in their own crate so
+//! they build and are tested on the host. See "Host tests" in the Readme.
+
+#![cfg_attr(not(test), no_std)]
+
+extern crate alloc;
+
+pub mod battery;
+pub mod crash;
+pub mod diagnostics;
+pub mod logger;
+pub mod network;
+pub mod shell;
@@ -0,0 +1,36 @@
+//! Log records as kept in RAM for the log viewer and the console.
+
+use core::fmt;
+
+use embassy_time::Instant;
+use heapless::String;
+use log::Level;
+
+pub const MESSAGE_LEN: usize = 96;
+
+/// One log line as kept in RAM. Longer messages are cut off.
+#[derive(Clone, Debug)]
+pub struct LogRecord {
+    pub level: Level,
+    /// Last segment of the module path, e.g. `ntp`.
+    pub module: &'static str,
+    /// Time since boot.
+    pub timestamp: Instant,
+    pub message: String<MESSAGE_LEN>,
+}
+
+/// Same layout as on the serial console.
+impl fmt::Display for LogRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let millis = self.timestamp.as_millis();
+        write!(
+            f,
+            "[{:>6}.{:03}] {:<5} {}: {}",
+            millis / 1000,
+            millis % 1000,
+            self.level,
+            self.module,
+            self.message
+        )
+    }
+}
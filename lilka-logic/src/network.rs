@@ -0,0 +1,29 @@
+//! Wi-Fi data shared between the network service and the console.
+
+use heapless::String;
+
+/// Network the station joins, the built-in one until another is picked.
+#[derive(Clone, Debug)]
+pub struct Credentials {
+    pub ssid: String<32>,
+    pub password: String<64>,
+}
+
+impl Credentials {
+    /// `None` if the SSID or the password is longer than Wi-Fi allows.
+    pub fn new(ssid: &str, password: &str) -> Option<Self> {
+        Some(Self {
+            ssid: ssid.try_into().ok()?,
+            password: password.try_into().ok()?,
+        })
+    }
+}
+
+/// A network found by a scan.
+#[derive(Clone, Debug)]
+pub struct AccessPoint {
+    pub ssid: String<32>,
+    pub channel: u8,
+    /// Signal strength in dBm.
+    pub rssi: i8,
+}
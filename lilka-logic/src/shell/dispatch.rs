@@ -0,0 +1,519 @@
+use core::fmt::{self, Write};
+use core::net::Ipv4Addr;
+
+use alloc::vec::Vec;
+use jiff::Timestamp;
+use log::Level;
+
+use crate::crash::CrashReport;
+use crate::diagnostics::Report;
+use crate::logger::LogRecord;
+use crate::network::{AccessPoint, Credentials};
+use crate::shell::parser::Command;
+use crate::shell::settings::Key;
+
+/// Usage and description of every command, printed by `help`.
+const HELP: [(&str, &str); 17] = [
+    ("help", "show this list"),
+    ("wifi [status]", "current network and address"),
+    ("wifi scan", "list networks nearby"),
+    (
+        "wifi connect <ssid> [password]",
+        "join another network until reboot",
+    ),
+    ("time", "current time in UTC"),
+    ("ntp sync", "sync the clock now"),
+    ("play <song>", "play a song by name or number"),
+    ("stop", "stop playback"),
+    ("songs", "list the songs"),
+    ("settings [get] [key]", "show settings"),
+    ("settings set <key> <value>", "change a setting"),
+    (
+        "screen push [name]",
+        "open a screen, list them without a name",
+    ),
+    ("diag", "heap, stack and task statistics"),
+    ("log [level]", "recent log records, info and up by default"),
+    ("crash", "report of the last panic"),
+    ("crash clear", "forget the last panic"),
+    ("reboot", "restart the device"),
+];
+
+/// Why `Backend::open_screen` did not open a screen.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum OpenError {
+    /// There is no screen by that name.
+    Unknown,
+    /// The UI queue is full, nothing was opened.
+    Busy,
+}
+
+/// What the commands act on. The device implements it with the services,
+/// so the dispatcher itself does not touch any hardware.
+// Everything runs on one executor, the futures need not be `Send`
+#[allow(async_fn_in_trait)]
+pub trait Backend {
+    type NetworkError: fmt::Display;
+    type NtpError: fmt::Display;
+    type AudioError: fmt::Display;
+    /// What `settings` returns, changed by name through `setting_keys`.
+    type Settings: Copy + 'static;
+
+    async fn wifi_scan(&mut self) -> Result<Vec<AccessPoint>, Self::NetworkError>;
+    fn wifi_connect(&mut self, credentials: Credentials) -> Result<(), Self::NetworkError>;
+    fn wifi_ssid(&self) -> heapless::String<32>;
+    /// `None` while there is no IP address.
+    fn wifi_address(&self) -> Option<Ipv4Addr>;
+    /// `None` while the clock isn't running.
+    fn now(&self) -> Option<Timestamp>;
+    fn clock_synced(&self) -> bool;
+    async fn ntp_sync(&mut self) -> Result<(), Self::NtpError>;
+    /// Name of the song at `index`, `None` past the last one.
+    fn song(&self, index: usize) -> Option<&'static str>;
+    /// Start the song at `index`.
+    fn play(&mut self, index: usize) -> Result<(), Self::AudioError>;
+    fn stop(&mut self) -> Result<(), Self::AudioError>;
+    fn settings(&self) -> Self::Settings;
+    fn set_settings(&mut self, settings: Self::Settings);
+    /// The settings the console shows and changes, by name.
+    fn setting_keys(&self) -> &'static [Key<Self::Settings>];
+    /// Names accepted by `open_screen`.
+    fn screens(&self) -> &'static [&'static str];
+    /// Open the named screen.
+    fn open_screen(&mut self, name: &str) -> Result<(), OpenError>;
+    fn diagnostics(&self) -> Report;
+    /// Kept log records at `level` or more severe, oldest first.
+    fn log(&self, level: Level) -> Vec<LogRecord>;
+    /// Report of the last panic, `None` if there was none or it was cleared.
+    fn last_crash(&self) -> Option<CrashReport>;
+    fn clear_crash(&mut self);
+    fn reboot(&mut self) -> !;
+}
+
+/// Run `command` and print its outcome to `out`.
+pub async fn execute(
+    command: Command<'_>,
+    backend: &mut impl Backend,
+    out: &mut impl Write,
+) -> fmt::Result {
+    match command {
+        Command::Help => {
+            for (usage, description) in HELP {
+                writeln!(out, "  {:<32} {}", usage, description)?;
+            }
+        }
+        Command::WifiStatus => {
+            write!(out, "network: {}, address: ", backend.wifi_ssid())?;
+            match backend.wifi_address() {
+                Some(address) => writeln!(out, "{}", address)?,
+                None => writeln!(out, "none")?,
+            }
+        }
+        Command::WifiScan => match backend.wifi_scan().await {
+            Ok(found) if found.is_empty() => writeln!(out, "no networks found")?,
+            Ok(found) => {
+                for ap in found {
+                    writeln!(
+                        out,
+                        "  {:>4} dBm  ch {:>2}  {}",
+                        ap.rssi, ap.channel, ap.ssid
+                    )?;
+                }
+            }
+            Err(e) => writeln!(out, "error: {}", e)?,
+        },
+        Command::WifiConnect { ssid, password } => match Credentials::new(ssid, password) {
+            Some(credentials) => match backend.wifi_connect(credentials) {
+                Ok(()) => writeln!(out, "connecting to {}", ssid)?,
+                Err(e) => writeln!(out, "error: {}", e)?,
+            },
+            None => writeln!(out, "error: SSID or password too long")?,
+        },
+        Command::Time => match backend.now() {
+            Some(now) => {
+                let synced = if backend.clock_synced() {
+                    "synced"
+                } else {
+                    "not synced"
+                };
+                writeln!(out, "{:.0} ({})", now, synced)?;
+            }
+            None => writeln!(out, "error: clock not running")?,
+        },
+        Command::NtpSync => match backend.ntp_sync().await {
+            Ok(()) => match backend.now() {
+                Some(now) => writeln!(out, "clock synced: {:.0}", now)?,
+                None => writeln!(out, "clock synced")?,
+            },
+            Err(e) => writeln!(out, "error: {}", e)?,
+        },
+        Command::Play(song) => match find_song(backend, song) {
+            Some((index, name)) => match backend.play(index) {
+                Ok(()) => writeln!(out, "playing {}", name)?,
+                Err(e) => writeln!(out, "error: {}", e)?,
+            },
+            None => writeln!(out, "error: no song '{}', see 'songs'", song)?,
+        },
+        Command::Stop => {
+            if let Err(e) = backend.stop() {
+                writeln!(out, "error: {}", e)?;
+            }
+        }
+        Command::Songs => {
+            for (index, name) in songs(backend) {
+                writeln!(out, "  {}  {}", index + 1, name)?;
+            }
+        }
+        Command::SettingsGet(None) => {
+            let current = backend.settings();
+            for key in backend.setting_keys() {
+                writeln!(out, "  {} = {}", key.name, key.show(&current))?;
+            }
+        }
+        Command::SettingsGet(Some(name)) => match find_setting(backend, name) {
+            Some(key) => writeln!(out, "{} = {}", key.name, key.show(&backend.settings()))?,
+            None => writeln!(out, "error: no setting '{}'", name)?,
+        },
+        Command::SettingsSet { key: name, value } => {
+            let Some(key) = find_setting(backend, name) else {
+                return writeln!(out, "error: no setting '{}'", name);
+            };
+            let mut changed = backend.settings();
+            match key.parse(&mut changed, value) {
+                Some(()) => {
+                    backend.set_settings(changed);
+                    writeln!(out, "{} = {}", key.name, key.show(&changed))?;
+                }
+                None => writeln!(out, "error: {} expects {}", key.name, key.expects)?,
+            }
+        }
+        Command::ScreenPush(None) => {
+            for name in backend.screens() {
+                writeln!(out, "  {}", name)?;
+            }
+        }
+        Command::ScreenPush(Some(name)) => match backend.open_screen(name) {
+            Ok(()) => {}
+            Err(OpenError::Unknown) => writeln!(out, "error: no screen '{}'", name)?,
+            Err(OpenError::Busy) => writeln!(out, "error: the UI is busy, try again")?,
+        },
+        Command::Diagnostics => write!(out, "{}", backend.diagnostics())?,
+        Command::Log(level) => {
+            let Ok(level) = level.unwrap_or("info").parse::<Level>() else {
+                return writeln!(out, "error: levels are error, warn, info, debug, trace");
+            };
+            for record in backend.log(level) {
+                writeln!(out, "{}", record)?;
+            }
+        }
+        Command::Crash => match backend.last_crash() {
+            Some(report) => write!(out, "{}", report)?,
+            None => writeln!(out, "no crash recorded")?,
+        },
+        Command::CrashClear => {
+            backend.clear_crash();
+            writeln!(out, "crash report cleared")?;
+        }
+        Command::Reboot => {
+            writeln!(out, "rebooting")?;
+            backend.reboot();
+        }
+    }
+    Ok(())
+}
+
+/// The songs the backend has, with their indices.
+fn songs<B: Backend>(backend: &B) -> impl Iterator<Item = (usize, &'static str)> + '_ {
+    (0..).map_while(|index| backend.song(index).map(|name| (index, name)))
+}
+
+/// A song by its number in `songs` or by name, ignoring case.
+fn find_song(backend: &impl Backend, song: &str) -> Option<(usize, &'static str)> {
+    match song.parse::<usize>() {
+        Ok(number) => {
+            let index = number.checked_sub(1)?;
+            backend.song(index).map(|name| (index, name))
+        }
+        Err(_) => songs(backend).find(|(_, name)| name.eq_ignore_ascii_case(song)),
+    }
+}
+
+fn find_setting<B: Backend>(backend: &B, name: &str) -> Option<&'static Key<B::Settings>> {
+    backend.setting_keys().iter().find(|key| key.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use core::future::Future;
+    use core::pin::pin;
+    use core::task::{Context, Poll, Waker};
+
+    use super::*;
+    use crate::shell::parser::parse;
+    use crate::shell::settings::Value;
+
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    struct FakeSettings {
+        volume: u8,
+    }
+
+    static KEYS: [Key<FakeSettings>; 1] = [Key::new(
+        "volume",
+        "0 to 10",
+        |s| {
+            let mut value = Value::new();
+            write!(value, "{}", s.volume).unwrap();
+            value
+        },
+        |s, v| {
+            let volume = v.parse().ok().filter(|volume| *volume <= 10)?;
+            s.volume = volume;
+            Some(())
+        },
+    )];
+
+    const SONGS: [&str; 2] = ["Startup", "Pink Panther"];
+
+    struct Fake {
+        settings: FakeSettings,
+        playing: Option<usize>,
+        joined: Option<Credentials>,
+        ui_busy: bool,
+        clock: Option<Timestamp>,
+    }
+
+    impl Fake {
+        fn new() -> Self {
+            Self {
+                settings: FakeSettings { volume: 5 },
+                playing: None,
+                joined: None,
+                ui_busy: false,
+                clock: Some(Timestamp::UNIX_EPOCH),
+            }
+        }
+    }
+
+    impl Backend for Fake {
+        type NetworkError = &'static str;
+        type NtpError = &'static str;
+        type AudioError = &'static str;
+        type Settings = FakeSettings;
+
+        async fn wifi_scan(&mut self) -> Result<Vec<AccessPoint>, &'static str> {
+            Ok(vec![AccessPoint {
+                ssid: "cafe".try_into().unwrap(),
+                channel: 6,
+                rssi: -60,
+            }])
+        }
+
+        fn wifi_connect(&mut self, credentials: Credentials) -> Result<(), &'static str> {
+            self.joined = Some(credentials);
+            Ok(())
+        }
+
+        fn wifi_ssid(&self) -> heapless::String<32> {
+            "home".try_into().unwrap()
+        }
+
+        fn wifi_address(&self) -> Option<Ipv4Addr> {
+            None
+        }
+
+        fn now(&self) -> Option<Timestamp> {
+            self.clock
+        }
+
+        fn clock_synced(&self) -> bool {
+            false
+        }
+
+        async fn ntp_sync(&mut self) -> Result<(), &'static str> {
+            Err("no network")
+        }
+
+        fn song(&self, index: usize) -> Option<&'static str> {
+            SONGS.get(index).copied()
+        }
+
+        fn play(&mut self, index: usize) -> Result<(), &'static str> {
+            self.playing = Some(index);
+            Ok(())
+        }
+
+        fn stop(&mut self) -> Result<(), &'static str> {
+            self.playing.take().map(|_| ()).ok_or("nothing playing")
+        }
+
+        fn settings(&self) -> FakeSettings {
+            self.settings
+        }
+
+        fn set_settings(&mut self, settings: FakeSettings) {
+            self.settings = settings;
+        }
+
+        fn setting_keys(&self) -> &'static [Key<FakeSettings>] {
+            &KEYS
+        }
+
+        fn screens(&self) -> &'static [&'static str] {
+            &["info", "log"]
+        }
+
+        fn open_screen(&mut self, name: &str) -> Result<(), OpenError> {
+            if !self.screens().contains(&name) {
+                Err(OpenError::Unknown)
+            } else if self.ui_busy {
+                Err(OpenError::Busy)
+            } else {
+                Ok(())
+            }
+        }
+
+        fn diagnostics(&self) -> Report {
+            Report::EMPTY
+        }
+
+        fn log(&self, _level: Level) -> Vec<LogRecord> {
+            Vec::new()
+        }
+
+        fn last_crash(&self) -> Option<CrashReport> {
+            None
+        }
+
+        fn clear_crash(&mut self) {}
+
+        fn reboot(&mut self) -> ! {
+            panic!("reboot")
+        }
+    }
+
+    /// The fake never waits, so one poll runs a command to the end.
+    fn run(backend: &mut Fake, line: &str) -> String {
+        let mut out = String::new();
+        let command = parse(line).unwrap();
+        {
+            let future = pin!(execute(command, backend, &mut out));
+            let mut cx = Context::from_waker(Waker::noop());
+            match future.poll(&mut cx) {
+                Poll::Ready(result) => result.unwrap(),
+                Poll::Pending => panic!("command did not finish"),
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn play_by_number_or_name() {
+        let mut fake = Fake::new();
+        assert_eq!(run(&mut fake, "play 2"), "playing Pink Panther\n");
+        assert_eq!(fake.playing, Some(1));
+        assert_eq!(run(&mut fake, "play startup"), "playing Startup\n");
+        assert_eq!(fake.playing, Some(0));
+    }
+
+    #[test]
+    fn play_unknown_song() {
+        let mut fake = Fake::new();
+        for line in ["play 0", "play 3", "play Imperial March"] {
+            assert!(
+                run(&mut fake, line).starts_with("error: no song"),
+                "{}",
+                line
+            );
+        }
+        assert_eq!(fake.playing, None);
+    }
+
+    #[test]
+    fn songs_are_numbered_from_one() {
+        let mut fake = Fake::new();
+        assert_eq!(run(&mut fake, "songs"), "  1  Startup\n  2  Pink Panther\n");
+    }
+
+    #[test]
+    fn backend_errors_are_printed() {
+        let mut fake = Fake::new();
+        assert_eq!(run(&mut fake, "stop"), "error: nothing playing\n");
+        assert_eq!(run(&mut fake, "ntp sync"), "error: no network\n");
+    }
+
+    #[test]
+    fn time_needs_a_running_clock() {
+        let mut fake = Fake::new();
+        assert_eq!(
+            run(&mut fake, "time"),
+            "1970-01-01T00:00:00Z (not synced)\n"
+        );
+        fake.clock = None;
+        assert_eq!(run(&mut fake, "time"), "error: clock not running\n");
+    }
+
+    #[test]
+    fn settings_by_name() {
+        let mut fake = Fake::new();
+        assert_eq!(run(&mut fake, "settings"), "  volume = 5\n");
+        assert_eq!(run(&mut fake, "settings set volume 7"), "volume = 7\n");
+        assert_eq!(fake.settings.volume, 7);
+        assert_eq!(
+            run(&mut fake, "settings set volume 11"),
+            "error: volume expects 0 to 10\n"
+        );
+        assert_eq!(
+            run(&mut fake, "settings get bass"),
+            "error: no setting 'bass'\n"
+        );
+        assert_eq!(fake.settings.volume, 7);
+    }
+
+    #[test]
+    fn screen_push_reports_why_it_failed() {
+        let mut fake = Fake::new();
+        assert_eq!(run(&mut fake, "screen push info"), "");
+        assert_eq!(
+            run(&mut fake, "screen push menu"),
+            "error: no screen 'menu'\n"
+        );
+        fake.ui_busy = true;
+        assert_eq!(
+            run(&mut fake, "screen push info"),
+            "error: the UI is busy, try again\n"
+        );
+    }
+
+    #[test]
+    fn wifi_commands() {
+        let mut fake = Fake::new();
+        assert_eq!(run(&mut fake, "wifi"), "network: home, address: none\n");
+        assert_eq!(run(&mut fake, "wifi scan"), "   -60 dBm  ch  6  cafe\n");
+        assert_eq!(
+            run(&mut fake, r#"wifi connect "Home Net" secret"#),
+            "connecting to Home Net\n"
+        );
+        let joined = fake.joined.take().unwrap();
+        assert_eq!(
+            (joined.ssid.as_str(), joined.password.as_str()),
+            ("Home Net", "secret")
+        );
+
+        let long = "x".repeat(33);
+        assert_eq!(
+            run(&mut fake, &format!("wifi connect {}", long)),
+            "error: SSID or password too long\n"
+        );
+        assert!(fake.joined.is_none());
+    }
+
+    #[test]
+    fn log_rejects_unknown_level() {
+        let mut fake = Fake::new();
+        assert_eq!(
+            run(&mut fake, "log loud"),
+            "error: levels are error, warn, info, debug, trace\n"
+        );
+        assert_eq!(run(&mut fake, "log warn"), "");
+    }
+}
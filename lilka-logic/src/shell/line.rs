@@ -0,0 +1,144 @@
+use core::str::Utf8Error;
+
+/// What a byte did to the line being typed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Edit {
+    /// The byte was added, echo it back.
+    Inserted,
+    /// The last byte was removed by a backspace.
+    Erased,
+    /// Enter was pressed, the line is ready in `LineBuffer::line`.
+    Complete,
+    /// Control byte, full buffer or backspace on an empty line.
+    Ignored,
+}
+
+/// Collects typed bytes into lines. Accepts CR, LF or CRLF line endings.
+pub struct LineBuffer<const N: usize> {
+    bytes: heapless::Vec<u8, N>,
+    // A LF right after a CR ends the same line
+    after_cr: bool,
+}
+
+impl<const N: usize> LineBuffer<N> {
+    pub const fn new() -> Self {
+        Self {
+            bytes: heapless::Vec::new(),
+            after_cr: false,
+        }
+    }
+
+    pub fn push(&mut self, byte: u8) -> Edit {
+        let after_cr = core::mem::replace(&mut self.after_cr, byte == b'\r');
+        match byte {
+            b'\n' if after_cr => Edit::Ignored,
+            b'\r' | b'\n' => Edit::Complete,
+            // Backspace and delete, terminals send either
+            0x08 | 0x7F => {
+                if self.bytes.is_empty() {
+                    return Edit::Ignored;
+                }
+                // Drop a whole UTF-8 sequence, not just its last byte
+                while let Some(byte) = self.bytes.pop() {
+                    if byte & 0xC0 != 0x80 {
+                        break;
+                    }
+                }
+                Edit::Erased
+            }
+            0x00..=0x1F => Edit::Ignored,
+            _ => match self.bytes.push(byte) {
+                Ok(()) => Edit::Inserted,
+                Err(_) => Edit::Ignored,
+            },
+        }
+    }
+
+    /// The typed line, without the line ending.
+    pub fn line(&self) -> Result<&str, Utf8Error> {
+        core::str::from_utf8(&self.bytes)
+    }
+
+    pub fn clear(&mut self) {
+        self.bytes.clear();
+    }
+}
+
+impl<const N: usize> Default for LineBuffer<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push_all<const N: usize>(line: &mut LineBuffer<N>, bytes: &[u8]) -> Vec<Edit> {
+        bytes.iter().map(|&byte| line.push(byte)).collect()
+    }
+
+    #[test]
+    fn collects_typed_bytes() {
+        let mut line = LineBuffer::<16>::new();
+        let edits = push_all(&mut line, b"ls\r");
+        assert_eq!(edits, [Edit::Inserted, Edit::Inserted, Edit::Complete]);
+        assert_eq!(line.line(), Ok("ls"));
+    }
+
+    #[test]
+    fn crlf_ends_one_line() {
+        let mut line = LineBuffer::<16>::new();
+        let edits = push_all(&mut line, b"a\r\n");
+        assert_eq!(edits, [Edit::Inserted, Edit::Complete, Edit::Ignored]);
+    }
+
+    #[test]
+    fn cr_and_lf_alone_end_lines() {
+        let mut line = LineBuffer::<16>::new();
+        assert_eq!(line.push(b'\n'), Edit::Complete);
+        assert_eq!(line.push(b'\r'), Edit::Complete);
+        // Two CRs are two lines, only a LF right after one is swallowed
+        assert_eq!(line.push(b'\r'), Edit::Complete);
+        assert_eq!(line.push(b'\n'), Edit::Ignored);
+        assert_eq!(line.push(b'\n'), Edit::Complete);
+    }
+
+    #[test]
+    fn backspace_erases_last_byte() {
+        let mut line = LineBuffer::<16>::new();
+        push_all(&mut line, b"ab");
+        assert_eq!(line.push(0x7F), Edit::Erased);
+        assert_eq!(line.push(0x08), Edit::Erased);
+        assert_eq!(line.line(), Ok(""));
+        assert_eq!(line.push(0x08), Edit::Ignored);
+    }
+
+    #[test]
+    fn backspace_erases_whole_multibyte_char() {
+        let mut line = LineBuffer::<16>::new();
+        push_all(&mut line, "aї€".as_bytes());
+        assert_eq!(line.push(0x7F), Edit::Erased);
+        assert_eq!(line.line(), Ok("aї"));
+        assert_eq!(line.push(0x7F), Edit::Erased);
+        assert_eq!(line.line(), Ok("a"));
+    }
+
+    #[test]
+    fn control_bytes_and_overflow_are_ignored() {
+        let mut line = LineBuffer::<2>::new();
+        let edits = push_all(&mut line, b"\x1babc");
+        assert_eq!(
+            edits,
+            [Edit::Ignored, Edit::Inserted, Edit::Inserted, Edit::Ignored]
+        );
+        assert_eq!(line.line(), Ok("ab"));
+    }
+
+    #[test]
+    fn cut_off_character_is_not_utf8() {
+        let mut line = LineBuffer::<2>::new();
+        push_all(&mut line, "aї".as_bytes());
+        assert!(line.line().is_err());
+    }
+}
@@ -0,0 +1,8 @@
+//! Command shell: lines are collected by `line`, turned into commands by `parser` and
+//! run by `dispatch` against a `Backend`, which the firmware implements with its
+//! services.
+
+pub mod dispatch;
+pub mod line;
+pub mod parser;
+pub mod settings;
@@ -0,0 +1,271 @@
+use core::fmt;
+
+/// A parsed console command, borrowing its arguments from the typed line.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Command<'a> {
+    Help,
+    /// Show the current network and address.
+    WifiStatus,
+    WifiScan,
+    WifiConnect {
+        ssid: &'a str,
+        password: &'a str,
+    },
+    Time,
+    NtpSync,
+    /// Play a song by name or by its number in `songs`.
+    Play(&'a str),
+    Stop,
+    Songs,
+    /// Show one setting, or all of them without a key.
+    SettingsGet(Option<&'a str>),
+    SettingsSet {
+        key: &'a str,
+        value: &'a str,
+    },
+    /// Open a screen by name, or list the names without one.
+    ScreenPush(Option<&'a str>),
+    Diagnostics,
+    /// Print the kept log records, down to the given level name.
+    Log(Option<&'a str>),
+    /// Print the report of the last panic.
+    Crash,
+    CrashClear,
+    Reboot,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ParseError<'a> {
+    /// Nothing but whitespace was typed.
+    Empty,
+    UnknownCommand(&'a str),
+    /// A required argument is missing, named by the usage text.
+    MissingArgument(&'static str),
+    UnexpectedArgument(&'a str),
+    UnterminatedQuote,
+}
+
+impl fmt::Display for ParseError<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "empty command"),
+            ParseError::UnknownCommand(word) => {
+                write!(f, "unknown command '{}', try 'help'", word)
+            }
+            ParseError::MissingArgument(name) => write!(f, "missing argument: {}", name),
+            ParseError::UnexpectedArgument(word) => write!(f, "unexpected argument '{}'", word),
+            ParseError::UnterminatedQuote => write!(f, "missing closing quote"),
+        }
+    }
+}
+
+/// Splits a line into words. Double quotes group words with spaces, e.g. an SSID.
+struct Words<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Words<'a> {
+    fn new(line: &'a str) -> Self {
+        Self { rest: line }
+    }
+
+    fn next(&mut self) -> Result<Option<&'a str>, ParseError<'a>> {
+        let rest = self.rest.trim_start();
+        if rest.is_empty() {
+            self.rest = rest;
+            return Ok(None);
+        }
+        if let Some(quoted) = rest.strip_prefix('"') {
+            let end = quoted.find('"').ok_or(ParseError::UnterminatedQuote)?;
+            self.rest = &quoted[end + 1..];
+            return Ok(Some(&quoted[..end]));
+        }
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        self.rest = &rest[end..];
+        Ok(Some(&rest[..end]))
+    }
+
+    fn required(&mut self, name: &'static str) -> Result<&'a str, ParseError<'a>> {
+        self.next()?.ok_or(ParseError::MissingArgument(name))
+    }
+
+    /// The rest of the line as one argument, so names with spaces need no quotes.
+    fn remainder(&mut self, name: &'static str) -> Result<&'a str, ParseError<'a>> {
+        let rest = self.rest.trim();
+        if rest.starts_with('"') {
+            return self.required(name);
+        }
+        self.rest = "";
+        if rest.is_empty() {
+            Err(ParseError::MissingArgument(name))
+        } else {
+            Ok(rest)
+        }
+    }
+
+    fn end(&mut self) -> Result<(), ParseError<'a>> {
+        match self.next()? {
+            Some(word) => Err(ParseError::UnexpectedArgument(word)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Parse one console line.
+pub fn parse(line: &str) -> Result<Command<'_>, ParseError<'_>> {
+    let mut words = Words::new(line);
+    let Some(name) = words.next()? else {
+        return Err(ParseError::Empty);
+    };
+
+    let command = match name {
+        "help" | "?" => Command::Help,
+        "wifi" => match words.next()? {
+            None | Some("status") => Command::WifiStatus,
+            Some("scan") => Command::WifiScan,
+            Some("connect") => {
+                let ssid = words.required("<ssid>")?;
+                // Open networks have no password
+                let password = words.next()?.unwrap_or("");
+                Command::WifiConnect { ssid, password }
+            }
+            Some(other) => return Err(ParseError::UnknownCommand(other)),
+        },
+        "time" => Command::Time,
+        "ntp" => match words.required("sync")? {
+            "sync" => Command::NtpSync,
+            other => return Err(ParseError::UnknownCommand(other)),
+        },
+        "play" => Command::Play(words.remainder("<song>")?),
+        "stop" => Command::Stop,
+        "songs" => Command::Songs,
+        "settings" => match words.next()? {
+            None | Some("get") => Command::SettingsGet(words.next()?),
+            Some("set") => Command::SettingsSet {
+                key: words.required("<key>")?,
+                value: words.required("<value>")?,
+            },
+            Some(other) => return Err(ParseError::UnknownCommand(other)),
+        },
+        "screen" => match words.required("push")? {
+            "push" => Command::ScreenPush(words.next()?),
+            other => return Err(ParseError::UnknownCommand(other)),
+        },
+        "diag" => Command::Diagnostics,
+        "log" => Command::Log(words.next()?),
+        "crash" => match words.next()? {
+            None => Command::Crash,
+            Some("clear") => Command::CrashClear,
+            Some(other) => return Err(ParseError::UnknownCommand(other)),
+        },
+        "reboot" => Command::Reboot,
+        other => return Err(ParseError::UnknownCommand(other)),
+    };
+    words.end()?;
+    Ok(command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blank_line_is_empty() {
+        assert_eq!(parse(""), Err(ParseError::Empty));
+        assert_eq!(parse("  \t "), Err(ParseError::Empty));
+    }
+
+    #[test]
+    fn plain_commands() {
+        assert_eq!(parse("help"), Ok(Command::Help));
+        assert_eq!(parse("?"), Ok(Command::Help));
+        assert_eq!(parse("  wifi  "), Ok(Command::WifiStatus));
+        assert_eq!(parse("wifi status"), Ok(Command::WifiStatus));
+        assert_eq!(parse("ntp sync"), Ok(Command::NtpSync));
+        assert_eq!(parse("crash clear"), Ok(Command::CrashClear));
+        assert_eq!(parse("log"), Ok(Command::Log(None)));
+        assert_eq!(parse("log debug"), Ok(Command::Log(Some("debug"))));
+    }
+
+    #[test]
+    fn quotes_group_words() {
+        assert_eq!(
+            parse(r#"wifi connect "Home Net" "pass word""#),
+            Ok(Command::WifiConnect {
+                ssid: "Home Net",
+                password: "pass word",
+            })
+        );
+        assert_eq!(
+            parse("wifi connect cafe"),
+            Ok(Command::WifiConnect {
+                ssid: "cafe",
+                password: "",
+            })
+        );
+        assert_eq!(
+            parse(r#"settings set theme """#),
+            Ok(Command::SettingsSet {
+                key: "theme",
+                value: "",
+            })
+        );
+    }
+
+    #[test]
+    fn unterminated_quote() {
+        assert_eq!(
+            parse(r#"wifi connect "Home Net"#),
+            Err(ParseError::UnterminatedQuote)
+        );
+    }
+
+    #[test]
+    fn remainder_takes_rest_of_line() {
+        assert_eq!(
+            parse("play  Pink Panther  "),
+            Ok(Command::Play("Pink Panther"))
+        );
+        assert_eq!(
+            parse(r#"play "Pink Panther""#),
+            Ok(Command::Play("Pink Panther"))
+        );
+        assert_eq!(
+            parse(r#"play "Pink" Panther"#),
+            Err(ParseError::UnexpectedArgument("Panther"))
+        );
+        assert_eq!(parse("play   "), Err(ParseError::MissingArgument("<song>")));
+    }
+
+    #[test]
+    fn unknown_commands() {
+        assert_eq!(
+            parse("format c:"),
+            Err(ParseError::UnknownCommand("format"))
+        );
+        assert_eq!(parse("wifi off"), Err(ParseError::UnknownCommand("off")));
+        assert_eq!(parse("ntp now"), Err(ParseError::UnknownCommand("now")));
+        assert_eq!(parse("crash now"), Err(ParseError::UnknownCommand("now")));
+    }
+
+    #[test]
+    fn argument_errors() {
+        assert_eq!(
+            parse("wifi connect"),
+            Err(ParseError::MissingArgument("<ssid>"))
+        );
+        assert_eq!(
+            parse("settings set theme"),
+            Err(ParseError::MissingArgument("<value>"))
+        );
+        assert_eq!(parse("screen"), Err(ParseError::MissingArgument("push")));
+        assert_eq!(
+            parse("reboot now"),
+            Err(ParseError::UnexpectedArgument("now"))
+        );
+        assert_eq!(
+            parse("settings get theme extra"),
+            Err(ParseError::UnexpectedArgument("extra"))
+        );
+    }
+}
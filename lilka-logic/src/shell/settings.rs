@@ -0,0 +1,39 @@
+//! Settings by name, as shown and typed on the console.
+
+use heapless::String;
+
+pub type Value = String<24>;
+
+/// A setting the console can read and change, in settings of type `S`.
+pub struct Key<S> {
+    pub name: &'static str,
+    /// What `set` accepts, for error messages.
+    pub expects: &'static str,
+    show: fn(&S) -> Value,
+    parse: fn(&mut S, &str) -> Option<()>,
+}
+
+impl<S> Key<S> {
+    pub const fn new(
+        name: &'static str,
+        expects: &'static str,
+        show: fn(&S) -> Value,
+        parse: fn(&mut S, &str) -> Option<()>,
+    ) -> Self {
+        Self {
+            name,
+            expects,
+            show,
+            parse,
+        }
+    }
+
+    pub fn show(&self, settings: &S) -> Value {
+        (self.show)(settings)
+    }
+
+    /// Apply `value` to `settings`. `None` if the value is not one the key accepts.
+    pub fn parse(&self, settings: &mut S, value: &str) -> Option<()> {
+        (self.parse)(settings, value)
+    }
+}
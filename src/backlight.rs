@@ -0,0 +1,91 @@
+use embassy_time::{Duration, Timer};
+use embedded_hal::pwm::SetDutyCycle;
+use esp_hal::{
+    ledc::{
+        channel::{self, ChannelIFace},
+        timer::{self, TimerIFace},
+        Ledc, LowSpeed,
+    },
+    peripherals::GPIO46,
+    time::Rate,
+};
+use static_cell::StaticCell;
+
+use crate::error::DisplayError;
+use crate::mk_static;
+
+// Perceived brightness to PWM duty (per mille) with gamma 2.2, sampled every 5%
+const GAMMA_TABLE: [u16; 21] = [
+    0, 1, 6, 15, 29, 47, 71, 99, 133, 173, 218, 268, 325, 388, 456, 531, 612, 699, 793, 893, 1000,
+];
+
+// Interval between brightness steps during a fade
+const FADE_STEP: Duration = Duration::from_millis(10);
+
+/// Display backlight driven by LEDC PWM on its own timer, so it doesn't clash with the buzzer.
+/// Brightness is expressed in perceived percent and gamma-corrected before reaching the PWM.
+pub struct Backlight {
+    channel: channel::Channel<'static, LowSpeed>,
+    percent: u8,
+}
+
+impl Backlight {
+    pub fn new(ledc: &Ledc<'static>, pin: GPIO46<'static>) -> Result<Self, DisplayError> {
+        let timer = mk_static!(
+            timer::Timer<'static, LowSpeed>,
+            ledc.timer::<LowSpeed>(timer::Number::Timer1)
+        );
+        timer.configure(timer::config::Config {
+            duty: timer::config::Duty::Duty10Bit,
+            clock_source: timer::LSClockSource::APBClk,
+            frequency: Rate::from_khz(20),
+        })?;
+        let timer: &'static timer::Timer<'static, LowSpeed> = timer;
+
+        let mut channel = ledc.channel(channel::Number::Channel1, pin);
+        channel.configure(channel::config::Config {
+            timer,
+            duty_pct: 100,
+            drive_mode: esp_hal::gpio::DriveMode::PushPull,
+        })?;
+
+        Ok(Backlight {
+            channel,
+            percent: 100,
+        })
+    }
+
+    pub fn percent(&self) -> u8 {
+        self.percent
+    }
+
+    /// Set the perceived brightness immediately.
+    pub fn set_percent(&mut self, percent: u8) {
+        let percent = percent.min(100);
+        let duty = gamma_duty(percent, self.channel.max_duty_cycle());
+        if self.channel.set_duty_cycle(duty).is_ok() {
+            self.percent = percent;
+        }
+    }
+
+    /// Smoothly change the brightness over `duration`.
+    pub async fn fade_to(&mut self, percent: u8, duration: Duration) {
+        let from = self.percent as i32;
+        let to = percent.min(100) as i32;
+        let steps = (duration.as_millis() / FADE_STEP.as_millis()).max(1) as i32;
+
+        for step in 1..=steps {
+            self.set_percent((from + (to - from) * step / steps) as u8);
+            Timer::after(FADE_STEP).await;
+        }
+    }
+}
+
+/// Map perceived brightness to a duty cycle, interpolating the gamma table.
+fn gamma_duty(percent: u8, max_duty: u16) -> u16 {
+    let idx = (percent / 5) as usize;
+    let lo = GAMMA_TABLE[idx] as u32;
+    let hi = GAMMA_TABLE[(idx + 1).min(GAMMA_TABLE.len() - 1)] as u32;
+    let per_mille = lo + (hi - lo) * (percent % 5) as u32 / 5;
+    (max_duty as u32 * per_mille / 1000) as u16
+}
@@ -0,0 +1,44 @@
+use esp_hal::analog::adc::{Adc, AdcCalCurve, AdcConfig, AdcPin, Attenuation};
+use esp_hal::peripherals::{ADC1, GPIO3};
+use esp_hal::Blocking;
+
+// The battery is connected to GPIO3 through a 33k/100k voltage divider
+const DIVIDER_NUMERATOR: u32 = 133;
+const DIVIDER_DENOMINATOR: u32 = 100;
+
+// Samples averaged per reading to smooth out ADC noise
+const OVERSAMPLING: u32 = 16;
+
+type BatteryPin = AdcPin<GPIO3<'static>, ADC1<'static>, AdcCalCurve<ADC1<'static>>>;
+
+pub struct Battery {
+    adc: Adc<'static, ADC1<'static>, Blocking>,
+    pin: BatteryPin,
+}
+
+impl Battery {
+    pub fn new(adc: ADC1<'static>, pin: GPIO3<'static>) -> Self {
+        let mut config = AdcConfig::new();
+        let pin =
+            config.enable_pin_with_cal::<_, AdcCalCurve<ADC1<'static>>>(pin, Attenuation::_11dB);
+        let adc = Adc::new(adc, config);
+
+        Battery { adc, pin }
+    }
+
+    /// Read the battery voltage in millivolts, averaged over several oneshot conversions.
+    pub fn read_mv(&mut self) -> u32 {
+        let mut total = 0u32;
+        for _ in 0..OVERSAMPLING {
+            // Calibrated conversions already return millivolts at the pin
+            let sample = loop {
+                if let Ok(value) = self.adc.read_oneshot(&mut self.pin) {
+                    break value;
+                }
+            };
+            total += sample as u32;
+        }
+
+        total / OVERSAMPLING * DIVIDER_NUMERATOR / DIVIDER_DENOMINATOR
+    }
+}
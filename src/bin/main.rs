@@ -2,39 +2,55 @@
 #![no_main]
 
 use alloc::boxed::Box;
-use alloc::vec::Vec;
+use core::fmt;
 
-use embassy_executor::Spawner;
+use embassy_executor::{SpawnToken, Spawner};
 use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
-use embassy_sync::channel::{Channel, Receiver, Sender};
-use embassy_time::{Duration, Timer};
+use embassy_sync::channel::{Receiver, Sender};
+use embassy_time::{with_deadline, Duration, Instant, Timer};
 
-use embedded_graphics::prelude::Dimensions;
+use embedded_graphics::prelude::{Dimensions, DrawTarget};
 use esp_backtrace as _;
 use esp_println::println;
-use log::info;
+use log::{error, info, warn};
 
+use lilka_rs::backlight::Backlight;
 use lilka_rs::board::Board;
-use lilka_rs::display::LilkaDisplay;
-use lilka_rs::input::{get_events, ButtonSet, InputPins};
-use lilka_rs::services::ntp_task;
-use lilka_rs::services::{network_task, ClockService, NetworkService};
-use lilka_rs::state::{UIEvent, UI_CHANNEL_SIZE};
-use lilka_rs::ui::screens::MenuScreen;
+use lilka_rs::display::{DisplayRotation, LilkaDisplay, PanelError, PanelInterface};
+use lilka_rs::error::DisplayError;
+use lilka_rs::input::{combo_pressed, get_events, remap, ButtonSet, InputPins};
+use lilka_rs::music::library;
+use lilka_rs::power::{low_power_mode, PowerManager, PowerState, WakePins};
+use lilka_rs::services::diagnostics::{monitor, Task};
+use lilka_rs::services::supervisor::heartbeat;
+use lilka_rs::services::{
+    audio_task, battery_task, diagnostics_task, network_task, AudioService, ClockService,
+    CrashService, DiagnosticsService, LogService, NetworkService, SettingsService, StorageService,
+};
+use lilka_rs::services::{ntp_task, settings_task, supervisor_task};
+use lilka_rs::shell::shell_task;
+use lilka_rs::state::{ButtonEvent, UIEvent, UI_CHANNEL, UI_CHANNEL_SIZE};
+use lilka_rs::ui::animation::{self, Animation};
+use lilka_rs::ui::boot::{BootSplash, BootStage};
+use lilka_rs::ui::compositor::Compositor;
+use lilka_rs::ui::navigator::Navigator;
+use lilka_rs::ui::screens::{self, CrashScreen, MenuScreen, NotificationsScreen};
+use lilka_rs::ui::screenshot;
+use lilka_rs::ui::theme;
 use lilka_rs::ui::widgets::Header;
-use lilka_rs::ui::{Screen, Transition, UIState};
+use lilka_rs::ui::{Screen, UIState};
 
 extern crate alloc;
 
-// Create a channel for UI events (buttons + ticks)
-static UI_CHANNEL: Channel<CriticalSectionRawMutex, UIEvent, UI_CHANNEL_SIZE> = Channel::new();
-
 #[esp_rtos::main]
 async fn main(spawner: Spawner) {
-    esp_println::logger::init_logger_from_env();
+    LogService::init();
+    DiagnosticsService::init();
 
     // Initialize Hardware
-    let board = Board::init();
+    // Without a display there is nothing to show an error on, the crash report
+    // keeps it for the next boot
+    let board = Board::init().unwrap_or_else(|e| halt(format_args!("Board init failed: {}", e)));
     info!("Hardware initialized!");
 
     // Group pins for the single input scanner
@@ -49,54 +65,212 @@ async fn main(spawner: Spawner) {
         d: board.d,
     };
 
+    StorageService::init(board.flash);
+    SettingsService::init();
+    CrashService::init();
+    let settings = SettingsService::get();
+    let mut display = board.display;
+    if settings.rotation != DisplayRotation::DEFAULT {
+        if let Err(e) = display.set_orientation(settings.rotation.orientation()) {
+            warn!("Failed to rotate display: {}", DisplayError::from(e));
+        }
+    }
+
+    spawn(&spawner, "diagnostics", diagnostics_task());
+    spawn(&spawner, "settings", settings_task());
+    spawn(&spawner, "audio", audio_task(board.buzzer, board.ledc));
+    spawn(&spawner, "battery", battery_task(board.battery));
+
+    // Boot splash: show subsystem progress while the startup jingle plays
+    let splash = settings
+        .boot_splash
+        .then(|| BootSplash::new(display.bounding_box()));
+    if let Some(splash) = &splash {
+        boot_draw(splash.draw_logo(&mut display));
+        boot_draw(splash.draw_stage(&mut display, BootStage::Display));
+        if settings.startup_sound {
+            if let Some(song) = library::find("Startup") {
+                if let Err(e) = AudioService::play(song) {
+                    warn!("Failed to play the startup sound: {}", e);
+                }
+            }
+        }
+    }
+    let mut skipped = splash.is_none();
+
+    spawn(&spawner, "network", network_task(board.wifi));
+    if let Some(splash) = splash.as_ref().filter(|_| !skipped) {
+        boot_draw(splash.draw_stage(&mut display, BootStage::Wifi));
+        skipped = !wait_boot_stage(&pins, Duration::from_secs(5), || {
+            NetworkService::stack().is_some_and(|s| s.is_link_up())
+        })
+        .await;
+    }
+
     ClockService::init(board.rtc);
-    spawner.spawn(network_task(board.wifi)).unwrap();
-    spawner.spawn(ntp_task("pool.ntp.org")).unwrap();
+    spawn(&spawner, "ntp", ntp_task("pool.ntp.org"));
+    spawn(&spawner, "shell", shell_task(board.console));
+    if let Some(splash) = splash.as_ref().filter(|_| !skipped) {
+        boot_draw(splash.draw_stage(&mut display, BootStage::Clock));
+        skipped = !wait_boot_stage(&pins, Duration::from_secs(3), ClockService::is_synced).await;
+    }
+
+    if let Some(splash) = splash.as_ref().filter(|_| !skipped) {
+        boot_draw(splash.draw_stage(&mut display, BootStage::Done));
+        skipped = !wait_boot_stage(&pins, Duration::from_millis(500), || false).await;
+    }
+    if skipped && splash.is_some() {
+        info!("Boot splash skipped");
+        if let Err(e) = AudioService::stop() {
+            warn!("Failed to stop the startup sound: {}", e);
+        }
+    }
+    boot_draw(display.clear(theme::current().background));
 
     // Spawn tick task for 1-second UI updates
-    spawner.spawn(tick_task(UI_CHANNEL.sender())).unwrap();
+    spawn(&spawner, "tick", tick_task(UI_CHANNEL.sender()));
 
     // Spawn Single Input System
-    spawner
-        .spawn(input_task(pins, UI_CHANNEL.sender()))
-        .unwrap();
+    spawn(&spawner, "input", input_task(pins, UI_CHANNEL.sender()));
 
     // Spawn UI System
-    spawner
-        .spawn(ui_task(board.display, UI_CHANNEL.receiver()))
-        .unwrap();
+    screens::register_menu();
+    if CrashService::is_fresh() {
+        // Queued before the UI task runs, so the report is the first thing shown
+        let open = UIEvent::Open(|bounds| Box::new(CrashScreen::new(bounds)));
+        if UI_CHANNEL.try_send(open).is_err() {
+            // Kept in flash, `crash` on the console still prints it
+            warn!("UI queue full, not showing the crash report");
+        }
+    }
+    spawn(
+        &spawner,
+        "ui",
+        ui_task(
+            display,
+            board.panel,
+            board.backlight,
+            board.wake_pins,
+            UI_CHANNEL.receiver(),
+        ),
+    );
+
+    // Last, so the critical tasks it watches are all running
+    spawn(&spawner, "supervisor", supervisor_task(board.watchdog));
 
     loop {
         Timer::after(Duration::from_secs(60)).await;
     }
 }
 
+/// Start a task the firmware can't run without.
+fn spawn<S>(spawner: &Spawner, name: &str, token: SpawnToken<S>) {
+    if let Err(e) = spawner.spawn(token) {
+        halt(format_args!("Failed to start the {} task: {}", name, e));
+    }
+}
+
+/// Stop for good on an error found while starting up. The reason goes to the log and
+/// to the crash report, which the next boot shows. Restarting on our own would only
+/// fail the same way over and over.
+fn halt(reason: fmt::Arguments) -> ! {
+    // Logs the reason as well
+    CrashService::record(reason);
+    error!("Halted, reset the device to try again");
+    loop {
+        core::hint::spin_loop();
+    }
+}
+
+/// Boot drawing goes straight to the panel. A failed draw only spoils the splash,
+/// the UI redraws everything once it starts.
+fn boot_draw(result: Result<(), PanelError>) {
+    if let Err(e) = result {
+        warn!("Failed to draw boot screen: {}", DisplayError::from(e));
+    }
+}
+
+/// Wait until `ready` returns true or `timeout` elapses.
+/// Returns false if a button was pressed to skip the boot splash.
+async fn wait_boot_stage(pins: &InputPins, timeout: Duration, ready: impl Fn() -> bool) -> bool {
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline && !ready() {
+        if pins.read_all() != ButtonSet(0) {
+            return false;
+        }
+        Timer::after(Duration::from_millis(20)).await;
+    }
+    true
+}
+
 #[embassy_executor::task]
 async fn tick_task(sender: Sender<'static, CriticalSectionRawMutex, UIEvent, UI_CHANNEL_SIZE>) {
+    monitor(Task::Tick, tick_loop(sender)).await
+}
+
+async fn tick_loop(sender: Sender<'static, CriticalSectionRawMutex, UIEvent, UI_CHANNEL_SIZE>) {
     loop {
         Timer::after(Duration::from_secs(1)).await;
         sender.send(UIEvent::Tick).await;
+        heartbeat(Task::Tick);
     }
 }
 
+// How long a C or D press waits for the other half of the screenshot combo
+const COMBO_WINDOW: Duration = Duration::from_millis(150);
+
 #[embassy_executor::task]
 async fn input_task(
     pins: InputPins,
     sender: Sender<'static, CriticalSectionRawMutex, UIEvent, UI_CHANNEL_SIZE>,
 ) {
-    let mut last_state = ButtonSet(0);
+    monitor(Task::Input, input_loop(pins, sender)).await
+}
+
+async fn input_loop(
+    pins: InputPins,
+    sender: Sender<'static, CriticalSectionRawMutex, UIEvent, UI_CHANNEL_SIZE>,
+) {
+    // Buttons held at startup (e.g. to skip the boot splash) must not trigger events
+    let mut last_state = pins.read_all();
+    // A lone C or D press, held back in case it starts the screenshot combo
+    let mut held: Option<(ButtonEvent, Instant)> = None;
 
     loop {
+        heartbeat(Task::Input);
         let current_state = pins.read_all();
 
         if current_state != last_state {
-            // Convert bitmask changes into UI events
-            for event in get_events(last_state, current_state) {
-                sender.send(UIEvent::Button(event)).await;
+            if combo_pressed(last_state, current_state, ButtonSet::SCREENSHOT_COMBO) {
+                // The combo replaces both of its presses
+                held = None;
+                sender.send(UIEvent::Screenshot).await;
+            } else {
+                // Convert bitmask changes into UI events
+                for event in get_events(last_state, current_state) {
+                    if let Some((earlier, _)) = held.take() {
+                        sender.send(UIEvent::Button(earlier)).await;
+                    }
+                    if matches!(event, ButtonEvent::C | ButtonEvent::D) {
+                        held = Some((event, Instant::now()));
+                    } else {
+                        sender.send(UIEvent::Button(event)).await;
+                    }
+                }
             }
             last_state = current_state;
         }
 
+        // Released, or the other half of the combo didn't follow in time
+        if let Some((event, since)) = held {
+            if since.elapsed() >= COMBO_WINDOW
+                || !last_state.is_pressed(ButtonSet::SCREENSHOT_COMBO)
+            {
+                held = None;
+                sender.send(UIEvent::Button(event)).await;
+            }
+        }
+
         // 20ms poll rate (50Hz) is plenty for UI and provides natural debouncing
         Timer::after(Duration::from_millis(20)).await;
     }
@@ -104,64 +278,142 @@ async fn input_task(
 
 #[embassy_executor::task]
 async fn ui_task(
-    mut display: LilkaDisplay,
+    display: LilkaDisplay,
+    panel: PanelInterface,
+    backlight: Backlight,
+    wake_pins: WakePins,
     receiver: Receiver<'static, CriticalSectionRawMutex, UIEvent, UI_CHANNEL_SIZE>,
 ) {
-    let header = Header::new(display.bounding_box());
-    let mut stack: Vec<Box<dyn Screen>> = Vec::new();
-    stack.push(Box::new(MenuScreen::new(display.bounding_box())));
+    monitor(
+        Task::Ui,
+        ui_loop(display, panel, backlight, wake_pins, receiver),
+    )
+    .await
+}
+
+async fn ui_loop(
+    display: LilkaDisplay,
+    panel: PanelInterface,
+    mut backlight: Backlight,
+    wake_pins: WakePins,
+    receiver: Receiver<'static, CriticalSectionRawMutex, UIEvent, UI_CHANNEL_SIZE>,
+) {
+    let mut canvas = Compositor::new(display, panel);
+    let mut header = Header::new(canvas.bounding_box());
+    let mut navigator = Navigator::new(Box::new(MenuScreen::new(canvas.bounding_box())));
+    apply_rotation(&mut canvas, &mut header, navigator.screens_mut());
 
     let mut state = UIState::default();
+    let mut power = PowerManager::new(wake_pins);
+    let mut theme_idx = theme::current_index();
 
-    header.draw(&mut display, &state).unwrap();
-    if let Some(screen) = stack.last_mut() {
-        screen.draw(&mut display, &state);
+    // Drawing to the compositor can't fail, it only fills the frame in memory
+    let Ok(()) = header.draw(&mut canvas, &state);
+    let Ok(()) = navigator.draw(&mut canvas, &state);
+    if let Err(e) = canvas.flush().await {
+        warn!("Failed to flush frame: {}", e);
     }
 
     loop {
-        let event = receiver.receive().await;
+        heartbeat(Task::Ui);
+        // Screens that animate ask for frames between the system ticks
+        let next_frame = match power.state() {
+            PowerState::PanelOff => None,
+            _ => navigator.next_frame(),
+        };
+        let event = match next_frame {
+            Some(at) => with_deadline(at, receiver.receive()).await.ok(),
+            None => Some(receiver.receive().await),
+        };
 
         // Update state
         state.wifi_connected = NetworkService::stack()
             .map(|s| s.is_link_up() && s.is_config_up())
             .unwrap_or(false);
 
-        // Only process screen transitions on button events
-        let transition = match event {
-            UIEvent::Button(button_event) => {
+        let animation = match event {
+            Some(UIEvent::Button(button_event)) => {
+                let button_event = remap(button_event, canvas.rotation());
                 info!("button: {:?}", button_event);
-                if let Some(screen) = stack.last_mut() {
-                    screen.update(button_event)
+                if power.on_input(canvas.display_mut(), &mut backlight).await {
+                    // The press only woke the panel up
+                    Animation::None
+                } else if matches!(button_event, ButtonEvent::D)
+                    && !navigator.has_dialog()
+                    && !NotificationsScreen::is_open()
+                {
+                    // D opens the notifications behind the bell in the header
+                    let screen = NotificationsScreen::new(canvas.bounding_box());
+                    navigator.open(Box::new(screen))
                 } else {
-                    Transition::Stay
+                    navigator.handle(button_event)
                 }
             }
-            UIEvent::Tick => Transition::Stay,
-        };
-
-        match transition {
-            Transition::Push(new_screen) => stack.push(new_screen),
-            Transition::Pop => {
-                stack.pop();
-                if let Some(screen) = stack.last_mut() {
-                    screen.ensure_redraw();
-                }
+            Some(UIEvent::Tick) => {
+                power.poll(canvas.display_mut(), &mut backlight).await;
+                navigator.tick()
+            }
+            Some(UIEvent::Screenshot) => {
+                screenshot::capture(&canvas).await;
+                Animation::None
             }
-            Transition::Replace(new_screen) => {
-                stack.pop();
-                stack.push(new_screen);
+            Some(UIEvent::Open(factory)) => {
+                // Counts as activity, so the panel wakes up to show the screen
+                power.on_input(canvas.display_mut(), &mut backlight).await;
+                navigator.open(factory(canvas.bounding_box()))
             }
-            Transition::Stay => {}
+            None => navigator.tick(),
+        };
+
+        power.sync_brightness(&mut backlight);
+        apply_rotation(&mut canvas, &mut header, navigator.screens_mut());
+
+        // Screens pick the theme up on every draw, only the header caches it
+        if theme::current_index() != theme_idx {
+            theme_idx = theme::current_index();
+            header = Header::new(canvas.bounding_box());
+            let Ok(()) = canvas.clear(theme::current().background);
+        }
+
+        // Nothing to draw while the panel is off
+        if power.state() == PowerState::PanelOff {
+            continue;
         }
 
+        canvas.begin_frame();
+
         // Header is drawn once here — no need for screens to manage it
-        header.draw(&mut display, &state).unwrap();
+        let Ok(()) = header.draw(&mut canvas, &state);
 
-        if let Some(screen) = stack.last_mut() {
-            screen.draw(&mut display, &state);
+        let Ok(()) = navigator.draw(&mut canvas, &state);
+
+        // Only the regions that actually changed are sent to the panel,
+        // animations skip straight to the new screen in low power mode
+        let animation = if low_power_mode() {
+            Animation::None
         } else {
-            stack.push(Box::new(MenuScreen::new(display.bounding_box())));
-            stack.last_mut().unwrap().draw(&mut display, &state);
-        }
+            animation
+        };
+        let content_area = theme::current().content_area(canvas.bounding_box());
+        animation::play(&mut canvas, content_area, animation).await;
+    }
+}
+
+/// Follow the rotation from settings: turn the panel and let the header and screens reflow.
+fn apply_rotation(canvas: &mut Compositor, header: &mut Header, stack: &mut [Box<dyn Screen>]) {
+    let rotation = SettingsService::get().rotation;
+    if rotation == canvas.rotation() {
+        return;
+    }
+    if let Err(e) = canvas.set_rotation(rotation) {
+        warn!("Failed to rotate display: {}", e);
+        return;
+    }
+    info!("Display rotated to {} degrees", rotation.degrees());
+
+    let bounds = canvas.bounding_box();
+    *header = Header::new(bounds);
+    for screen in stack.iter_mut() {
+        screen.resize(bounds);
     }
 }
@@ -1,27 +1,39 @@
-use core::cell::RefCell;
-use embassy_embedded_hal::shared_bus::blocking::spi::SpiDevice;
-use embassy_sync::blocking_mutex::NoopMutex;
+use embassy_sync::blocking_mutex::raw::NoopRawMutex;
+use embassy_sync::mutex::Mutex;
 use embassy_time::Delay;
 use esp_hal::clock::CpuClock;
-use esp_hal::gpio::{Input, InputConfig, Level, NoPin, Output, OutputConfig, Pull};
+use esp_hal::dma::{DmaRxBuf, DmaTxBuf};
+use esp_hal::dma_buffers;
+use esp_hal::gpio::{
+    Input, InputConfig, InputPin, Level, NoPin, Output, OutputConfig, Pin, Pull, WakeEvent,
+};
+use esp_hal::ledc::{LSGlobalClkSource, Ledc};
+use esp_hal::peripherals::TIMG1;
 use esp_hal::rtc_cntl::Rtc;
+use esp_hal::spi;
 use esp_hal::spi::master::Spi;
 use esp_hal::time::Rate;
-use esp_hal::timer::timg::TimerGroup;
-use esp_hal::{spi, Blocking};
-use mipidsi::interface::SpiInterface;
+use esp_hal::timer::timg::{TimerGroup, Wdt};
+use esp_hal::usb_serial_jtag::{UsbSerialJtag, UsbSerialJtagRx};
+use esp_hal::Async;
+use log::warn;
 use mipidsi::models::ST7789;
-use mipidsi::options::{ColorInversion, Orientation, RefreshOrder, Rotation};
+use mipidsi::options::{ColorInversion, RefreshOrder};
 use mipidsi::Builder;
 use static_cell::StaticCell;
 
-use crate::display::LilkaDisplay;
+use crate::backlight::Backlight;
+use crate::battery::Battery;
+use crate::buzzer::Buzzer;
+use crate::display::{DisplayRotation, LilkaDisplay, PanelBus, PanelInterface};
+use crate::error::DisplayError;
+use crate::power::WakePins;
 
-static SPI_BUS: StaticCell<NoopMutex<RefCell<Spi<'static, Blocking>>>> = StaticCell::new();
-static DISPLAY_BUFFER: StaticCell<[u8; 512]> = StaticCell::new();
+static PANEL_BUS: StaticCell<Mutex<NoopRawMutex, PanelBus>> = StaticCell::new();
 
 pub struct Board {
     pub display: LilkaDisplay,
+    pub panel: PanelInterface,
     pub rtc: Rtc<'static>,
     pub up: Input<'static>,
     pub down: Input<'static>,
@@ -31,26 +43,42 @@ pub struct Board {
     pub b: Input<'static>,
     pub c: Input<'static>,
     pub d: Input<'static>,
+    /// A, B, C and D again, for waking up from deep sleep.
+    pub wake_pins: WakePins,
     pub wifi: esp_hal::peripherals::WIFI<'static>,
+    pub flash: esp_hal::peripherals::FLASH<'static>,
+    pub buzzer: Buzzer,
+    pub ledc: Ledc<'static>,
+    pub battery: Battery,
+    pub backlight: Backlight,
+    /// Input of the serial console. Output goes through `esp_println`.
+    pub console: UsbSerialJtagRx<'static, Async>,
+    /// Fed by the supervisor, left disabled until then.
+    pub watchdog: Wdt<TIMG1<'static>>,
 }
 
 impl Board {
-    pub fn init() -> Self {
+    /// Bring up the hardware. Fails only if the display or its backlight can't be set
+    /// up, the device has no way to show anything without them.
+    pub fn init() -> Result<Self, DisplayError> {
         // 1. Initialize Peripherals
         let config = esp_hal::Config::default().with_cpu_clock(CpuClock::max());
         let peripherals = esp_hal::init(config);
 
-        // 2. Initialize Heap (72 KB)
+        // 2. Initialize Heap (72 KB internal + PSRAM for the UI frame buffers)
         esp_alloc::heap_allocator!(size: 72 * 1024);
+        esp_alloc::psram_allocator!(peripherals.PSRAM, esp_hal::psram);
 
         // 3. Initialize Esp RTOS
         let timg0 = TimerGroup::new(peripherals.TIMG0);
         esp_rtos::start(timg0.timer0);
 
-        // 4. Backlight
-        Output::new(peripherals.GPIO46, Level::High, OutputConfig::default()).set_high();
+        // 4. Backlight (PWM via LEDC, shared with the buzzer)
+        let mut ledc = Ledc::new(peripherals.LEDC);
+        ledc.set_global_slow_clock(LSGlobalClkSource::APBClk);
+        let backlight = Backlight::new(&ledc, peripherals.GPIO46)?;
 
-        // 5. SPI & Display
+        // 5. SPI (with DMA) & Display
         let spi_mosi = peripherals.GPIO17;
         let spi_clk = peripherals.GPIO18;
         let display_dc = Output::new(peripherals.GPIO15, Level::Low, OutputConfig::default());
@@ -61,27 +89,31 @@ impl Board {
             .with_frequency(Rate::from_mhz(40))
             .with_mode(spi::Mode::_0);
 
-        let spi = Spi::new(peripherals.SPI2, spi_config)
-            .unwrap()
+        // Frame data is streamed in chunks of the TX buffer size, nothing is ever read back
+        let (rx_buffer, rx_descriptors, tx_buffer, tx_descriptors) = dma_buffers!(32, 8192);
+        let dma_rx_buf = DmaRxBuf::new(rx_descriptors, rx_buffer)?;
+        let dma_tx_buf = DmaTxBuf::new(tx_descriptors, tx_buffer)?;
+
+        let spi = Spi::new(peripherals.SPI2, spi_config)?
             .with_cs(NoPin)
             .with_mosi(spi_mosi)
             .with_miso(NoPin)
-            .with_sck(spi_clk);
+            .with_sck(spi_clk)
+            .with_dma(peripherals.DMA_CH0)
+            .with_buffers(dma_rx_buf, dma_tx_buf)
+            .into_async();
 
-        let spi_bus = SPI_BUS.init(NoopMutex::new(RefCell::new(spi)));
-        let spi_device = SpiDevice::new(spi_bus, display_cs);
-        let display_buffer = DISPLAY_BUFFER.init([0_u8; 512]);
-        let di = SpiInterface::new(spi_device, display_dc, display_buffer);
+        let panel_bus = PANEL_BUS.init(Mutex::new(PanelBus::new(spi, display_cs, display_dc)));
+        let panel = PanelInterface::new(panel_bus);
 
-        let display = Builder::new(ST7789, di)
+        let display = Builder::new(ST7789, panel)
             .display_size(240, 280)
-            .orientation(Orientation::new().rotate(Rotation::Deg270))
+            .orientation(DisplayRotation::DEFAULT.orientation())
             .display_offset(0, 20)
             .refresh_order(RefreshOrder::default())
             .invert_colors(ColorInversion::Inverted)
             .reset_pin(display_rst)
-            .init(&mut Delay)
-            .unwrap();
+            .init(&mut Delay)?;
 
         // 6. Buttons
         let controls_config = InputConfig::default().with_pull(Pull::Up);
@@ -89,18 +121,60 @@ impl Board {
         // Rtc system
         let rtc = Rtc::new(peripherals.LPWR);
 
-        Self {
+        // 7. Battery voltage (ADC)
+        let battery = Battery::new(peripherals.ADC1, peripherals.GPIO3);
+
+        // 8. Serial console over the USB port
+        let (console, _) = UsbSerialJtag::new(peripherals.USB_DEVICE)
+            .into_async()
+            .split();
+
+        // 9. Watchdog
+        let watchdog = TimerGroup::new(peripherals.TIMG1).wdt;
+
+        // 10. Deep sleep wake-up
+        // SAFETY: the buttons only read these pins, they are reconfigured for sleep
+        // right before the chip powers down
+        let wake_pins = unsafe {
+            WakePins::new([
+                peripherals.GPIO5.clone_unchecked().degrade(),
+                peripherals.GPIO6.clone_unchecked().degrade(),
+                peripherals.GPIO10.clone_unchecked().degrade(),
+                peripherals.GPIO9.clone_unchecked().degrade(),
+            ])
+        };
+
+        Ok(Self {
             display,
+            panel,
             rtc,
-            up: Input::new(peripherals.GPIO38, controls_config),
-            down: Input::new(peripherals.GPIO41, controls_config),
-            left: Input::new(peripherals.GPIO39, controls_config),
-            right: Input::new(peripherals.GPIO40, controls_config),
-            a: Input::new(peripherals.GPIO5, controls_config),
-            b: Input::new(peripherals.GPIO6, controls_config),
-            c: Input::new(peripherals.GPIO10, controls_config),
-            d: Input::new(peripherals.GPIO9, controls_config),
+            up: button(peripherals.GPIO38, controls_config),
+            down: button(peripherals.GPIO41, controls_config),
+            left: button(peripherals.GPIO39, controls_config),
+            right: button(peripherals.GPIO40, controls_config),
+            a: button(peripherals.GPIO5, controls_config),
+            b: button(peripherals.GPIO6, controls_config),
+            c: button(peripherals.GPIO10, controls_config),
+            d: button(peripherals.GPIO9, controls_config),
+            wake_pins,
             wifi: peripherals.WIFI,
-        }
+            flash: peripherals.FLASH,
+            buzzer: Buzzer::new(peripherals.GPIO11),
+            ledc,
+            battery,
+            backlight,
+            console,
+            watchdog,
+        })
+    }
+}
+
+/// Configure a button input. Buttons are active low and can wake the chip from light sleep.
+fn button(pin: impl InputPin + 'static, config: InputConfig) -> Input<'static> {
+    let mut input = Input::new(pin, config);
+    // The button still works, it just can't wake the chip
+    if let Err(e) = input.wakeup_enable(true, WakeEvent::LowLevel) {
+        warn!("Failed to enable button wakeup: {:?}", e);
     }
+    input
 }
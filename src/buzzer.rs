@@ -9,6 +9,7 @@ use esp_hal::{
     time::Rate,
 };
 
+use crate::error::AudioError;
 use crate::music;
 
 pub struct Buzzer {
@@ -20,40 +21,60 @@ impl Buzzer {
         Buzzer { output_pin: pin }
     }
 
-    pub async fn play(&mut self, freq: f64, duration: u64, ledc: &mut Ledc<'_>) {
+    pub async fn play(
+        &mut self,
+        freq: f64,
+        duration: u64,
+        ledc: &mut Ledc<'_>,
+    ) -> Result<(), AudioError> {
         let mut lstimer0 = ledc.timer::<LowSpeed>(timer::Number::Timer0);
-        lstimer0
-            .configure(timer::config::Config {
-                duty: timer::config::Duty::Duty8Bit,
-                clock_source: timer::LSClockSource::APBClk,
-                frequency: Rate::from_hz(freq as u32),
-            })
-            .unwrap();
+        lstimer0.configure(timer::config::Config {
+            duty: timer::config::Duty::Duty8Bit,
+            clock_source: timer::LSClockSource::APBClk,
+            frequency: Rate::from_hz(freq as u32),
+        })?;
 
         let mut channel = ledc.channel(channel::Number::Channel0, self.output_pin.reborrow());
-        channel
-            .configure(channel::config::Config {
-                timer: &lstimer0,
-                duty_pct: 50,
-                drive_mode: esp_hal::gpio::DriveMode::PushPull, // pin_config: channel::config::PinConfig::PushPull,
-            })
-            .unwrap();
+        channel.configure(channel::config::Config {
+            timer: &lstimer0,
+            duty_pct: 50,
+            drive_mode: esp_hal::gpio::DriveMode::PushPull, // pin_config: channel::config::PinConfig::PushPull,
+        })?;
 
         Timer::after(Duration::from_millis(duration)).await;
-        channel.set_duty(0).unwrap();
+        channel.set_duty(0)?;
+        Ok(())
+    }
+
+    /// Play a single melody note followed by the short gap that separates it from the next one.
+    pub async fn play_note<'s>(
+        &mut self,
+        song: &music::Song<'s>,
+        note: f64,
+        duration_type: i16,
+        ledc: &mut Ledc<'_>,
+    ) -> Result<(), AudioError> {
+        let note_duration = song.calc_note_duration(duration_type) as u64;
+        let pause_duration = note_duration / 10; // 10% of note_duration
+        if note == music::notes::REST {
+            Timer::after(Duration::from_millis(note_duration)).await;
+            return Ok(());
+        }
+        self.play(note, note_duration - pause_duration, ledc)
+            .await?;
+        Timer::after(Duration::from_millis(pause_duration)).await;
+        Ok(())
     }
 
     // TODO: mutex ?
-    pub async fn play_song<'s>(&mut self, song: &music::Song<'s>, ledc: &mut Ledc<'_>) {
+    pub async fn play_song<'s>(
+        &mut self,
+        song: &music::Song<'s>,
+        ledc: &mut Ledc<'_>,
+    ) -> Result<(), AudioError> {
         for (note, duration_type) in song.melody {
-            let note_duration = song.calc_note_duration(*duration_type) as u64;
-            let pause_duration = note_duration / 10; // 10% of note_duration
-            if *note == music::notes::REST {
-                Timer::after(Duration::from_millis(note_duration)).await;
-                continue;
-            }
-            self.play(*note, note_duration - pause_duration, ledc).await;
-            Timer::after(Duration::from_millis(pause_duration)).await;
+            self.play_note(song, *note, *duration_type, ledc).await?;
         }
+        Ok(())
     }
 }
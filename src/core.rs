@@ -1,5 +1,7 @@
 mod format;
 
+pub use format::Truncate;
+
 #[macro_export]
 macro_rules! mk_static {
     ($t:ty,$val:expr) => {{
@@ -10,3 +10,17 @@ macro_rules! format {
         s
     }};
 }
+
+/// Writes into a `heapless::String` what fits and drops the rest.
+pub struct Truncate<'a, const N: usize>(pub &'a mut heapless::String<N>);
+
+impl<const N: usize> core::fmt::Write for Truncate<'_, N> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        for c in s.chars() {
+            if self.0.push(c).is_err() {
+                return Err(core::fmt::Error);
+            }
+        }
+        Ok(())
+    }
+}
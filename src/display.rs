@@ -1,22 +1,186 @@
-use mipidsi::interface::SpiInterface;
+use mipidsi::interface::Interface;
 use mipidsi::models::ST7789;
+use mipidsi::options::{Orientation, Rotation};
 use mipidsi::Display;
 
-use embassy_embedded_hal::shared_bus::blocking::spi::SpiDevice;
 use embassy_sync::blocking_mutex::raw::NoopRawMutex;
+use embassy_sync::mutex::Mutex;
 
 use esp_hal::gpio::Output;
-use esp_hal::spi::master::Spi;
-use esp_hal::Blocking;
-
-pub type LilkaDisplay = Display<
-    SpiInterface<
-        'static,
-        SpiDevice<'static, NoopRawMutex, Spi<'static, Blocking>, Output<'static>>,
-        // ExclusiveDevice<Spi<'static, Blocking>, Output<'static>, Delay>,
-        Output<'static>,
-    >,
-    ST7789,
-    Output<'static>,
-    // NoResetPin,
->;
+use esp_hal::spi::master::SpiDmaBus;
+use esp_hal::Async;
+
+// Bytes per blocking SPI write when mipidsi streams pixels through the interface
+const PIXEL_CHUNK: usize = 512;
+
+pub type LilkaDisplay = Display<PanelInterface, ST7789, Output<'static>>;
+
+/// Orientation of the panel. The 240x280 panel sits inside the 240x320 ST7789 memory;
+/// mipidsi recomputes the window offset for every rotation from `display_offset`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum DisplayRotation {
+    Deg0 = 0,
+    Deg90 = 1,
+    Deg180 = 2,
+    Deg270 = 3,
+}
+
+impl DisplayRotation {
+    /// Landscape with the D-pad on the left, the orientation the buttons are labelled for.
+    pub const DEFAULT: Self = DisplayRotation::Deg270;
+
+    pub(crate) fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(DisplayRotation::Deg0),
+            1 => Some(DisplayRotation::Deg90),
+            2 => Some(DisplayRotation::Deg180),
+            3 => Some(DisplayRotation::Deg270),
+            _ => None,
+        }
+    }
+
+    pub fn degrees(self) -> u16 {
+        self as u16 * 90
+    }
+
+    pub fn orientation(self) -> Orientation {
+        let rotation = match self {
+            DisplayRotation::Deg0 => Rotation::Deg0,
+            DisplayRotation::Deg90 => Rotation::Deg90,
+            DisplayRotation::Deg180 => Rotation::Deg180,
+            DisplayRotation::Deg270 => Rotation::Deg270,
+        };
+        Orientation::new().rotate(rotation)
+    }
+
+    /// Quarter turns clockwise relative to the default orientation.
+    pub fn quarter_turns(self) -> u8 {
+        (self as u8 + 4 - Self::DEFAULT as u8) % 4
+    }
+
+    /// The next rotation clockwise (`true`) or counter-clockwise (`false`).
+    pub fn step(self, forward: bool) -> Self {
+        let next = if forward {
+            self as u8 + 1
+        } else {
+            self as u8 + 3
+        };
+        Self::from_u8(next % 4).unwrap_or(Self::DEFAULT)
+    }
+}
+
+#[derive(Debug)]
+pub enum PanelError {
+    Spi(esp_hal::spi::Error),
+    /// The bus is in use by an async transfer.
+    Busy,
+}
+
+impl From<esp_hal::spi::Error> for PanelError {
+    fn from(e: esp_hal::spi::Error) -> Self {
+        PanelError::Spi(e)
+    }
+}
+
+/// SPI bus with DMA plus the chip select and data/command lines of the ST7789.
+pub struct PanelBus {
+    spi: SpiDmaBus<'static, Async>,
+    cs: Output<'static>,
+    dc: Output<'static>,
+}
+
+impl PanelBus {
+    pub fn new(spi: SpiDmaBus<'static, Async>, cs: Output<'static>, dc: Output<'static>) -> Self {
+        Self { spi, cs, dc }
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<(), PanelError> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        embedded_hal::spi::SpiBus::write(&mut self.spi, data)?;
+        embedded_hal::spi::SpiBus::flush(&mut self.spi)?;
+        Ok(())
+    }
+}
+
+/// Display interface shared between mipidsi, which sends commands and small blocking
+/// writes, and the compositor, which streams frame data over DMA without blocking the executor.
+#[derive(Clone, Copy)]
+pub struct PanelInterface {
+    bus: &'static Mutex<NoopRawMutex, PanelBus>,
+}
+
+impl PanelInterface {
+    pub fn new(bus: &'static Mutex<NoopRawMutex, PanelBus>) -> Self {
+        Self { bus }
+    }
+
+    /// Send pixel data for a memory write that was started with `Display::set_pixels`.
+    /// The task yields while DMA moves the data.
+    pub async fn write_pixels_async(&self, data: &[u8]) -> Result<(), PanelError> {
+        let mut bus = self.bus.lock().await;
+        bus.dc.set_high();
+        bus.cs.set_low();
+        let result = match embedded_hal_async::spi::SpiBus::write(&mut bus.spi, data).await {
+            Ok(()) => embedded_hal_async::spi::SpiBus::flush(&mut bus.spi).await,
+            Err(e) => Err(e),
+        };
+        bus.cs.set_high();
+        result.map_err(PanelError::from)
+    }
+
+    fn with_bus<R>(
+        &mut self,
+        f: impl FnOnce(&mut PanelBus) -> Result<R, PanelError>,
+    ) -> Result<R, PanelError> {
+        let mut bus = self.bus.try_lock().map_err(|_| PanelError::Busy)?;
+        bus.cs.set_low();
+        let result = f(&mut bus);
+        bus.cs.set_high();
+        result
+    }
+}
+
+impl Interface for PanelInterface {
+    type Word = u8;
+    type Error = PanelError;
+
+    fn send_command(&mut self, command: u8, args: &[u8]) -> Result<(), Self::Error> {
+        self.with_bus(|bus| {
+            bus.dc.set_low();
+            bus.write(&[command])?;
+            bus.dc.set_high();
+            bus.write(args)
+        })
+    }
+
+    fn send_pixels<const N: usize>(
+        &mut self,
+        pixels: impl IntoIterator<Item = [Self::Word; N]>,
+    ) -> Result<(), Self::Error> {
+        self.with_bus(|bus| {
+            bus.dc.set_high();
+            let mut buffer = [0u8; PIXEL_CHUNK];
+            let mut len = 0;
+            for pixel in pixels {
+                if len + N > buffer.len() {
+                    bus.write(&buffer[..len])?;
+                    len = 0;
+                }
+                buffer[len..len + N].copy_from_slice(&pixel);
+                len += N;
+            }
+            bus.write(&buffer[..len])
+        })
+    }
+
+    fn send_repeated_pixel<const N: usize>(
+        &mut self,
+        pixel: [Self::Word; N],
+        count: u32,
+    ) -> Result<(), Self::Error> {
+        self.send_pixels((0..count).map(|_| pixel))
+    }
+}
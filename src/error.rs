@@ -0,0 +1,295 @@
+//! Errors of the subsystems, one enum each.
+//!
+//! `Display` gives the text for the log and the console. Errors the user can do something
+//! about also have `msg`, a short translated reason for notifications. With the `defmt`
+//! feature they implement `defmt::Format` too, with the same text.
+
+use core::convert::Infallible;
+use core::fmt;
+
+use embassy_net::dns;
+use embassy_net::udp::BindError;
+use esp_hal::dma::DmaBufError;
+use esp_hal::ledc::{channel, timer};
+use esp_hal::spi::master::ConfigError;
+use esp_radio::wifi::WifiError;
+use esp_radio::InitializationError;
+use esp_storage::FlashStorageError;
+use mipidsi::builder::{ConfigurationError, InitError};
+
+use crate::display::PanelError;
+use crate::i18n::Msg;
+
+/// Setting up or talking to the display panel.
+#[derive(Debug)]
+pub enum DisplayError {
+    /// A transfer to the panel failed.
+    Panel(PanelError),
+    /// The SPI bus rejected its configuration.
+    Spi(ConfigError),
+    /// The DMA buffers for the SPI bus could not be set up.
+    Dma(DmaBufError),
+    /// Size or offset given to the panel driver don't fit the controller.
+    Config(ConfigurationError),
+    /// The LEDC timer can't make the backlight PWM.
+    BacklightTimer(timer::Error),
+    BacklightChannel(channel::Error),
+}
+
+impl fmt::Display for DisplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DisplayError::Panel(PanelError::Spi(e)) => write!(f, "panel transfer failed: {:?}", e),
+            DisplayError::Panel(PanelError::Busy) => write!(f, "panel bus busy"),
+            DisplayError::Spi(e) => write!(f, "SPI setup failed: {:?}", e),
+            DisplayError::Dma(e) => write!(f, "DMA setup failed: {:?}", e),
+            DisplayError::Config(e) => write!(f, "invalid panel configuration: {:?}", e),
+            DisplayError::BacklightTimer(e) => write!(f, "backlight timer setup failed: {:?}", e),
+            DisplayError::BacklightChannel(e) => {
+                write!(f, "backlight channel setup failed: {:?}", e)
+            }
+        }
+    }
+}
+
+impl From<PanelError> for DisplayError {
+    fn from(e: PanelError) -> Self {
+        DisplayError::Panel(e)
+    }
+}
+
+impl From<InitError<PanelError, Infallible>> for DisplayError {
+    fn from(e: InitError<PanelError, Infallible>) -> Self {
+        match e {
+            InitError::Interface(e) => DisplayError::Panel(e),
+            InitError::ResetPin(e) => match e {},
+            InitError::InvalidConfiguration(e) => DisplayError::Config(e),
+        }
+    }
+}
+
+impl From<ConfigError> for DisplayError {
+    fn from(e: ConfigError) -> Self {
+        DisplayError::Spi(e)
+    }
+}
+
+impl From<DmaBufError> for DisplayError {
+    fn from(e: DmaBufError) -> Self {
+        DisplayError::Dma(e)
+    }
+}
+
+impl From<timer::Error> for DisplayError {
+    fn from(e: timer::Error) -> Self {
+        DisplayError::BacklightTimer(e)
+    }
+}
+
+impl From<channel::Error> for DisplayError {
+    fn from(e: channel::Error) -> Self {
+        DisplayError::BacklightChannel(e)
+    }
+}
+
+/// Wi-Fi and the network service.
+#[derive(Debug)]
+pub enum NetworkError {
+    /// The radio could not be brought up, the device stays offline.
+    Radio(InitializationError),
+    Wifi(WifiError),
+    /// The service is still busy with an earlier request.
+    Busy,
+    /// No answer from the service in time, e.g. the radio is still starting.
+    Timeout,
+}
+
+impl NetworkError {
+    pub fn msg(&self) -> Msg {
+        match self {
+            NetworkError::Radio(_) => Msg::ErrorRadio,
+            NetworkError::Wifi(_) => Msg::ErrorWifi,
+            NetworkError::Busy => Msg::ErrorBusy,
+            NetworkError::Timeout => Msg::ErrorTimeout,
+        }
+    }
+}
+
+impl fmt::Display for NetworkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NetworkError::Radio(e) => write!(f, "radio init failed: {:?}", e),
+            NetworkError::Wifi(e) => write!(f, "Wi-Fi error: {:?}", e),
+            NetworkError::Busy => write!(f, "network busy, try again"),
+            NetworkError::Timeout => write!(f, "network timed out"),
+        }
+    }
+}
+
+impl From<InitializationError> for NetworkError {
+    fn from(e: InitializationError) -> Self {
+        NetworkError::Radio(e)
+    }
+}
+
+impl From<WifiError> for NetworkError {
+    fn from(e: WifiError) -> Self {
+        NetworkError::Wifi(e)
+    }
+}
+
+/// Syncing the clock over SNTP.
+#[derive(Debug)]
+pub enum NtpError {
+    Dns(dns::Error),
+    /// The server name resolved to no address.
+    NoAddress,
+    Socket(BindError),
+    /// The server didn't answer or the answer was not valid.
+    Request(sntpc::Error),
+    /// A sync asked for with `ntp::sync_now` didn't finish in time.
+    Timeout,
+    /// The time arrived but there is no clock to set it on.
+    NoClock,
+}
+
+impl NtpError {
+    pub fn msg(&self) -> Msg {
+        match self {
+            NtpError::Dns(_) | NtpError::NoAddress => Msg::ErrorServerNotFound,
+            NtpError::Socket(_) => Msg::ErrorNetwork,
+            NtpError::Request(_) => Msg::ErrorNoAnswer,
+            NtpError::Timeout => Msg::ErrorTimeout,
+            NtpError::NoClock => Msg::ErrorNoClock,
+        }
+    }
+}
+
+impl fmt::Display for NtpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NtpError::Dns(e) => write!(f, "DNS lookup failed: {:?}", e),
+            NtpError::NoAddress => write!(f, "no address for the server"),
+            NtpError::Socket(e) => write!(f, "socket bind failed: {:?}", e),
+            NtpError::Request(e) => write!(f, "SNTP request failed: {:?}", e),
+            NtpError::Timeout => write!(f, "sync timed out"),
+            NtpError::NoClock => write!(f, "clock not running"),
+        }
+    }
+}
+
+impl From<dns::Error> for NtpError {
+    fn from(e: dns::Error) -> Self {
+        NtpError::Dns(e)
+    }
+}
+
+impl From<BindError> for NtpError {
+    fn from(e: BindError) -> Self {
+        NtpError::Socket(e)
+    }
+}
+
+impl From<sntpc::Error> for NtpError {
+    fn from(e: sntpc::Error) -> Self {
+        NtpError::Request(e)
+    }
+}
+
+/// Reading and writing the flash records.
+#[derive(Debug)]
+pub enum StorageError {
+    /// `StorageService::init` has not run yet.
+    NotInitialized,
+    Flash(FlashStorageError),
+}
+
+impl StorageError {
+    pub fn msg(&self) -> Msg {
+        Msg::ErrorStorage
+    }
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::NotInitialized => write!(f, "storage not initialized"),
+            StorageError::Flash(e) => write!(f, "flash access failed: {:?}", e),
+        }
+    }
+}
+
+impl From<FlashStorageError> for StorageError {
+    fn from(e: FlashStorageError) -> Self {
+        StorageError::Flash(e)
+    }
+}
+
+/// The music player and the buzzer.
+#[derive(Debug)]
+pub enum AudioError {
+    /// Too many commands waiting for the player.
+    QueueFull,
+    /// No song at this index in `music::LIBRARY`.
+    UnknownSong(usize),
+    /// The LEDC timer can't make the note's frequency.
+    Timer(timer::Error),
+    Channel(channel::Error),
+}
+
+impl AudioError {
+    pub fn msg(&self) -> Msg {
+        match self {
+            AudioError::QueueFull => Msg::ErrorBusy,
+            AudioError::UnknownSong(_) => Msg::ErrorUnknownSong,
+            AudioError::Timer(_) | AudioError::Channel(_) => Msg::ErrorBuzzer,
+        }
+    }
+}
+
+impl fmt::Display for AudioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AudioError::QueueFull => write!(f, "player busy, try again"),
+            AudioError::UnknownSong(index) => write!(f, "no song at index {}", index),
+            AudioError::Timer(e) => write!(f, "buzzer timer setup failed: {:?}", e),
+            AudioError::Channel(e) => write!(f, "buzzer channel setup failed: {:?}", e),
+        }
+    }
+}
+
+impl From<timer::Error> for AudioError {
+    fn from(e: timer::Error) -> Self {
+        AudioError::Timer(e)
+    }
+}
+
+impl From<channel::Error> for AudioError {
+    fn from(e: channel::Error) -> Self {
+        AudioError::Channel(e)
+    }
+}
+
+/// The wrapped esp-hal and driver errors only have `Debug`, so the text is formatted on
+/// the device rather than deferred to the host.
+#[cfg(feature = "defmt")]
+macro_rules! format_as_display {
+    ($($error:ty),*) => {
+        $(
+            impl defmt::Format for $error {
+                fn format(&self, f: defmt::Formatter) {
+                    defmt::write!(f, "{}", defmt::Display2Format(self))
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "defmt")]
+format_as_display!(
+    DisplayError,
+    NetworkError,
+    NtpError,
+    StorageError,
+    AudioError
+);
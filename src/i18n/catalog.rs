@@ -0,0 +1,214 @@
+use super::{Lang, PluralCategory};
+
+/// Declares `Msg` with one variant per entry. Each entry must list every language.
+macro_rules! catalog {
+    ($($key:ident { en: $en:literal, uk: $uk:literal $(,)? })*) => {
+        /// Keys of all translated UI strings.
+        #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+        pub enum Msg {
+            $($key,)*
+        }
+
+        impl Msg {
+            pub fn text(self, lang: Lang) -> &'static str {
+                match self {
+                    $(Msg::$key => match lang {
+                        Lang::En => $en,
+                        Lang::Uk => $uk,
+                    },)*
+                }
+            }
+        }
+    };
+}
+
+/// Declares `Plural` with the forms of each word: `[one, other]` in English and
+/// `[one, few, many]` in Ukrainian.
+macro_rules! plurals {
+    ($($key:ident {
+        en: [$en_one:literal, $en_other:literal],
+        uk: [$uk_one:literal, $uk_few:literal, $uk_many:literal] $(,)?
+    })*) => {
+        /// Words that change form with the number they count.
+        #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+        pub enum Plural {
+            $($key,)*
+        }
+
+        impl Plural {
+            pub fn form(self, lang: Lang, category: PluralCategory) -> &'static str {
+                match self {
+                    $(Plural::$key => match (lang, category) {
+                        (Lang::En, PluralCategory::One) => $en_one,
+                        (Lang::En, _) => $en_other,
+                        (Lang::Uk, PluralCategory::One) => $uk_one,
+                        (Lang::Uk, PluralCategory::Few) => $uk_few,
+                        (Lang::Uk, _) => $uk_many,
+                    },)*
+                }
+            }
+        }
+    };
+}
+
+catalog! {
+    // Main menu
+    MenuInfo { en: "Info", uk: "Інформація" }
+    MenuNetwork { en: "Network", uk: "Мережа" }
+    MenuMusic { en: "Music", uk: "Музика" }
+    MenuSettings { en: "Settings", uk: "Налаштування" }
+    MenuDiagnostics { en: "Diagnostics", uk: "Діагностика" }
+    MenuLog { en: "Log", uk: "Журнал" }
+    MenuDisplay { en: "Display", uk: "Екран" }
+    MenuAllSettings { en: "All settings", uk: "Усі налаштування" }
+
+    // Dialog buttons
+    ButtonOk { en: "OK", uk: "Гаразд" }
+    ButtonCancel { en: "Cancel", uk: "Скасувати" }
+    ButtonYes { en: "Yes", uk: "Так" }
+    ButtonNo { en: "No", uk: "Ні" }
+
+    // Boot splash
+    BootDisplay { en: "Display", uk: "Дисплей" }
+    BootWifi { en: "Wi-Fi", uk: "Wi-Fi" }
+    BootClock { en: "Clock", uk: "Годинник" }
+    BootReady { en: "Ready", uk: "Готово" }
+
+    // Screens
+    InfoAbout {
+        en: "Lilka is an open game console made in Ukraine",
+        uk: "Лілка — відкрита ігрова консоль, розроблена в Україні",
+    }
+    InfoDevice { en: "Device", uk: "Пристрій" }
+    InfoMemory { en: "Memory", uk: "Пам'ять" }
+    InfoTime { en: "Time", uk: "Час" }
+    InfoDate { en: "Date", uk: "Дата" }
+    InfoAboutTitle { en: "About", uk: "Про Лілку" }
+    InfoVersion { en: "Version", uk: "Версія" }
+    InfoBuild { en: "Build", uk: "Збірка" }
+    InfoChip { en: "Chip", uk: "Чип" }
+    InfoMac { en: "MAC", uk: "MAC" }
+    InfoReset { en: "Last reset", uk: "Перезапуск" }
+    InfoUptime { en: "Uptime", uk: "Час роботи" }
+    InfoHeapUsed { en: "Heap used", uk: "Зайнято" }
+    InfoHeapFree { en: "Heap free", uk: "Вільно" }
+    InfoGateway { en: "Gateway", uk: "Шлюз" }
+    InfoDns { en: "DNS", uk: "DNS" }
+    InfoTimeSynced { en: "Synchronized", uk: "Синхронізовано" }
+    ResetPowerOn { en: "Power on", uk: "Увімкнення" }
+    ResetSoftware { en: "Software", uk: "Програмний" }
+    ResetDeepSleep { en: "Deep sleep", uk: "Глибокий сон" }
+    ResetWatchdog { en: "Watchdog", uk: "Сторожовий таймер" }
+    ResetBrownOut { en: "Brown-out", uk: "Просідання живлення" }
+    ResetUsb { en: "USB", uk: "USB" }
+    ResetUnknown { en: "Unknown", uk: "Невідомо" }
+    UnitKilobytes { en: "KB", uk: "КБ" }
+    UnitMilliseconds { en: "ms", uk: "мс" }
+    WifiTitle { en: "Wi-Fi", uk: "Wi-Fi" }
+    WifiNetwork { en: "Network", uk: "Мережа" }
+    WifiStatus { en: "Status", uk: "Стан" }
+    WifiConnected { en: "Connected", uk: "Підключено" }
+    WifiDisconnected { en: "Not connected", uk: "Не підключено" }
+    WifiAddress { en: "Address", uk: "Адреса" }
+    MusicPlaying { en: "Playing", uk: "Грає" }
+    MusicPaused { en: "Paused", uk: "Пауза" }
+    MusicStopped { en: "Stopped", uk: "Зупинено" }
+
+    // Settings
+    SettingBootSplash { en: "Boot splash", uk: "Заставка" }
+    SettingBootSound { en: "Boot sound", uk: "Звук запуску" }
+    SettingDim { en: "Dim after", uk: "Затемнення" }
+    SettingScreenOff { en: "Screen off", uk: "Вимк. екрана" }
+    SettingSleep { en: "Sleep", uk: "Сон" }
+    SettingBrightness { en: "Brightness", uk: "Яскравість" }
+    SettingRotation { en: "Rotation", uk: "Поворот" }
+    SettingTheme { en: "Theme", uk: "Тема" }
+    SettingLanguage { en: "Language", uk: "Мова" }
+    SettingPowerSaving { en: "Power saving", uk: "Енергозбереження" }
+    ValueOn { en: "On", uk: "Увімк." }
+    ValueOff { en: "Off", uk: "Вимк." }
+    ValueNever { en: "Never", uk: "Ніколи" }
+    ValueYes { en: "Yes", uk: "Так" }
+    ValueNo { en: "No", uk: "Ні" }
+    SleepLight { en: "Light", uk: "Легкий" }
+    SleepDeep { en: "Deep", uk: "Глибокий" }
+
+    // Theme names
+    ThemeMint { en: "Mint", uk: "М'ята" }
+    ThemeContrast { en: "Contrast", uk: "Контраст" }
+    ThemeLight { en: "Light", uk: "Світла" }
+    ThemeAmber { en: "Amber", uk: "Бурштин" }
+
+    // Notifications
+    NotificationsTitle { en: "Notifications", uk: "Сповіщення" }
+    NotificationsEmpty { en: "No notifications", uk: "Немає сповіщень" }
+    AgeNow { en: "now", uk: "щойно" }
+    AgeMinutes { en: "min", uk: "хв" }
+    AgeHours { en: "h", uk: "год" }
+    NotifyWifiConnected { en: "Wi-Fi connected", uk: "Wi-Fi підключено" }
+    NotifyWifiFailed { en: "Wi-Fi connection failed", uk: "Не вдалося підключити Wi-Fi" }
+    NotifyClockSynced { en: "Clock synchronized", uk: "Годинник синхронізовано" }
+    NotifyClockSyncFailed { en: "Time sync failed", uk: "Не вдалося синхронізувати час" }
+    NotifyBatteryLow { en: "Battery low", uk: "Низький заряд батареї" }
+    NotifyBatteryCritical { en: "Battery critical", uk: "Критичний заряд батареї" }
+    NotifySettingsNotSaved { en: "Settings not saved", uk: "Налаштування не збережено" }
+    NotifyPlaybackFailed { en: "Playback failed", uk: "Не вдалося відтворити" }
+
+    // Settings reset
+    SettingsReset { en: "Reset settings", uk: "Скидання налаштувань" }
+    SettingsResetQuestion {
+        en: "Restore all settings to their defaults?",
+        uk: "Повернути всі налаштування до типових?",
+    }
+    SettingsResetDone { en: "Settings restored", uk: "Налаштування відновлено" }
+
+    // Diagnostics
+    DiagLargestBlock { en: "Largest block (SRAM)", uk: "Найбільший блок (SRAM)" }
+    DiagFragmentation { en: "Fragmentation", uk: "Фрагментація" }
+    DiagStack { en: "Main stack", uk: "Основний стек" }
+    DiagLatency { en: "Timer latency", uk: "Затримка таймера" }
+    DiagTasks { en: "Tasks", uk: "Задачі" }
+    DiagTasksLegend { en: "load, max poll, polls", uk: "навант., макс., опитувань" }
+    DiagQueues { en: "Queues", uk: "Черги" }
+    DiagQueuesLegend { en: "now/size, peak", uk: "зараз/розмір, пік" }
+
+    // Log viewer
+    LogEmpty { en: "No log records", uk: "Записів немає" }
+    LogFilterAll { en: "All", uk: "Усі" }
+    LogFilterInfo { en: "Info", uk: "Інфо" }
+    LogFilterWarnings { en: "Warnings", uk: "Попередження" }
+    LogFilterErrors { en: "Errors", uk: "Помилки" }
+
+    // Last crash
+    CrashTitle { en: "Last crash", uk: "Останній збій" }
+    CrashNone { en: "No crash recorded", uk: "Збоїв не було" }
+    CrashMessage { en: "Message", uk: "Повідомлення" }
+    CrashLocation { en: "Location", uk: "Місце" }
+    CrashUptime { en: "Uptime", uk: "Час роботи" }
+    CrashBacktrace { en: "Backtrace", uk: "Стек викликів" }
+    CrashClearQuestion { en: "Forget this crash report?", uk: "Забути цей звіт про збій?" }
+
+    // Error reasons, shown after what failed
+    ErrorRadio { en: "radio not available", uk: "радіо недоступне" }
+    ErrorWifi { en: "Wi-Fi error", uk: "помилка Wi-Fi" }
+    ErrorBusy { en: "busy, try again", uk: "зайнято, спробуйте ще" }
+    ErrorTimeout { en: "timed out", uk: "час очікування минув" }
+    ErrorServerNotFound { en: "server not found", uk: "сервер не знайдено" }
+    ErrorNetwork { en: "network error", uk: "помилка мережі" }
+    ErrorNoAnswer { en: "no answer", uk: "немає відповіді" }
+    ErrorNoClock { en: "clock not running", uk: "годинник не працює" }
+    ErrorStorage { en: "flash error", uk: "помилка пам'яті" }
+    ErrorUnknownSong { en: "no such song", uk: "немає такої пісні" }
+    ErrorBuzzer { en: "buzzer error", uk: "помилка зумера" }
+}
+
+plurals! {
+    Seconds {
+        en: ["second", "seconds"],
+        uk: ["секунда", "секунди", "секунд"],
+    }
+    Minutes {
+        en: ["minute", "minutes"],
+        uk: ["хвилина", "хвилини", "хвилин"],
+    }
+}
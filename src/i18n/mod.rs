@@ -0,0 +1,89 @@
+//! UI translations.
+//!
+//! Every string shown on screen is a `Msg` key from `catalog.rs`. The catalog macro only
+//! accepts entries with all languages filled in, so a missing translation or a misspelled
+//! key is a compile error rather than a blank label at runtime.
+
+mod catalog;
+
+pub use catalog::{Msg, Plural};
+
+use crate::services::SettingsService;
+
+/// UI language, stored in settings.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Lang {
+    En = 0,
+    Uk = 1,
+}
+
+impl Lang {
+    pub const ALL: [Lang; 2] = [Lang::En, Lang::Uk];
+
+    pub(crate) fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Lang::En),
+            1 => Some(Lang::Uk),
+            _ => None,
+        }
+    }
+
+    /// Name of the language in the language itself, so it can be found in any UI language.
+    pub fn native_name(&self) -> &'static str {
+        match self {
+            Lang::En => "English",
+            Lang::Uk => "Українська",
+        }
+    }
+
+    /// The next language in `ALL`, wrapping around.
+    pub fn step(&self, forward: bool) -> Self {
+        let count = Self::ALL.len();
+        let idx = *self as usize;
+        let next = if forward {
+            (idx + 1) % count
+        } else {
+            (idx + count - 1) % count
+        };
+        Self::ALL[next]
+    }
+
+    /// CLDR plural category of `n` in this language.
+    pub fn plural_category(&self, n: u32) -> PluralCategory {
+        match self {
+            Lang::En if n == 1 => PluralCategory::One,
+            Lang::En => PluralCategory::Other,
+            Lang::Uk => match (n % 10, n % 100) {
+                (1, rem) if rem != 11 => PluralCategory::One,
+                (2..=4, rem) if !(12..=14).contains(&rem) => PluralCategory::Few,
+                _ => PluralCategory::Many,
+            },
+        }
+    }
+}
+
+/// Plural forms a word can take, English only uses `One` and `Other`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PluralCategory {
+    One,
+    Few,
+    Many,
+    Other,
+}
+
+/// The language selected in settings.
+pub fn current() -> Lang {
+    SettingsService::get().language
+}
+
+/// Translate `msg` into the current language.
+pub fn tr(msg: Msg) -> &'static str {
+    msg.text(current())
+}
+
+/// The form of `word` that goes with the number `n` in the current language.
+pub fn plural(word: Plural, n: u32) -> &'static str {
+    let lang = current();
+    word.form(lang, lang.plural_category(n))
+}
@@ -1,3 +1,4 @@
+use crate::display::DisplayRotation;
 use crate::state::ButtonEvent;
 use esp_hal::gpio::Input;
 
@@ -14,11 +15,19 @@ impl ButtonSet {
     pub const C: u16 = 1 << 6;
     pub const D: u16 = 1 << 7;
 
+    /// Hold C and D together to capture a screenshot.
+    pub const SCREENSHOT_COMBO: u16 = Self::C | Self::D;
+
     pub fn is_pressed(&self, mask: u16) -> bool {
         (self.0 & mask) != 0
     }
 }
 
+/// True when the last button of `combo` went down, with the rest already held.
+pub fn combo_pressed(old: ButtonSet, new: ButtonSet, combo: u16) -> bool {
+    (new.0 & combo) == combo && (old.0 & combo) != combo
+}
+
 pub struct InputPins {
     pub up: Input<'static>,
     pub down: Input<'static>,
@@ -103,3 +112,24 @@ pub fn get_events(old: ButtonSet, new: ButtonSet) -> impl Iterator<Item = Button
 
     events.into_iter().flatten()
 }
+
+/// Map a D-pad press to the direction it points to on the rotated screen,
+/// so that Up always moves towards the top of what is displayed.
+pub fn remap(event: ButtonEvent, rotation: DisplayRotation) -> ButtonEvent {
+    // Directions in clockwise order
+    const DIRECTIONS: [ButtonEvent; 4] = [
+        ButtonEvent::Up,
+        ButtonEvent::Right,
+        ButtonEvent::Down,
+        ButtonEvent::Left,
+    ];
+    let idx = match event {
+        ButtonEvent::Up => 0,
+        ButtonEvent::Right => 1,
+        ButtonEvent::Down => 2,
+        ButtonEvent::Left => 3,
+        _ => return event,
+    };
+    // Content turned clockwise means the buttons point counter-clockwise relative to it
+    DIRECTIONS[(idx + 4 - rotation.quarter_turns() as usize) % 4]
+}
@@ -2,13 +2,18 @@
 
 extern crate alloc;
 
+pub mod backlight;
+pub mod battery;
 pub mod board;
 pub mod buzzer;
 pub mod core;
 pub mod display;
+pub mod error;
+pub mod i18n;
 pub mod input;
-pub mod menu;
 pub mod music;
+pub mod power;
 pub mod services;
+pub mod shell;
 pub mod state;
 pub mod ui;
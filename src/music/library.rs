@@ -0,0 +1,38 @@
+use crate::music::songs::{pink_panther, startup};
+use crate::music::Song;
+
+/// A song registered in the library, with the metadata the UI needs to list it.
+pub struct SongEntry {
+    pub name: &'static str,
+    pub tempo: u16,
+    pub melody: &'static [(f64, i16)],
+}
+
+impl SongEntry {
+    pub fn song(&self) -> Song<'static> {
+        Song::new(self.tempo, self.melody)
+    }
+
+    pub fn duration_ms(&self) -> u32 {
+        self.song().duration_ms()
+    }
+}
+
+// Register new songs here to make them available in the music player
+pub static LIBRARY: &[SongEntry] = &[
+    SongEntry {
+        name: "Startup",
+        tempo: startup::TEMPO,
+        melody: &startup::MELODY,
+    },
+    SongEntry {
+        name: "Pink Panther",
+        tempo: pink_panther::TEMPO,
+        melody: &pink_panther::MELODY,
+    },
+];
+
+/// Look up a song index by its name.
+pub fn find(name: &str) -> Option<usize> {
+    LIBRARY.iter().position(|entry| entry.name == name)
+}
@@ -1,5 +1,7 @@
+pub mod library;
 pub mod notes;
 pub mod song;
 pub mod songs;
 
+pub use library::{SongEntry, LIBRARY};
 pub use song::Song;
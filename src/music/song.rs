@@ -17,4 +17,12 @@ impl<'a> Song<'a> {
             (duration as f64 * 1.5) as u32
         }
     }
+
+    /// Total playback time of the melody in milliseconds.
+    pub fn duration_ms(&self) -> u32 {
+        self.melody
+            .iter()
+            .map(|(_, divider)| self.calc_note_duration(*divider))
+            .sum()
+    }
 }
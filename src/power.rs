@@ -0,0 +1,177 @@
+use embassy_time::{Delay, Duration, Instant};
+use esp_hal::gpio::{AnyPin, RtcPin};
+use esp_hal::rtc_cntl::sleep::{GpioWakeupSource, RtcioWakeupSource, WakeupLevel};
+use log::{info, warn};
+
+use crate::backlight::Backlight;
+use crate::display::LilkaDisplay;
+use crate::error::DisplayError;
+use crate::error::DisplayError;
+use crate::services::{BatteryService, ClockService, SettingsService, SleepMode};
+
+// Backlight level while dimmed, in percent
+const DIM_PERCENT: u8 = 10;
+
+const DIM_FADE: Duration = Duration::from_millis(400);
+const WAKE_FADE: Duration = Duration::from_millis(150);
+
+/// Whether to skip work that only makes the UI smoother: on when enabled in settings
+/// and while the battery is low.
+pub fn low_power_mode() -> bool {
+    SettingsService::get().power_saving || BatteryService::status().is_some_and(|s| s.is_low())
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PowerState {
+    Active,
+    Dimmed,
+    PanelOff,
+}
+
+/// The buttons that wake the chip from deep sleep: A, B, C and D. Only RTC IO pins
+/// (GPIO0-21) are watched in deep sleep, the D-pad pins are not among them.
+pub struct WakePins {
+    pins: [AnyPin<'static>; 4],
+}
+
+impl WakePins {
+    /// # Safety
+    ///
+    /// The pins are also read as buttons, so they must be inputs and may only be
+    /// reconfigured on the way into deep sleep, which `PowerManager` does.
+    pub unsafe fn new(pins: [AnyPin<'static>; 4]) -> Self {
+        Self { pins }
+    }
+}
+
+/// Dims and switches off the display after inactivity and optionally puts the chip to sleep.
+/// Driven by the UI task, which owns the display and sees every button press.
+pub struct PowerManager {
+    state: PowerState,
+    last_activity: Instant,
+    wake_pins: WakePins,
+}
+
+impl PowerManager {
+    pub fn new(wake_pins: WakePins) -> Self {
+        Self {
+            state: PowerState::Active,
+            last_activity: Instant::now(),
+            wake_pins,
+        }
+    }
+
+    pub fn state(&self) -> PowerState {
+        self.state
+    }
+
+    /// Register a button press. Returns true if the press only woke the panel
+    /// and should not be passed on to the UI.
+    pub async fn on_input(
+        &mut self,
+        display: &mut LilkaDisplay,
+        backlight: &mut Backlight,
+    ) -> bool {
+        self.last_activity = Instant::now();
+        let was_off = self.state == PowerState::PanelOff;
+        self.set_state(PowerState::Active, display, backlight).await;
+        was_off
+    }
+
+    /// Follow brightness changes from settings while the display is active.
+    pub fn sync_brightness(&mut self, backlight: &mut Backlight) {
+        let brightness = SettingsService::get().brightness;
+        if self.state == PowerState::Active && backlight.percent() != brightness {
+            backlight.set_percent(brightness);
+        }
+    }
+
+    /// Apply inactivity timeouts. Call periodically.
+    pub async fn poll(&mut self, display: &mut LilkaDisplay, backlight: &mut Backlight) {
+        let settings = SettingsService::get();
+        let idle = self.last_activity.elapsed();
+        let expired = |secs: u16| secs > 0 && idle >= Duration::from_secs(secs as u64);
+
+        let target = if expired(settings.screen_off_timeout_secs) {
+            PowerState::PanelOff
+        } else if expired(settings.dim_timeout_secs) {
+            PowerState::Dimmed
+        } else {
+            PowerState::Active
+        };
+        self.set_state(target, display, backlight).await;
+
+        if self.state == PowerState::PanelOff {
+            match settings.sleep_mode {
+                SleepMode::Off => {}
+                SleepMode::Light => self.light_sleep(display, backlight),
+                SleepMode::Deep => self.deep_sleep(),
+            }
+        }
+    }
+
+    async fn set_state(
+        &mut self,
+        state: PowerState,
+        display: &mut LilkaDisplay,
+        backlight: &mut Backlight,
+    ) {
+        if state == self.state {
+            return;
+        }
+        info!("Power state: {:?} -> {:?}", self.state, state);
+
+        // ST7789 keeps its frame memory while in sleep-in, so no redraw is needed on wake
+        if self.state == PowerState::PanelOff {
+            if let Err(e) = display.wake(&mut Delay) {
+                warn!("Failed to wake display: {}", DisplayError::from(e));
+            }
+        }
+
+        let brightness = SettingsService::get().brightness;
+        match state {
+            PowerState::Active => backlight.fade_to(brightness, WAKE_FADE).await,
+            PowerState::Dimmed => {
+                backlight
+                    .fade_to(DIM_PERCENT.min(brightness), DIM_FADE)
+                    .await
+            }
+            PowerState::PanelOff => {
+                backlight.fade_to(0, DIM_FADE).await;
+                if let Err(e) = display.sleep(&mut Delay) {
+                    warn!("Failed to put display to sleep: {}", DisplayError::from(e));
+                }
+            }
+        }
+        self.state = state;
+    }
+
+    /// Sleep until any button is pressed. Buttons are configured as GPIO wake sources in `Board::init`.
+    fn light_sleep(&mut self, display: &mut LilkaDisplay, backlight: &mut Backlight) {
+        info!("Entering light sleep");
+        if ClockService::sleep_light(&[&GpioWakeupSource::new()]).is_none() {
+            warn!("Clock not running, staying awake");
+            return;
+        }
+        info!("Woke up from light sleep");
+        // Only a button press wakes the chip, so count it as activity right away,
+        // otherwise the next poll would send it straight back to sleep
+        self.on_input(display, backlight);
+    }
+
+    /// Sleep until A, B, C or D is pressed. The chip resets on wake-up, so the button
+    /// drivers never see the pins reconfigured for sleep.
+    fn deep_sleep(&mut self) {
+        info!("Entering deep sleep, press A, B, C or D to wake up");
+        let [a, b, c, d] = &mut self.wake_pins.pins;
+        let mut pins: [(&mut dyn RtcPin, WakeupLevel); 4] = [
+            (a, WakeupLevel::Low),
+            (b, WakeupLevel::Low),
+            (c, WakeupLevel::Low),
+            (d, WakeupLevel::Low),
+        ];
+        let rtcio = RtcioWakeupSource::new(&mut pins);
+        ClockService::sleep_deep(&[&rtcio]);
+        warn!("Clock not running, staying awake");
+    }
+}
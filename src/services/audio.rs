@@ -0,0 +1,197 @@
+use core::cell::RefCell;
+
+use embassy_sync::blocking_mutex::{raw::CriticalSectionRawMutex, Mutex};
+use embassy_sync::channel::Channel;
+use esp_hal::ledc::Ledc;
+use log::{info, warn};
+
+use crate::buzzer::Buzzer;
+use crate::error::AudioError;
+use crate::i18n::Msg;
+use crate::music::notes::REST;
+use crate::music::LIBRARY;
+use crate::services::diagnostics::{monitor, Task};
+use crate::services::NotificationService;
+
+const COMMAND_QUEUE_SIZE: usize = 4;
+
+static COMMANDS: Channel<CriticalSectionRawMutex, AudioCommand, COMMAND_QUEUE_SIZE> =
+    Channel::new();
+
+static STATUS: Mutex<CriticalSectionRawMutex, RefCell<PlayerStatus>> =
+    Mutex::new(RefCell::new(PlayerStatus::IDLE));
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PlaybackState {
+    Stopped,
+    Playing,
+    Paused,
+}
+
+/// Snapshot of the player, published by `audio_task` after every note and command.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PlayerStatus {
+    /// Index of the current song in `music::LIBRARY`.
+    pub song: Option<usize>,
+    pub state: PlaybackState,
+    pub note_index: usize,
+    pub elapsed_ms: u32,
+    /// Frequency of the note being played, `REST` when silent.
+    pub note: f64,
+}
+
+impl PlayerStatus {
+    pub const IDLE: Self = Self {
+        song: None,
+        state: PlaybackState::Stopped,
+        note_index: 0,
+        elapsed_ms: 0,
+        note: REST,
+    };
+
+    fn start(song: usize) -> Self {
+        Self {
+            song: Some(song),
+            state: PlaybackState::Playing,
+            ..Self::IDLE
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+enum AudioCommand {
+    Play(usize),
+    TogglePause,
+    Stop,
+    Next,
+}
+
+/// Handle for controlling music playback from other tasks
+pub struct AudioService;
+
+impl AudioService {
+    /// Start playing the song at `index` in `music::LIBRARY` from the beginning.
+    pub fn play(index: usize) -> Result<(), AudioError> {
+        if index >= LIBRARY.len() {
+            return Err(AudioError::UnknownSong(index));
+        }
+        Self::send(AudioCommand::Play(index))
+    }
+
+    pub fn toggle_pause() -> Result<(), AudioError> {
+        Self::send(AudioCommand::TogglePause)
+    }
+
+    pub fn stop() -> Result<(), AudioError> {
+        Self::send(AudioCommand::Stop)
+    }
+
+    /// Skip to the next song in the library, wrapping around at the end.
+    pub fn next() -> Result<(), AudioError> {
+        Self::send(AudioCommand::Next)
+    }
+
+    pub fn status() -> PlayerStatus {
+        STATUS.lock(|status| *status.borrow())
+    }
+
+    /// Commands waiting in the queue and its capacity.
+    pub fn queue_fill() -> (usize, usize) {
+        (COMMANDS.len(), COMMANDS.capacity())
+    }
+
+    fn send(command: AudioCommand) -> Result<(), AudioError> {
+        COMMANDS.try_send(command).map_err(|_| {
+            warn!("Audio command queue full, dropping {:?}", command);
+            AudioError::QueueFull
+        })
+    }
+}
+
+fn apply(status: &mut PlayerStatus, command: AudioCommand) {
+    match command {
+        AudioCommand::Play(index) if index < LIBRARY.len() => {
+            *status = PlayerStatus::start(index);
+        }
+        AudioCommand::Play(index) => warn!("Unknown song index {}", index),
+        AudioCommand::TogglePause => {
+            status.state = match status.state {
+                PlaybackState::Playing => PlaybackState::Paused,
+                PlaybackState::Paused => PlaybackState::Playing,
+                PlaybackState::Stopped => PlaybackState::Stopped,
+            };
+            status.note = REST;
+        }
+        AudioCommand::Stop => *status = PlayerStatus::IDLE,
+        AudioCommand::Next => {
+            let next = status
+                .song
+                .map(|song| (song + 1) % LIBRARY.len())
+                .unwrap_or(0);
+            *status = PlayerStatus::start(next);
+        }
+    }
+}
+
+fn publish(status: PlayerStatus) {
+    STATUS.lock(|inner| *inner.borrow_mut() = status);
+}
+
+/// Music player task - owns the buzzer and plays songs note by note,
+/// checking for commands between notes.
+#[embassy_executor::task]
+pub async fn audio_task(buzzer: Buzzer, ledc: Ledc<'static>) {
+    monitor(Task::Audio, run(buzzer, ledc)).await
+}
+
+async fn run(mut buzzer: Buzzer, mut ledc: Ledc<'static>) {
+    let mut status = PlayerStatus::IDLE;
+
+    loop {
+        // Nothing to play: sleep until someone asks for music
+        if status.state != PlaybackState::Playing {
+            let command = COMMANDS.receive().await;
+            apply(&mut status, command);
+        }
+        while let Ok(command) = COMMANDS.try_receive() {
+            apply(&mut status, command);
+        }
+        publish(status);
+
+        let Some(index) = status.song else {
+            continue;
+        };
+        if status.state != PlaybackState::Playing {
+            continue;
+        }
+
+        let entry = &LIBRARY[index];
+        let song = entry.song();
+        match song.melody.get(status.note_index) {
+            Some(&(note, duration_type)) => {
+                status.note = note;
+                publish(status);
+
+                if let Err(e) = buzzer
+                    .play_note(&song, note, duration_type, &mut ledc)
+                    .await
+                {
+                    // The next note would most likely fail the same way
+                    warn!("Stopped playing {}: {}", entry.name, e);
+                    NotificationService::failed(Msg::NotifyPlaybackFailed, e.msg());
+                    status = PlayerStatus::IDLE;
+                    publish(status);
+                    continue;
+                }
+
+                status.note_index += 1;
+                status.elapsed_ms += song.calc_note_duration(duration_type);
+            }
+            None => {
+                info!("Finished playing {}", entry.name);
+                status = PlayerStatus::IDLE;
+            }
+        }
+        publish(status);
+    }
+}
@@ -0,0 +1,107 @@
+use alloc::format;
+use core::cell::RefCell;
+
+use embassy_sync::blocking_mutex::{raw::CriticalSectionRawMutex, Mutex};
+use embassy_time::{Duration, Timer};
+use lilka_logic::battery::{percent_from_mv, ChargeDetector, MovingAverage};
+use log::{info, warn};
+
+use crate::battery::Battery;
+use crate::i18n::{self, Msg};
+use crate::services::diagnostics::{monitor, Task};
+use crate::services::NotificationService;
+
+const SAMPLE_INTERVAL_SECS: u64 = 5;
+// Sample window for the voltage filter and charge detection (1 minute)
+const FILTER_WINDOW: usize = 12;
+
+pub const LOW_BATTERY_PERCENT: u8 = 15;
+pub const CRITICAL_BATTERY_PERCENT: u8 = 5;
+
+static STATUS: Mutex<CriticalSectionRawMutex, RefCell<Option<BatteryStatus>>> =
+    Mutex::new(RefCell::new(None));
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BatteryStatus {
+    pub voltage_mv: u32,
+    pub percent: u8,
+    pub charging: bool,
+}
+
+impl BatteryStatus {
+    pub fn is_low(&self) -> bool {
+        !self.charging && self.percent <= LOW_BATTERY_PERCENT
+    }
+}
+
+pub struct BatteryService;
+
+impl BatteryService {
+    /// Latest battery reading. Returns None until the first sample is taken.
+    pub fn status() -> Option<BatteryStatus> {
+        STATUS.lock(|inner| *inner.borrow())
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Warning {
+    None,
+    Low,
+    Critical,
+}
+
+/// Battery monitor task - samples the battery voltage and publishes the charge level
+#[embassy_executor::task]
+pub async fn battery_task(battery: Battery) {
+    monitor(Task::Battery, run(battery)).await
+}
+
+async fn run(mut battery: Battery) {
+    let mut filter = MovingAverage::<FILTER_WINDOW>::new();
+    let mut charge_detector = ChargeDetector::<FILTER_WINDOW>::new();
+    let mut warning = Warning::None;
+
+    loop {
+        let voltage_mv = filter.push(battery.read_mv());
+        let status = BatteryStatus {
+            voltage_mv,
+            percent: percent_from_mv(voltage_mv),
+            charging: charge_detector.update(voltage_mv),
+        };
+        STATUS.lock(|inner| inner.borrow_mut().replace(status));
+
+        // Warn once per threshold crossing, re-arm when charging
+        let level = match status.percent {
+            _ if status.charging => Warning::None,
+            p if p <= CRITICAL_BATTERY_PERCENT => Warning::Critical,
+            p if p <= LOW_BATTERY_PERCENT => Warning::Low,
+            _ => Warning::None,
+        };
+        if level > warning {
+            match level {
+                Warning::Critical => {
+                    warn!("Battery critical: {}%", status.percent);
+                    NotificationService::error(format!(
+                        "{}: {}%",
+                        i18n::tr(Msg::NotifyBatteryCritical),
+                        status.percent
+                    ));
+                }
+                Warning::Low => {
+                    warn!("Battery low: {}%", status.percent);
+                    NotificationService::warning(format!(
+                        "{}: {}%",
+                        i18n::tr(Msg::NotifyBatteryLow),
+                        status.percent
+                    ));
+                }
+                Warning::None => {}
+            }
+        } else if level == Warning::None && warning != Warning::None {
+            info!("Battery level ok: {}%", status.percent);
+        }
+        warning = level;
+
+        Timer::after(Duration::from_secs(SAMPLE_INTERVAL_SECS)).await;
+    }
+}
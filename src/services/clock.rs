@@ -1,11 +1,14 @@
 use core::cell::RefCell;
+use core::sync::atomic::{AtomicBool, Ordering};
 
 use embassy_sync::blocking_mutex::{raw::CriticalSectionRawMutex, Mutex};
+use esp_hal::rtc_cntl::sleep::WakeSource;
 use esp_hal::rtc_cntl::Rtc;
 use jiff::Timestamp;
 
 static CLOCK: Mutex<CriticalSectionRawMutex, RefCell<Option<Rtc<'static>>>> =
     Mutex::new(RefCell::new(None));
+static SYNCED: AtomicBool = AtomicBool::new(false);
 
 pub struct ClockService;
 
@@ -14,19 +17,51 @@ impl ClockService {
         CLOCK.lock(|inner| inner.borrow_mut().replace(rtc));
     }
 
-    pub fn get_current_time() -> Timestamp {
+    /// `None` before `init` and while the chip is in light sleep.
+    pub fn get_current_time() -> Option<Timestamp> {
         CLOCK.lock(|inner| {
             let rtc = inner.borrow();
-            let rtc = rtc.as_ref().expect("ClockService not initialized");
-            Timestamp::from_microsecond(rtc.current_time_us() as i64).unwrap()
+            Timestamp::from_microsecond(rtc.as_ref()?.current_time_us() as i64).ok()
         })
     }
 
-    pub fn set_current_time(timestamp_us: u64) {
+    /// Set the time and count the clock as synced. `None` if there is no clock to set,
+    /// as for `get_current_time`.
+    pub fn set_current_time(timestamp_us: u64) -> Option<()> {
         CLOCK.lock(|inner| {
             let mut rtc = inner.borrow_mut();
-            let rtc = rtc.as_mut().expect("ClockService not initialized");
-            rtc.set_current_time_us(timestamp_us);
-        });
+            rtc.as_mut()?.set_current_time_us(timestamp_us);
+            Some(())
+        })?;
+        SYNCED.store(true, Ordering::Release);
+        Some(())
+    }
+
+    /// Whether the time has been set from a reliable source since boot.
+    pub fn is_synced() -> bool {
+        SYNCED.load(Ordering::Acquire)
+    }
+
+    /// Enter light sleep until one of `wake_sources` fires. The RTC keeps counting while asleep.
+    ///
+    /// The RTC is taken out of the lock while asleep, so interrupts stay enabled
+    /// and can wake the chip. `None` without sleeping if `init` hasn't run.
+    pub fn sleep_light(wake_sources: &[&dyn WakeSource]) -> Option<()> {
+        let mut rtc = Self::take()?;
+        rtc.sleep_light(wake_sources);
+        Self::init(rtc);
+        Some(())
+    }
+
+    /// Enter deep sleep. The chip resets when one of `wake_sources` fires, so this
+    /// only returns if `init` hasn't run.
+    pub fn sleep_deep(wake_sources: &[&dyn WakeSource]) {
+        if let Some(mut rtc) = Self::take() {
+            rtc.sleep_deep(wake_sources)
+        }
+    }
+
+    fn take() -> Option<Rtc<'static>> {
+        CLOCK.lock(|inner| inner.borrow_mut().take())
     }
 }
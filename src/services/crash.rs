@@ -0,0 +1,157 @@
+//! Crash reports. The panic handler keeps one in RTC memory across the reset that
+//! follows, the next boot moves it to flash so it also survives a power cycle.
+
+use core::cell::RefCell;
+use core::fmt::{self, Write};
+use core::panic::PanicInfo;
+use core::ptr::addr_of_mut;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use embassy_sync::blocking_mutex::{raw::CriticalSectionRawMutex, Mutex};
+use embassy_time::{Duration, Instant};
+use esp_backtrace::Backtrace;
+use esp_println::println;
+use heapless::{String, Vec};
+use lilka_logic::crash::{MAGIC, MAX_FRAMES, RECORD_SIZE};
+use log::{info, warn};
+
+use crate::core::Truncate;
+use crate::services::storage::{Record, StorageService};
+
+pub use lilka_logic::crash::CrashReport;
+
+// Left alone by the bootloader, so it survives the software reset after a panic.
+// Holds garbage after power-on, which the magic and checksum reject.
+#[esp_hal::ram(unstable(rtc_fast, persistent))]
+static mut PENDING: [u8; RECORD_SIZE] = [0; RECORD_SIZE];
+
+static LAST: Mutex<CriticalSectionRawMutex, RefCell<Option<CrashReport>>> =
+    Mutex::new(RefCell::new(None));
+
+// The last crash happened right before this boot
+static FRESH: AtomicBool = AtomicBool::new(false);
+
+static PANICKING: AtomicBool = AtomicBool::new(false);
+
+/// A report with only the message, taken now.
+fn report_now(message: fmt::Arguments) -> CrashReport {
+    let mut report = CrashReport {
+        message: String::new(),
+        location: String::new(),
+        uptime: Duration::from_micros(Instant::now().as_micros()),
+        backtrace: Vec::new(),
+    };
+    // A cut off message is still worth keeping
+    let _ = Truncate(&mut report.message).write_fmt(message);
+    report
+}
+
+fn capture(info: &PanicInfo) -> CrashReport {
+    let mut report = report_now(format_args!("{}", info.message()));
+    if let Some(location) = info.location() {
+        let _ = write!(
+            Truncate(&mut report.location),
+            "{}:{}:{}",
+            location.file(),
+            location.line(),
+            location.column()
+        );
+    }
+    for frame in Backtrace::capture().frames().iter().take(MAX_FRAMES) {
+        let _ = report.backtrace.push(frame.program_counter() as u32);
+    }
+    report
+}
+
+/// Keep a report of the panic for the next boot, then restart.
+#[panic_handler]
+fn panic(info: &PanicInfo) -> ! {
+    // Panicking again while recording: the first report is what matters
+    if PANICKING.swap(true, Ordering::Relaxed) {
+        esp_hal::system::software_reset();
+    }
+
+    println!("\n====== PANIC ======\n{}", info);
+    let report = capture(info);
+    keep_pending(&report);
+
+    println!("Backtrace:");
+    for pc in &report.backtrace {
+        println!("0x{:08x}", pc);
+    }
+    println!("Restarting");
+    esp_hal::system::software_reset()
+}
+
+/// Leave `report` for `CrashService::init` on the next boot.
+fn keep_pending(report: &CrashReport) {
+    let record = report.encode();
+    // SAFETY: written with interrupts off, so a panic can't interleave with `record`,
+    // and only read by `CrashService::init` before anything else runs
+    critical_section::with(|_| unsafe { addr_of_mut!(PENDING).write(record) });
+}
+
+pub struct CrashService;
+
+impl CrashService {
+    /// Pick up the report of a panic before this boot and move it to flash.
+    /// Needs `StorageService` initialized.
+    pub fn init() {
+        // SAFETY: nothing else runs this early, see `keep_pending`
+        let pending = unsafe { addr_of_mut!(PENDING).read() };
+
+        let report = match CrashReport::decode(&pending) {
+            Some(report) => {
+                warn!("Restarted after a crash: {}", report.message);
+                // SAFETY: as above
+                unsafe { addr_of_mut!(PENDING).write([0; RECORD_SIZE]) };
+                if let Err(e) = StorageService::write(Record::Crash, &report.encode()) {
+                    warn!("Failed to save crash report: {}", e);
+                }
+                FRESH.store(true, Ordering::Relaxed);
+                Some(report)
+            }
+            None => {
+                let mut record = [0u8; RECORD_SIZE];
+                match StorageService::read(Record::Crash, &mut record) {
+                    Ok(()) => CrashReport::decode(&record),
+                    Err(e) => {
+                        warn!("Failed to read crash report: {}", e);
+                        None
+                    }
+                }
+            }
+        };
+        LAST.lock(|inner| *inner.borrow_mut() = report);
+    }
+
+    /// Leave a report for the next boot without panicking, for resets the firmware
+    /// sees coming such as the watchdog's. A panic before the reset replaces it.
+    pub fn record(message: fmt::Arguments) {
+        let report = report_now(message);
+        warn!("Crash recorded: {}", report.message);
+        keep_pending(&report);
+    }
+
+    /// The most recent crash, kept until cleared.
+    pub fn last() -> Option<CrashReport> {
+        LAST.lock(|inner| inner.borrow().clone())
+    }
+
+    /// Whether the device restarted because of the crash in `last`.
+    pub fn is_fresh() -> bool {
+        FRESH.load(Ordering::Relaxed)
+    }
+
+    /// Forget the last crash, in flash as well.
+    pub fn clear() {
+        LAST.lock(|inner| inner.borrow_mut().take());
+        FRESH.store(false, Ordering::Relaxed);
+        // An erased magic is enough for the record to read as empty
+        if let Err(e) = StorageService::write(Record::Crash, &[0xFF; MAGIC.len()]) {
+            warn!("Failed to clear crash report: {}", e);
+        } else {
+            info!("Crash report cleared");
+        }
+    }
+}
@@ -0,0 +1,261 @@
+//! Runtime health: heap, stack, executor and queue statistics.
+//!
+//! All embassy tasks run on the main stack of CPU 0, their futures live in the executor's
+//! static arena. There is no stack per task to measure, so the stack figure is the high-water
+//! mark of the main stack, found by painting it at boot. Per task the executor is measured
+//! instead: how often each task is polled and how long the polls take, see [`monitor`].
+
+use core::alloc::Layout;
+use core::cell::RefCell;
+use core::future::Future;
+use core::pin::Pin;
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use core::task::{Context, Poll};
+
+use embassy_sync::blocking_mutex::{raw::CriticalSectionRawMutex, Mutex};
+use embassy_time::{Duration, Instant, Timer};
+use esp_alloc::MemoryCapability;
+use log::{info, warn};
+
+use crate::services::{AudioService, SystemService};
+use crate::state::UI_CHANNEL;
+
+pub use lilka_logic::diagnostics::{
+    ChannelReport, HeapReport, LatencyReport, Report, StackReport, Task, TaskReport, TASK_COUNT,
+};
+
+const SAMPLE_PERIOD: Duration = Duration::from_millis(100);
+// Samples per report, one report a second
+const SAMPLES: u32 = 10;
+// Reports between summaries on the serial console
+const LOG_EVERY: u32 = 60;
+
+// Fill pattern for the unused part of the main stack
+const STACK_PAINT: u32 = 0xA5A5_A5A5;
+// The stack protector guard sits just above the stack end, it must not be painted over
+const STACK_GUARD_SKIP: usize = 256;
+// Room left below the caller of `init`
+const STACK_PAINT_MARGIN: usize = 512;
+
+extern "C" {
+    static _stack_end_cpu0: u32;
+    static _stack_start_cpu0: u32;
+}
+
+/// Counters updated by [`Monitored`] on every poll.
+struct Counters {
+    polls: AtomicU32,
+    busy_us: AtomicU32,
+    max_poll_us: AtomicU32,
+}
+
+impl Counters {
+    const fn new() -> Self {
+        Self {
+            polls: AtomicU32::new(0),
+            busy_us: AtomicU32::new(0),
+            max_poll_us: AtomicU32::new(0),
+        }
+    }
+}
+
+static COUNTERS: [Counters; TASK_COUNT] = [const { Counters::new() }; TASK_COUNT];
+
+static STACK_PAINTED: AtomicBool = AtomicBool::new(false);
+
+static REPORT: Mutex<CriticalSectionRawMutex, RefCell<Report>> =
+    Mutex::new(RefCell::new(Report::EMPTY));
+
+/// A future whose polls are counted for `task`.
+pub struct Monitored<F> {
+    task: Task,
+    future: F,
+}
+
+/// Count the polls of `future` and the time they take under `task`.
+pub fn monitor<F: Future>(task: Task, future: F) -> Monitored<F> {
+    Monitored { task, future }
+}
+
+impl<F: Future> Future for Monitored<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let task = self.task;
+        // SAFETY: `future` is never moved out of the pinned `Monitored`
+        let future = unsafe { self.map_unchecked_mut(|this| &mut this.future) };
+        let start = Instant::now();
+        let result = future.poll(cx);
+        let took = start.elapsed().as_micros() as u32;
+
+        let counters = &COUNTERS[task as usize];
+        counters.polls.fetch_add(1, Ordering::Relaxed);
+        counters.busy_us.fetch_add(took, Ordering::Relaxed);
+        counters.max_poll_us.fetch_max(took, Ordering::Relaxed);
+        result
+    }
+}
+
+pub struct DiagnosticsService;
+
+impl DiagnosticsService {
+    /// Paint the unused main stack so its high-water mark can be found later.
+    /// Call first thing in `main`, everything below the caller is overwritten.
+    pub fn init() {
+        let here = 0u32;
+        let here = core::ptr::addr_of!(here) as usize;
+        if !(stack_end()..stack_start()).contains(&here) {
+            warn!("Not running on the main stack, its usage is not tracked");
+            return;
+        }
+        // Interrupts would put their frames right where the paint goes
+        critical_section::with(|_| {
+            let mut addr = stack_end() + STACK_GUARD_SKIP;
+            while addr < here - STACK_PAINT_MARGIN {
+                // SAFETY: the stack below the current frame is not in use
+                unsafe { (addr as *mut u32).write_volatile(STACK_PAINT) };
+                addr += 4;
+            }
+        });
+        STACK_PAINTED.store(true, Ordering::Release);
+    }
+
+    /// The latest report, at most a second old. The largest free block is measured
+    /// now, it takes a few allocations.
+    pub fn report() -> Report {
+        let mut report = REPORT.lock(|report| *report.borrow());
+        report.heap.largest_free = largest_free_block(report.heap.internal_free);
+        report
+    }
+
+    /// Print the latest report to the serial console.
+    pub fn log_report() {
+        info!("diagnostics:\n{}", Self::report());
+    }
+}
+
+fn stack_end() -> usize {
+    // SAFETY: only the address of the linker symbol is taken
+    unsafe { core::ptr::addr_of!(_stack_end_cpu0) as usize }
+}
+
+fn stack_start() -> usize {
+    // SAFETY: only the address of the linker symbol is taken
+    unsafe { core::ptr::addr_of!(_stack_start_cpu0) as usize }
+}
+
+/// The stack grows down, so the first word that lost its paint marks the deepest use.
+fn stack_usage() -> StackReport {
+    let (end, start) = (stack_end(), stack_start());
+    if !STACK_PAINTED.load(Ordering::Acquire) {
+        return StackReport {
+            peak: 0,
+            size: start - end,
+        };
+    }
+    let mut addr = end + STACK_GUARD_SKIP;
+    // SAFETY: the range lies inside the main stack
+    while addr < start && unsafe { (addr as *const u32).read_volatile() } == STACK_PAINT {
+        addr += 4;
+    }
+    StackReport {
+        peak: start - addr,
+        size: start - end,
+    }
+}
+
+/// Find the largest block the allocator hands out from internal RAM by trying sizes up
+/// to its free memory. PSRAM is left alone: probing it would mean allocating megabytes.
+fn largest_free_block(free: usize) -> usize {
+    let (mut fits, mut fails) = (0, free + 1);
+    while fails - fits > 64 {
+        let size = fits + (fails - fits) / 2;
+        let Ok(layout) = Layout::from_size_align(size, 4) else {
+            break;
+        };
+        // SAFETY: the block is released right away and never touched
+        let block =
+            unsafe { esp_alloc::HEAP.alloc_caps(MemoryCapability::Internal.into(), layout) };
+        if block.is_null() {
+            fails = size;
+        } else {
+            unsafe { alloc::alloc::dealloc(block, layout) };
+            fits = size;
+        }
+    }
+    fits
+}
+
+fn channels() -> [(usize, usize); 2] {
+    [
+        (UI_CHANNEL.len(), UI_CHANNEL.capacity()),
+        AudioService::queue_fill(),
+    ]
+}
+
+/// Samples timer latency and queue fill, and refreshes the report every second.
+#[embassy_executor::task]
+pub async fn diagnostics_task() {
+    monitor(Task::Diagnostics, run()).await
+}
+
+async fn run() {
+    let mut busy_before = [0u32; TASK_COUNT];
+    let mut peaks = [0usize; 2];
+    let mut reports = 0u32;
+    let mut max_latency_us = 0u32;
+
+    loop {
+        let mut late_us = 0u32;
+        for _ in 0..SAMPLES {
+            let due = Instant::now() + SAMPLE_PERIOD;
+            Timer::at(due).await;
+            let late = Instant::now().saturating_duration_since(due).as_micros() as u32;
+            late_us += late;
+            max_latency_us = max_latency_us.max(late);
+            for (peak, (len, _)) in peaks.iter_mut().zip(channels()) {
+                *peak = (*peak).max(len);
+            }
+        }
+
+        let usage = SystemService::heap();
+        let mut report = Report {
+            heap: HeapReport {
+                used: usage.used,
+                free: usage.free,
+                internal_free: usage.internal_free,
+                // Measured in `report`, on demand
+                largest_free: 0,
+            },
+            stack: stack_usage(),
+            latency: LatencyReport {
+                avg_us: late_us / SAMPLES,
+                max_us: max_latency_us,
+            },
+            ..Report::EMPTY
+        };
+        let window_us = (SAMPLE_PERIOD * SAMPLES).as_micros() as u32;
+        for (idx, task) in report.tasks.iter_mut().enumerate() {
+            let counters = &COUNTERS[idx];
+            let busy = counters.busy_us.load(Ordering::Relaxed);
+            task.polls = counters.polls.load(Ordering::Relaxed);
+            task.load =
+                (busy.wrapping_sub(busy_before[idx]) as u64 * 1000 / window_us as u64) as u32;
+            task.max_poll_us = counters.max_poll_us.load(Ordering::Relaxed);
+            busy_before[idx] = busy;
+        }
+        for ((channel, (len, capacity)), peak) in
+            report.channels.iter_mut().zip(channels()).zip(peaks)
+        {
+            channel.len = len;
+            channel.capacity = capacity;
+            channel.peak = peak;
+        }
+        REPORT.lock(|current| *current.borrow_mut() = report);
+
+        reports += 1;
+        if reports % LOG_EVERY == 0 {
+            DiagnosticsService::log_report();
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//! Logger for the `log` macros: forwards every record to serial and keeps the latest
+//! ones in RAM for the log viewer.
+
+use core::cell::RefCell;
+use core::fmt::Write;
+
+use alloc::vec::Vec;
+use embassy_sync::blocking_mutex::{raw::CriticalSectionRawMutex, Mutex};
+use embassy_time::Instant;
+use esp_println::println;
+use heapless::{Deque, String};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+use crate::core::Truncate;
+
+pub use lilka_logic::logger::LogRecord;
+
+const CAPACITY: usize = 64;
+
+static LOGGER: RingLogger = RingLogger;
+
+static BUFFER: Mutex<CriticalSectionRawMutex, RefCell<Buffer>> =
+    Mutex::new(RefCell::new(Buffer::new()));
+
+struct Buffer {
+    records: Deque<LogRecord, CAPACITY>,
+    // Records ever written, so readers can tell when something new came in
+    written: u32,
+}
+
+impl Buffer {
+    const fn new() -> Self {
+        Self {
+            records: Deque::new(),
+            written: 0,
+        }
+    }
+}
+
+struct RingLogger;
+
+impl Log for RingLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let timestamp = Instant::now();
+        let module = record
+            .module_path_static()
+            .and_then(|path| path.rsplit("::").next())
+            .unwrap_or("?");
+        let millis = timestamp.as_millis();
+        println!(
+            "[{:>6}.{:03}] {:<5} {}: {}",
+            millis / 1000,
+            millis % 1000,
+            record.level(),
+            module,
+            record.args()
+        );
+
+        let mut message = String::new();
+        // A cut off message is still worth keeping
+        let _ = write!(Truncate(&mut message), "{}", record.args());
+        let entry = LogRecord {
+            level: record.level(),
+            module,
+            timestamp,
+            message,
+        };
+        BUFFER.lock(|buffer| {
+            let mut buffer = buffer.borrow_mut();
+            if buffer.records.is_full() {
+                buffer.records.pop_front();
+            }
+            let _ = buffer.records.push_back(entry);
+            buffer.written = buffer.written.wrapping_add(1);
+        });
+    }
+
+    fn flush(&self) {}
+}
+
+/// Level set at build time with `ESP_LOG`, as with the `esp_println` logger.
+/// Per-module filters are not supported, only the leading level is used.
+fn max_level() -> LevelFilter {
+    option_env!("ESP_LOG")
+        .and_then(|spec| spec.split(',').next())
+        .and_then(|level| level.trim().parse().ok())
+        .unwrap_or(LevelFilter::Info)
+}
+
+pub struct LogService;
+
+impl LogService {
+    /// Install the logger. Call once, before anything logs.
+    pub fn init() {
+        if log::set_logger(&LOGGER).is_ok() {
+            log::set_max_level(max_level());
+        }
+    }
+
+    /// Kept records at `level` or more severe, oldest first.
+    pub fn records(level: Level) -> Vec<LogRecord> {
+        BUFFER.lock(|buffer| {
+            buffer
+                .borrow()
+                .records
+                .iter()
+                .filter(|record| record.level <= level)
+                .cloned()
+                .collect()
+        })
+    }
+
+    /// Number of records logged since boot, wrapping around.
+    pub fn written() -> u32 {
+        BUFFER.lock(|buffer| buffer.borrow().written)
+    }
+
+    pub fn clear() {
+        BUFFER.lock(|buffer| buffer.borrow_mut().records.clear());
+    }
+}
@@ -1,7 +1,27 @@
+pub mod audio;
+pub mod battery;
 pub mod clock;
+pub mod crash;
+pub mod diagnostics;
+pub mod logger;
 pub mod network;
+pub mod notifications;
 pub mod ntp;
+pub mod settings;
+pub mod storage;
+pub mod supervisor;
+pub mod system;
 
+pub use audio::{audio_task, AudioService};
+pub use battery::{battery_task, BatteryService};
 pub use clock::ClockService;
-pub use network::{network_task, NetworkService};
+pub use crash::{CrashReport, CrashService};
+pub use diagnostics::{diagnostics_task, DiagnosticsService};
+pub use logger::{LogRecord, LogService};
+pub use network::{network_task, AccessPoint, Credentials, NetworkService};
+pub use notifications::{Notification, NotificationService, Severity};
 pub use ntp::ntp_task;
+pub use settings::{settings_task, Settings, SettingsService, SleepMode};
+pub use storage::StorageService;
+pub use supervisor::supervisor_task;
+pub use system::SystemService;
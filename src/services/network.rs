@@ -1,24 +1,59 @@
+use core::cell::RefCell;
 use core::ptr;
 use core::sync::atomic::{AtomicPtr, Ordering};
 
+use alloc::boxed::Box;
+use alloc::vec::Vec;
 use embassy_futures::join::join;
+use embassy_futures::select::{select, Either};
 use embassy_net::{Stack, StackResources};
-use embassy_time::{Duration, Timer};
+use embassy_sync::blocking_mutex::{raw::CriticalSectionRawMutex, Mutex};
+use embassy_sync::channel::Channel;
+use embassy_sync::signal::Signal;
+use embassy_time::{with_timeout, Duration, Timer};
 use esp_hal::peripherals::WIFI;
 use esp_hal::rng::Rng;
-use esp_println::println;
 use esp_radio::wifi::{ClientConfig, ModeConfig, ScanConfig, WifiController, WifiEvent};
+use heapless::String;
+use log::{debug, error, info, warn};
 use static_cell::StaticCell;
 
+use crate::error::NetworkError;
+use crate::i18n::{self, Msg};
 use crate::mk_static;
+use crate::services::diagnostics::{monitor, Task};
+use crate::services::supervisor::Backoff;
+use crate::services::{Notification, NotificationService};
+use crate::ui::screens::WifiScreen;
+
+pub use lilka_logic::network::{AccessPoint, Credentials};
 
 const SSID: &str = "chilla";
 const PASSWORD: &str = "40454540";
+const SCAN_LIMIT: usize = 10;
+const SCAN_TIMEOUT: Duration = Duration::from_secs(10);
+// Between failed attempts to connect, growing while the network stays out of reach
+const RETRY_MIN: Duration = Duration::from_secs(5);
+const RETRY_MAX: Duration = Duration::from_secs(300);
 
 // Static storage for network stack pointer - accessible from other tasks
 // Safety: Stack is initialized once and never moved. Access is read-only after init.
 static NETWORK_STACK: AtomicPtr<Stack<'static>> = AtomicPtr::new(ptr::null_mut());
 
+static CREDENTIALS: Mutex<CriticalSectionRawMutex, RefCell<Option<Credentials>>> =
+    Mutex::new(RefCell::new(None));
+
+static COMMANDS: Channel<CriticalSectionRawMutex, NetworkCommand, 2> = Channel::new();
+
+static SCAN_RESULT: Signal<CriticalSectionRawMutex, Result<Vec<AccessPoint>, NetworkError>> =
+    Signal::new();
+
+#[derive(Clone, Debug)]
+enum NetworkCommand {
+    Scan,
+    Connect(Credentials),
+}
+
 /// Handle for accessing network functionality from other tasks
 pub struct NetworkService;
 
@@ -45,6 +80,38 @@ impl NetworkService {
         }
     }
 
+    /// Name of the access point the device connects to.
+    pub fn ssid() -> String<32> {
+        Self::credentials().ssid
+    }
+
+    pub fn credentials() -> Credentials {
+        CREDENTIALS.lock(|inner| {
+            inner
+                .borrow()
+                .clone()
+                .unwrap_or_else(|| Credentials::new(SSID, PASSWORD).unwrap())
+        })
+    }
+
+    /// Look for access points nearby. Fails if the radio is not running yet.
+    pub async fn scan() -> Result<Vec<AccessPoint>, NetworkError> {
+        SCAN_RESULT.reset();
+        COMMANDS
+            .try_send(NetworkCommand::Scan)
+            .map_err(|_| NetworkError::Busy)?;
+        with_timeout(SCAN_TIMEOUT, SCAN_RESULT.wait())
+            .await
+            .map_err(|_| NetworkError::Timeout)?
+    }
+
+    /// Drop the current link and join another network. Not remembered across reboots.
+    pub fn connect(credentials: Credentials) -> Result<(), NetworkError> {
+        COMMANDS
+            .try_send(NetworkCommand::Connect(credentials))
+            .map_err(|_| NetworkError::Busy)
+    }
+
     /// Wait until the network stack is available
     pub async fn wait_for_stack() -> &'static Stack<'static> {
         loop {
@@ -66,15 +133,20 @@ impl NetworkService {
 /// Main network task - spawn this from main
 #[embassy_executor::task]
 pub async fn network_task(wifi: WIFI<'static>) {
+    if let Err(e) = monitor(Task::Network, run(wifi)).await {
+        // The rest of the device works fine offline
+        error!("Network unavailable: {}", e);
+        NotificationService::failed(Msg::NotifyWifiFailed, e.msg());
+    }
+}
+
+/// Only returns if the radio can't be brought up.
+async fn run(wifi: WIFI<'static>) -> Result<(), NetworkError> {
     // Initialize radio
-    let radio_init = mk_static!(
-        esp_radio::Controller<'static>,
-        esp_radio::init().expect("Failed to initialize radio")
-    );
+    let radio_init = mk_static!(esp_radio::Controller<'static>, esp_radio::init()?);
 
     // Initialize WiFi
-    let (controller, interfaces) = esp_radio::wifi::new(radio_init, wifi, Default::default())
-        .expect("Failed to initialize Wi-Fi");
+    let (controller, interfaces) = esp_radio::wifi::new(radio_init, wifi, Default::default())?;
 
     // Initialize network stack
     let config = embassy_net::Config::dhcpv4(Default::default());
@@ -94,45 +166,115 @@ pub async fn network_task(wifi: WIFI<'static>) {
 
     // Run both connection manager and network runner concurrently
     join(connection_loop(controller), runner.run()).await;
+    Ok(())
 }
 
 async fn connection_loop(mut controller: WifiController<'static>) {
-    println!("WiFi connection manager started");
+    info!("WiFi connection manager started");
+    // Tell the user once per outage, not on every retry
+    let mut failure_notified = false;
+    let mut backoff = Backoff::new(RETRY_MIN, RETRY_MAX);
 
     loop {
         if matches!(controller.is_connected(), Ok(true)) {
-            controller.wait_for_event(WifiEvent::StaDisconnected).await;
-            Timer::after(Duration::from_millis(5000)).await;
+            match select(
+                controller.wait_for_event(WifiEvent::StaDisconnected),
+                COMMANDS.receive(),
+            )
+            .await
+            {
+                Either::First(_) => Timer::after(Duration::from_millis(5000)).await,
+                Either::Second(command) => {
+                    handle_command(&mut controller, command).await;
+                    continue;
+                }
+            }
         }
 
-        if !matches!(controller.is_started(), Ok(true)) {
-            let station_config = ModeConfig::Client(
-                ClientConfig::default()
-                    .with_ssid(SSID.into())
-                    .with_password(PASSWORD.into()),
-            );
-            controller.set_config(&station_config).unwrap();
-            println!("Starting WiFi");
-            controller.start_async().await.unwrap();
-            println!("WiFi started");
-
-            // Optional: scan
-            let scan_config = ScanConfig::default().with_max(10);
-            let result = controller
-                .scan_with_config_async(scan_config)
-                .await
-                .unwrap();
-            for ap in result {
-                println!("{:?}", ap);
+        match connect(&mut controller).await {
+            Ok(()) => {
+                info!("WiFi connected!");
+                NotificationService::info(i18n::tr(Msg::NotifyWifiConnected));
+                failure_notified = false;
+                backoff.reset();
+            }
+            Err(e) => {
+                warn!("WiFi connection failed: {}", e);
+                if !failure_notified {
+                    NotificationService::post(
+                        Notification::failed(Msg::NotifyWifiFailed, e.msg())
+                            .with_action(|bounds| Box::new(WifiScreen::new(bounds))),
+                    );
+                    failure_notified = true;
+                }
+                // Commands are served while waiting for the next attempt
+                if let Either::Second(command) =
+                    select(Timer::after(backoff.next_delay()), COMMANDS.receive()).await
+                {
+                    handle_command(&mut controller, command).await;
+                }
             }
         }
+    }
+}
 
-        println!("Connecting to WiFi...");
-        match controller.connect_async().await {
-            Ok(_) => println!("WiFi connected!"),
-            Err(e) => {
-                println!("WiFi connection failed: {e:?}");
-                Timer::after(Duration::from_millis(5000)).await;
+/// Start the radio if it isn't running and join the configured network.
+/// Failing at any step leaves the controller ready for another attempt.
+async fn connect(controller: &mut WifiController<'static>) -> Result<(), NetworkError> {
+    if !matches!(controller.is_started(), Ok(true)) {
+        let credentials = NetworkService::credentials();
+        let station_config = ModeConfig::Client(
+            ClientConfig::default()
+                .with_ssid(credentials.ssid.as_str().into())
+                .with_password(credentials.password.as_str().into()),
+        );
+        controller.set_config(&station_config)?;
+        info!("Starting WiFi");
+        controller.start_async().await?;
+        info!("WiFi started");
+
+        // Only logged, a failed scan doesn't stop the connection
+        let scan_config = ScanConfig::default().with_max(SCAN_LIMIT);
+        match controller.scan_with_config_async(scan_config).await {
+            Ok(found) => {
+                for ap in found {
+                    debug!("{:?}", ap);
+                }
+            }
+            Err(e) => warn!("WiFi scan failed: {e:?}"),
+        }
+    }
+
+    info!("Connecting to WiFi...");
+    Ok(controller.connect_async().await?)
+}
+
+async fn handle_command(controller: &mut WifiController<'static>, command: NetworkCommand) {
+    match command {
+        NetworkCommand::Scan => {
+            let config = ScanConfig::default().with_max(SCAN_LIMIT);
+            let result = match controller.scan_with_config_async(config).await {
+                Ok(found) => Ok(found
+                    .iter()
+                    .map(|ap| AccessPoint {
+                        ssid: ap.ssid.as_str().try_into().unwrap_or_default(),
+                        channel: ap.channel,
+                        rssi: ap.signal_strength,
+                    })
+                    .collect()),
+                Err(e) => {
+                    warn!("WiFi scan failed: {e:?}");
+                    Err(NetworkError::Wifi(e))
+                }
+            };
+            SCAN_RESULT.signal(result);
+        }
+        NetworkCommand::Connect(credentials) => {
+            info!("Switching WiFi to {}", credentials.ssid);
+            CREDENTIALS.lock(|inner| inner.borrow_mut().replace(credentials));
+            // Restarting picks the new network up with the config
+            if let Err(e) = controller.stop_async().await {
+                warn!("Failed to stop WiFi: {e:?}");
             }
         }
     }
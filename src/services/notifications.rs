@@ -0,0 +1,133 @@
+use core::cell::RefCell;
+
+use alloc::collections::VecDeque;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use embassy_sync::blocking_mutex::{raw::CriticalSectionRawMutex, Mutex};
+use embassy_time::Instant;
+
+use crate::i18n::{self, Msg};
+use crate::ui::menu::ScreenFactory;
+
+// Oldest notifications are dropped beyond this
+const HISTORY_LEN: usize = 16;
+
+static NOTIFICATIONS: Mutex<CriticalSectionRawMutex, RefCell<Inbox>> =
+    Mutex::new(RefCell::new(Inbox {
+        history: VecDeque::new(),
+        next_id: 1,
+        unread: 0,
+    }));
+
+struct Inbox {
+    history: VecDeque<Notification>,
+    next_id: u32,
+    unread: usize,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+/// A message for the user, shown as a toast and kept in the notification list.
+#[derive(Clone)]
+pub struct Notification {
+    /// Increases with every posted notification, 0 until posted.
+    pub id: u32,
+    pub severity: Severity,
+    pub text: String,
+    /// Screen opened when the notification is selected in the list.
+    pub action: Option<ScreenFactory>,
+    pub posted_at: Instant,
+}
+
+impl Notification {
+    pub fn new(severity: Severity, text: impl Into<String>) -> Self {
+        Self {
+            id: 0,
+            severity,
+            text: text.into(),
+            action: None,
+            posted_at: Instant::now(),
+        }
+    }
+
+    /// A warning that `what` failed, with the reason, e.g. "Time sync failed: timed out".
+    pub fn failed(what: Msg, reason: Msg) -> Self {
+        let text = format!("{}: {}", i18n::tr(what), i18n::tr(reason));
+        Self::new(Severity::Warning, text)
+    }
+
+    pub fn with_action(mut self, action: ScreenFactory) -> Self {
+        self.action = Some(action);
+        self
+    }
+}
+
+/// Messages from background tasks to the user.
+pub struct NotificationService;
+
+impl NotificationService {
+    /// Show a notification. Can be called from any task.
+    pub fn post(mut notification: Notification) {
+        NOTIFICATIONS.lock(|inbox| {
+            let mut inbox = inbox.borrow_mut();
+            notification.id = inbox.next_id;
+            notification.posted_at = Instant::now();
+            inbox.next_id += 1;
+            inbox.unread = (inbox.unread + 1).min(HISTORY_LEN);
+            if inbox.history.len() == HISTORY_LEN {
+                inbox.history.pop_front();
+            }
+            inbox.history.push_back(notification);
+        });
+    }
+
+    pub fn info(text: impl Into<String>) {
+        Self::post(Notification::new(Severity::Info, text));
+    }
+
+    pub fn warning(text: impl Into<String>) {
+        Self::post(Notification::new(Severity::Warning, text));
+    }
+
+    pub fn error(text: impl Into<String>) {
+        Self::post(Notification::new(Severity::Error, text));
+    }
+
+    /// See `Notification::failed`.
+    pub fn failed(what: Msg, reason: Msg) {
+        Self::post(Notification::failed(what, reason));
+    }
+
+    /// The most recent notification.
+    pub fn latest() -> Option<Notification> {
+        NOTIFICATIONS.lock(|inbox| inbox.borrow().history.back().cloned())
+    }
+
+    /// Kept notifications, newest first.
+    pub fn history() -> Vec<Notification> {
+        NOTIFICATIONS.lock(|inbox| inbox.borrow().history.iter().rev().cloned().collect())
+    }
+
+    /// Notifications posted since the list was last opened.
+    pub fn unread() -> usize {
+        NOTIFICATIONS.lock(|inbox| inbox.borrow().unread)
+    }
+
+    pub fn mark_read() {
+        NOTIFICATIONS.lock(|inbox| inbox.borrow_mut().unread = 0);
+    }
+
+    pub fn clear() {
+        NOTIFICATIONS.lock(|inbox| {
+            let mut inbox = inbox.borrow_mut();
+            inbox.history.clear();
+            inbox.unread = 0;
+        });
+    }
+}
@@ -1,17 +1,30 @@
 // use alloc::vec;
 use core::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+use embassy_futures::select::select;
 use embassy_net::udp::UdpSocket;
 use embassy_net::{dns, IpAddress};
-use embassy_time::{Duration, Timer};
-use esp_println::println;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::signal::Signal;
+use embassy_time::{with_timeout, Duration, Timer};
+use log::warn;
 use smoltcp::socket::udp;
 use sntpc::{NtpContext, NtpTimestampGenerator};
 use sntpc_net_embassy::UdpSocketWrapper;
 
-use crate::services::{ClockService, NetworkService};
+use crate::error::NtpError;
+use crate::i18n::{self, Msg};
+use crate::services::diagnostics::{monitor, Task};
+use crate::services::supervisor::Backoff;
+use crate::services::{ClockService, NetworkService, NotificationService};
 
 const SYNC_INTERVAL_SECS: u64 = 3600; // 1 hour
 const USEC_IN_SEC: u64 = 1_000_000;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+// First retry after a failed sync, doubling up to the regular interval
+const RETRY_MIN: Duration = Duration::from_secs(30);
+
+static SYNC_REQUEST: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+static SYNC_RESULT: Signal<CriticalSectionRawMutex, Result<(), NtpError>> = Signal::new();
 
 // Timestamp generator for sntpc
 #[derive(Copy, Clone)]
@@ -20,36 +33,66 @@ struct TimestampGen;
 impl NtpTimestampGenerator for TimestampGen {
     fn init(&mut self) {}
 
+    // The server only echoes these back, so zero is fine while there is no clock
     fn timestamp_sec(&self) -> u64 {
-        ClockService::get_current_time().as_second() as u64
+        ClockService::get_current_time().map_or(0, |now| now.as_second() as u64)
     }
 
     fn timestamp_subsec_micros(&self) -> u32 {
-        (ClockService::get_current_time().subsec_nanosecond() / 1000) as u32
+        ClockService::get_current_time().map_or(0, |now| (now.subsec_nanosecond() / 1000) as u32)
     }
 }
 
 #[embassy_executor::task]
 pub async fn ntp_task(ntp_server: &'static str) {
-    loop {
-        if let Err(e) = sync_time(ntp_server).await {
-            println!("NTP sync failed: {:?}", e);
-        }
+    monitor(Task::Ntp, run(ntp_server)).await
+}
 
-        Timer::after(Duration::from_secs(SYNC_INTERVAL_SECS)).await;
+async fn run(ntp_server: &'static str) {
+    let interval = Duration::from_secs(SYNC_INTERVAL_SECS);
+    let mut backoff = Backoff::new(RETRY_MIN, interval);
+    // Tell the user once per outage, not on every retry
+    let mut failure_notified = false;
+
+    loop {
+        let result = sync_time(ntp_server).await;
+        let delay = match &result {
+            Ok(()) => {
+                backoff.reset();
+                failure_notified = false;
+                interval
+            }
+            Err(e) => {
+                warn!("NTP sync failed: {}", e);
+                if !failure_notified {
+                    NotificationService::failed(Msg::NotifyClockSyncFailed, e.msg());
+                    failure_notified = true;
+                }
+                backoff.next_delay()
+            }
+        };
+        SYNC_RESULT.signal(result);
+
+        select(Timer::after(delay), SYNC_REQUEST.wait()).await;
     }
 }
 
-async fn sync_time(ntp_server: &'static str) -> Result<(), &'static str> {
+/// Sync the clock now instead of waiting for the next hourly sync.
+pub async fn sync_now() -> Result<(), NtpError> {
+    SYNC_RESULT.reset();
+    SYNC_REQUEST.signal(());
+    with_timeout(REQUEST_TIMEOUT, SYNC_RESULT.wait())
+        .await
+        .map_err(|_| NtpError::Timeout)?
+}
+
+async fn sync_time(ntp_server: &'static str) -> Result<(), NtpError> {
     let stack = NetworkService::wait_for_ip().await;
 
     // Resolve the ip
-    let addrs = stack
-        .dns_query(ntp_server, dns::DnsQueryType::A)
-        .await
-        .map_err(|_| "DNS failed")?;
+    let addrs = stack.dns_query(ntp_server, dns::DnsQueryType::A).await?;
 
-    let ntp_address = addrs.first().copied().ok_or("No DNS results")?;
+    let ntp_address = addrs.first().copied().ok_or(NtpError::NoAddress)?;
 
     let ntp_ip = match ntp_address {
         IpAddress::Ipv4(addr) => Ipv4Addr::from(addr.octets()),
@@ -70,7 +113,7 @@ async fn sync_time(ntp_server: &'static str) -> Result<(), &'static str> {
     );
 
     // 0 means stack picks ephemeral port
-    socket.bind(0).map_err(|_| "Bind failed")?;
+    socket.bind(0)?;
 
     // Get time via SNTP
     let socket_wrapper = UdpSocketWrapper::new(socket);
@@ -81,14 +124,17 @@ async fn sync_time(ntp_server: &'static str) -> Result<(), &'static str> {
         &socket_wrapper,
         context,
     )
-    .await
-    .map_err(|_| "SNTP request failed")?;
+    .await?;
 
     // Convert NTP time to microseconds and set RTC
     let timestamp_us =
         (result.sec() as u64 * USEC_IN_SEC) + ((result.sec_fraction() as u64 * USEC_IN_SEC) >> 32);
 
-    ClockService::set_current_time(timestamp_us);
+    let first_sync = !ClockService::is_synced();
+    ClockService::set_current_time(timestamp_us).ok_or(NtpError::NoClock)?;
+    if first_sync {
+        NotificationService::info(i18n::tr(Msg::NotifyClockSynced));
+    }
 
     Ok(())
 }
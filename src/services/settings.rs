@@ -0,0 +1,256 @@
+use core::cell::RefCell;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use embassy_sync::blocking_mutex::{raw::CriticalSectionRawMutex, Mutex};
+use embassy_sync::signal::Signal;
+use embassy_time::{with_timeout, Duration};
+use log::{info, warn};
+
+use crate::display::DisplayRotation;
+use crate::i18n::{Lang, Msg};
+use crate::services::diagnostics::{monitor, Task};
+use crate::services::storage::{Record, StorageService};
+use crate::services::NotificationService;
+
+const RECORD_SIZE: usize = 64;
+const HEADER_SIZE: usize = 5;
+const MAGIC: [u8; 4] = *b"LLKS";
+
+/// Lowest brightness selectable in settings, so the screen never goes fully dark by accident.
+pub const MIN_BRIGHTNESS: u8 = 10;
+
+// Changes are written once they have settled for this long, so stepping through
+// a picker costs one flash write instead of one per step
+const SAVE_DELAY: Duration = Duration::from_secs(2);
+
+static SETTINGS: Mutex<CriticalSectionRawMutex, RefCell<Settings>> =
+    Mutex::new(RefCell::new(Settings::DEFAULT));
+
+// Set when the settings in RAM differ from the ones in flash
+static DIRTY: AtomicBool = AtomicBool::new(false);
+static CHANGED: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+
+/// User preferences, persisted to flash.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Settings {
+    /// Show the splash screen while subsystems start.
+    pub boot_splash: bool,
+    /// Play the startup melody at power-on.
+    pub startup_sound: bool,
+    /// Idle time before the backlight dims, 0 disables dimming.
+    pub dim_timeout_secs: u16,
+    /// Idle time before the panel is switched off, 0 keeps it on.
+    pub screen_off_timeout_secs: u16,
+    /// What to do once the panel is off.
+    pub sleep_mode: SleepMode,
+    /// Backlight brightness in perceived percent.
+    pub brightness: u8,
+    /// Display orientation, buttons are remapped to follow it.
+    pub rotation: DisplayRotation,
+    /// Index into `ui::theme::THEMES`.
+    pub theme: u8,
+    /// UI language.
+    pub language: Lang,
+    /// Trade smoothness for battery life, e.g. no screen animations.
+    pub power_saving: bool,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum SleepMode {
+    /// Keep running with the panel off.
+    Off = 0,
+    /// Light sleep until a button is pressed. Wi-Fi disconnects while asleep.
+    Light = 1,
+    /// Deep sleep, pressing A reboots the device.
+    Deep = 2,
+}
+
+impl SleepMode {
+    fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(SleepMode::Off),
+            1 => Some(SleepMode::Light),
+            2 => Some(SleepMode::Deep),
+            _ => None,
+        }
+    }
+}
+
+impl Settings {
+    pub const DEFAULT: Self = Self {
+        boot_splash: true,
+        startup_sound: true,
+        dim_timeout_secs: 30,
+        screen_off_timeout_secs: 60,
+        sleep_mode: SleepMode::Off,
+        brightness: 80,
+        rotation: DisplayRotation::DEFAULT,
+        theme: 0,
+        language: Lang::En,
+        power_saving: false,
+    };
+
+    /// Serialize into a flash record: magic, payload length, payload, checksum.
+    /// New fields are only ever appended to the payload so older records stay readable.
+    fn encode(&self) -> [u8; RECORD_SIZE] {
+        let dim = self.dim_timeout_secs.to_le_bytes();
+        let screen_off = self.screen_off_timeout_secs.to_le_bytes();
+        let payload = [
+            self.boot_splash as u8,
+            self.startup_sound as u8,
+            dim[0],
+            dim[1],
+            screen_off[0],
+            screen_off[1],
+            self.sleep_mode as u8,
+            self.brightness,
+            self.rotation as u8,
+            self.theme,
+            self.language as u8,
+            self.power_saving as u8,
+        ];
+
+        let mut record = [0xFF; RECORD_SIZE];
+        record[..MAGIC.len()].copy_from_slice(&MAGIC);
+        record[MAGIC.len()] = payload.len() as u8;
+        record[HEADER_SIZE..HEADER_SIZE + payload.len()].copy_from_slice(&payload);
+        record[HEADER_SIZE + payload.len()] = checksum(&payload);
+        record
+    }
+
+    /// Parse a flash record, falling back to defaults for fields it doesn't contain.
+    fn decode(record: &[u8; RECORD_SIZE]) -> Option<Self> {
+        if record[..MAGIC.len()] != MAGIC {
+            return None;
+        }
+        let len = record[MAGIC.len()] as usize;
+        if HEADER_SIZE + len >= RECORD_SIZE {
+            return None;
+        }
+        let payload = &record[HEADER_SIZE..HEADER_SIZE + len];
+        if record[HEADER_SIZE + len] != checksum(payload) {
+            return None;
+        }
+
+        let mut settings = Self::DEFAULT;
+        let field = |idx: usize| payload.get(idx).copied();
+        let field_u16 = |idx: usize| Some(u16::from_le_bytes([field(idx)?, field(idx + 1)?]));
+        if let Some(value) = field(0) {
+            settings.boot_splash = value != 0;
+        }
+        if let Some(value) = field(1) {
+            settings.startup_sound = value != 0;
+        }
+        if let Some(value) = field_u16(2) {
+            settings.dim_timeout_secs = value;
+        }
+        if let Some(value) = field_u16(4) {
+            settings.screen_off_timeout_secs = value;
+        }
+        if let Some(mode) = field(6).and_then(SleepMode::from_u8) {
+            settings.sleep_mode = mode;
+        }
+        if let Some(value) = field(7) {
+            settings.brightness = value.clamp(MIN_BRIGHTNESS, 100);
+        }
+        if let Some(rotation) = field(8).and_then(DisplayRotation::from_u8) {
+            settings.rotation = rotation;
+        }
+        if let Some(value) = field(9) {
+            settings.theme = value;
+        }
+        if let Some(language) = field(10).and_then(Lang::from_u8) {
+            settings.language = language;
+        }
+        if let Some(value) = field(11) {
+            settings.power_saving = value != 0;
+        }
+        Some(settings)
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+fn checksum(payload: &[u8]) -> u8 {
+    payload
+        .iter()
+        .fold(0xA5u8, |acc, byte| acc.rotate_left(1) ^ byte)
+}
+
+pub struct SettingsService;
+
+impl SettingsService {
+    /// Load settings from flash. Missing or corrupted records fall back to defaults.
+    /// Needs `StorageService` initialized.
+    pub fn init() {
+        let mut record = [0u8; RECORD_SIZE];
+
+        let settings = match StorageService::read(Record::Settings, &mut record) {
+            Ok(()) => Settings::decode(&record).unwrap_or_else(|| {
+                info!("No stored settings, using defaults");
+                Settings::DEFAULT
+            }),
+            Err(e) => {
+                warn!("Failed to read settings: {}", e);
+                Settings::DEFAULT
+            }
+        };
+
+        SETTINGS.lock(|inner| *inner.borrow_mut() = settings);
+    }
+
+    pub fn get() -> Settings {
+        SETTINGS.lock(|inner| *inner.borrow())
+    }
+
+    /// Modify settings. They apply right away and are written to flash by
+    /// `settings_task` once no more changes come in.
+    pub fn update(f: impl FnOnce(&mut Settings)) {
+        let changed = SETTINGS.lock(|inner| {
+            let mut settings = inner.borrow_mut();
+            let old = *settings;
+            f(&mut settings);
+            old != *settings
+        });
+
+        if changed {
+            DIRTY.store(true, Ordering::Relaxed);
+            CHANGED.signal(());
+        }
+    }
+
+    /// Write pending changes to flash now, e.g. before a reboot.
+    pub fn flush() {
+        if DIRTY.swap(false, Ordering::Relaxed) {
+            Self::save(&Self::get());
+        }
+    }
+
+    fn save(settings: &Settings) {
+        if let Err(e) = StorageService::write(Record::Settings, &settings.encode()) {
+            warn!("Failed to save settings: {}", e);
+            // Still applied, but back to the old ones after a reboot
+            NotificationService::failed(Msg::NotifySettingsNotSaved, e.msg());
+        }
+    }
+}
+
+/// Writes changed settings to flash, see `SettingsService::update`.
+#[embassy_executor::task]
+pub async fn settings_task() {
+    monitor(Task::Settings, run()).await
+}
+
+async fn run() {
+    loop {
+        CHANGED.wait().await;
+        // Every further change restarts the delay
+        while with_timeout(SAVE_DELAY, CHANGED.wait()).await.is_ok() {}
+        SettingsService::flush();
+    }
+}
@@ -0,0 +1,61 @@
+//! Raw access to the flash records the firmware keeps, one sector each.
+
+use core::cell::RefCell;
+
+use embassy_sync::blocking_mutex::{raw::CriticalSectionRawMutex, Mutex};
+use embedded_storage::{ReadStorage, Storage};
+use esp_hal::peripherals::FLASH;
+use esp_storage::{FlashStorage, FlashStorageError};
+
+use crate::error::StorageError;
+
+// The firmware doesn't use ESP-IDF NVS, so its partition is free for our own records
+const NVS_OFFSET: u32 = 0x9000;
+// Every record has a sector to itself, so rewriting one never erases another
+const SECTOR_SIZE: u32 = 0x1000;
+
+static STORAGE: Mutex<CriticalSectionRawMutex, RefCell<Option<FlashStorage<'static>>>> =
+    Mutex::new(RefCell::new(None));
+
+/// The records kept in flash.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Record {
+    Settings = 0,
+    /// The last crash report, see `CrashService`.
+    Crash = 1,
+}
+
+impl Record {
+    fn offset(self) -> u32 {
+        NVS_OFFSET + self as u32 * SECTOR_SIZE
+    }
+}
+
+pub struct StorageService;
+
+impl StorageService {
+    pub fn init(flash: FLASH<'static>) {
+        STORAGE.lock(|inner| inner.borrow_mut().replace(FlashStorage::new(flash)));
+    }
+
+    /// Fill `buf` from the start of `record`.
+    pub fn read(record: Record, buf: &mut [u8]) -> Result<(), StorageError> {
+        Self::with(|storage| storage.read(record.offset(), buf))
+    }
+
+    /// Overwrite the start of `record` with `data`.
+    pub fn write(record: Record, data: &[u8]) -> Result<(), StorageError> {
+        Self::with(|storage| storage.write(record.offset(), data))
+    }
+
+    fn with(
+        f: impl FnOnce(&mut FlashStorage<'static>) -> Result<(), FlashStorageError>,
+    ) -> Result<(), StorageError> {
+        STORAGE.lock(|inner| {
+            let mut storage = inner.borrow_mut();
+            let storage = storage.as_mut().ok_or(StorageError::NotInitialized)?;
+            Ok(f(storage)?)
+        })
+    }
+}
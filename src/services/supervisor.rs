@@ -0,0 +1,128 @@
+//! Keeps the device alive. The hardware watchdog is only fed while every critical task
+//! keeps checking in, and services that fail retry with a growing delay instead of
+//! taking the device down.
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use embassy_time::{Duration, Timer};
+use esp_hal::peripherals::TIMG1;
+use esp_hal::rtc_cntl::SocResetReason;
+use esp_hal::timer::timg::{MwdtStage, Wdt};
+use log::{error, info, warn};
+
+use crate::services::diagnostics::{monitor, Task, TASK_COUNT};
+use crate::services::CrashService;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+/// Checks a critical task may miss before it counts as stuck. Generous, as the UI is
+/// legitimately busy for a while during a screenshot.
+const STALL_CHECKS: u8 = 30;
+/// Reset by the watchdog once it goes this long without food.
+const WATCHDOG_TIMEOUT_SECS: u64 = 10;
+
+/// Tasks the device is useless without. A stuck one resets the device.
+const CRITICAL: [Task; 3] = [Task::Ui, Task::Input, Task::Tick];
+
+static HEARTBEATS: [AtomicU32; TASK_COUNT] = [const { AtomicU32::new(0) }; TASK_COUNT];
+
+/// Tell the supervisor `task` is making progress. Critical tasks call it on every loop.
+pub fn heartbeat(task: Task) {
+    HEARTBEATS[task as usize].fetch_add(1, Ordering::Relaxed);
+}
+
+/// Delay between retries of a failing service. Doubles with every failure up to `max`,
+/// and starts over after a success.
+pub struct Backoff {
+    initial: Duration,
+    max: Duration,
+    next: Duration,
+}
+
+impl Backoff {
+    pub const fn new(initial: Duration, max: Duration) -> Self {
+        Self {
+            initial,
+            max,
+            next: initial,
+        }
+    }
+
+    /// How long to wait before the next attempt.
+    pub fn next_delay(&mut self) -> Duration {
+        let delay = self.next;
+        self.next = Duration::from_ticks((delay.as_ticks() * 2).min(self.max.as_ticks()));
+        delay
+    }
+
+    pub fn reset(&mut self) {
+        self.next = self.initial;
+    }
+}
+
+/// Feeds the hardware watchdog while the critical tasks are alive. Spawn once the
+/// critical tasks are running.
+#[embassy_executor::task]
+pub async fn supervisor_task(watchdog: Wdt<TIMG1<'static>>) {
+    monitor(Task::Supervisor, run(watchdog)).await
+}
+
+async fn run(mut watchdog: Wdt<TIMG1<'static>>) {
+    match esp_hal::system::reset_reason() {
+        Some(
+            reason @ (SocResetReason::CoreMwdt1
+            | SocResetReason::CpuMwdt1
+            | SocResetReason::CoreRtcWdt
+            | SocResetReason::SysRtcWdt),
+        ) => warn!("Reset by a watchdog: {:?}", reason),
+        reason => info!("Reset reason: {:?}", reason),
+    }
+
+    watchdog.set_timeout(
+        MwdtStage::Stage0,
+        esp_hal::time::Duration::from_secs(WATCHDOG_TIMEOUT_SECS),
+    );
+    watchdog.enable();
+
+    let mut seen = [0u32; TASK_COUNT];
+    let mut silent = [0u8; TASK_COUNT];
+    let mut starving = false;
+
+    loop {
+        // Counting missed checks instead of comparing timestamps keeps light sleep,
+        // when nothing runs, from looking like a stall
+        let mut stuck = None;
+        for task in CRITICAL {
+            let idx = task as usize;
+            let beats = HEARTBEATS[idx].load(Ordering::Relaxed);
+            if beats != seen[idx] {
+                seen[idx] = beats;
+                silent[idx] = 0;
+            } else {
+                silent[idx] = silent[idx].saturating_add(1);
+            }
+            if silent[idx] >= STALL_CHECKS {
+                stuck = Some(task);
+            }
+        }
+
+        match stuck {
+            // The report is written by then, so the reset goes ahead even if the task recovers
+            _ if starving => {}
+            None => watchdog.feed(),
+            Some(task) => {
+                error!(
+                    "{} task stuck, watchdog resets in {} s",
+                    task.name(),
+                    WATCHDOG_TIMEOUT_SECS
+                );
+                CrashService::record(format_args!(
+                    "watchdog: {} task stopped responding",
+                    task.name()
+                ));
+                starving = true;
+            }
+        }
+
+        Timer::after(CHECK_INTERVAL).await;
+    }
+}
@@ -0,0 +1,80 @@
+use embassy_time::{Duration, Instant};
+use esp_alloc::MemoryCapability;
+use esp_hal::efuse::Efuse;
+use esp_hal::rtc_cntl::SocResetReason;
+
+/// Firmware version from `Cargo.toml`.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Commit the firmware was built from, set by `build.rs`.
+pub const GIT_HASH: &str = env!("LILKA_GIT_HASH");
+
+/// Heap usage in bytes, internal RAM and PSRAM together.
+#[derive(Copy, Clone, Debug)]
+pub struct HeapUsage {
+    pub used: usize,
+    pub free: usize,
+    /// The part of `free` in internal RAM.
+    pub internal_free: usize,
+}
+
+/// Why the chip last started, grouped from the reset reasons of the SoC.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ResetCause {
+    PowerOn,
+    Software,
+    DeepSleep,
+    Watchdog,
+    BrownOut,
+    /// Reset over USB, e.g. by the flasher.
+    Usb,
+    Unknown,
+}
+
+/// Facts about the chip and the running firmware.
+pub struct SystemService;
+
+impl SystemService {
+    /// Silicon revision as (major, minor).
+    pub fn chip_revision() -> (u8, u8) {
+        (Efuse::major_chip_version(), Efuse::minor_chip_version())
+    }
+
+    /// Base MAC address, also used by the Wi-Fi station.
+    pub fn mac_address() -> [u8; 6] {
+        Efuse::mac_address()
+    }
+
+    /// Time since boot. Light sleep counts, deep sleep restarts it.
+    pub fn uptime() -> Duration {
+        Duration::from_ticks(Instant::now().as_ticks())
+    }
+
+    pub fn heap() -> HeapUsage {
+        HeapUsage {
+            used: esp_alloc::HEAP.used(),
+            free: esp_alloc::HEAP.free(),
+            internal_free: esp_alloc::HEAP.free_caps(MemoryCapability::Internal.into()),
+        }
+    }
+
+    pub fn reset_cause() -> ResetCause {
+        match esp_hal::system::reset_reason() {
+            Some(SocResetReason::ChipPowerOn) => ResetCause::PowerOn,
+            Some(SocResetReason::CoreSw | SocResetReason::CpuSw) => ResetCause::Software,
+            Some(SocResetReason::CoreDeepSleep) => ResetCause::DeepSleep,
+            Some(
+                SocResetReason::CoreMwdt0
+                | SocResetReason::CoreMwdt1
+                | SocResetReason::CoreRtcWdt
+                | SocResetReason::CpuMwdt0
+                | SocResetReason::CpuMwdt1
+                | SocResetReason::CpuRtcWdt
+                | SocResetReason::SysRtcWdt
+                | SocResetReason::SysSuperWdt,
+            ) => ResetCause::Watchdog,
+            Some(SocResetReason::SysBrownOut) => ResetCause::BrownOut,
+            Some(SocResetReason::CoreUsbUart | SocResetReason::CoreUsbJtag) => ResetCause::Usb,
+            _ => ResetCause::Unknown,
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use core::fmt::{self, Write};
+use core::net::Ipv4Addr;
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use embedded_io_async::Read;
+use esp_hal::usb_serial_jtag::UsbSerialJtagRx;
+use esp_hal::Async;
+use esp_println::{print, println};
+use jiff::Timestamp;
+use lilka_logic::shell::dispatch::{self, Backend, OpenError};
+use lilka_logic::shell::line::{Edit, LineBuffer};
+use lilka_logic::shell::parser::{self, ParseError};
+use lilka_logic::shell::settings::Key;
+use log::{warn, Level};
+
+use crate::error::{AudioError, NetworkError, NtpError};
+use crate::music::LIBRARY;
+use crate::services::diagnostics::{monitor, Report, Task};
+use crate::services::{
+    ntp, AccessPoint, AudioService, ClockService, CrashReport, CrashService, Credentials,
+    DiagnosticsService, LogRecord, LogService, NetworkService, Settings, SettingsService,
+};
+use crate::shell::settings::KEYS;
+use crate::state::{UIEvent, UI_CHANNEL};
+use crate::ui::menu::ScreenFactory;
+use crate::ui::screens::{
+    CrashScreen, DiagnosticsScreen, InfoScreen, LogScreen, MusicScreen, NotificationsScreen,
+    SettingsScreen, WifiScreen,
+};
+
+const LINE_SIZE: usize = 128;
+const PROMPT: &str = "> ";
+
+/// Screens `screen push` can open, by name.
+const SCREENS: [(&str, ScreenFactory); 8] = [
+    ("info", |bounds| Box::new(InfoScreen::new(bounds))),
+    ("wifi", |bounds| Box::new(WifiScreen::new(bounds))),
+    ("music", |bounds| Box::new(MusicScreen::new(bounds))),
+    ("settings", |bounds| Box::new(SettingsScreen::new(bounds))),
+    ("notifications", |bounds| {
+        Box::new(NotificationsScreen::new(bounds))
+    }),
+    ("diagnostics", |bounds| {
+        Box::new(DiagnosticsScreen::new(bounds))
+    }),
+    ("log", |bounds| Box::new(LogScreen::new(bounds))),
+    ("crash", |bounds| Box::new(CrashScreen::new(bounds))),
+];
+
+const SCREEN_NAMES: [&str; SCREENS.len()] = {
+    let mut names = [""; SCREENS.len()];
+    let mut idx = 0;
+    while idx < SCREENS.len() {
+        names[idx] = SCREENS[idx].0;
+        idx += 1;
+    }
+    names
+};
+
+/// Console output, shared with the log over `esp_println`.
+struct Console;
+
+impl Write for Console {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        print!("{}", s);
+        Ok(())
+    }
+}
+
+/// The commands acting on the running services.
+struct Device;
+
+impl Backend for Device {
+    type NetworkError = NetworkError;
+    type NtpError = NtpError;
+    type AudioError = AudioError;
+    type Settings = Settings;
+
+    async fn wifi_scan(&mut self) -> Result<Vec<AccessPoint>, NetworkError> {
+        NetworkService::scan().await
+    }
+
+    fn wifi_connect(&mut self, credentials: Credentials) -> Result<(), NetworkError> {
+        NetworkService::connect(credentials)
+    }
+
+    fn wifi_ssid(&self) -> heapless::String<32> {
+        NetworkService::ssid()
+    }
+
+    fn wifi_address(&self) -> Option<Ipv4Addr> {
+        NetworkService::stack()
+            .and_then(|stack| stack.config_v4())
+            .map(|config| config.address.address())
+    }
+
+    fn now(&self) -> Option<Timestamp> {
+        ClockService::get_current_time()
+    }
+
+    fn clock_synced(&self) -> bool {
+        ClockService::is_synced()
+    }
+
+    async fn ntp_sync(&mut self) -> Result<(), NtpError> {
+        ntp::sync_now().await
+    }
+
+    fn song(&self, index: usize) -> Option<&'static str> {
+        LIBRARY.get(index).map(|entry| entry.name)
+    }
+
+    fn play(&mut self, index: usize) -> Result<(), AudioError> {
+        AudioService::play(index)
+    }
+
+    fn stop(&mut self) -> Result<(), AudioError> {
+        AudioService::stop()
+    }
+
+    fn settings(&self) -> Settings {
+        SettingsService::get()
+    }
+
+    fn set_settings(&mut self, settings: Settings) {
+        SettingsService::update(|current| *current = settings);
+    }
+
+    fn setting_keys(&self) -> &'static [Key<Settings>] {
+        &KEYS
+    }
+
+    fn screens(&self) -> &'static [&'static str] {
+        &SCREEN_NAMES
+    }
+
+    fn open_screen(&mut self, name: &str) -> Result<(), OpenError> {
+        let (_, factory) = SCREENS
+            .iter()
+            .find(|(screen, _)| *screen == name)
+            .ok_or(OpenError::Unknown)?;
+        UI_CHANNEL
+            .try_send(UIEvent::Open(*factory))
+            .map_err(|_| OpenError::Busy)
+    }
+
+    fn diagnostics(&self) -> Report {
+        DiagnosticsService::report()
+    }
+
+    fn log(&self, level: Level) -> Vec<LogRecord> {
+        LogService::records(level)
+    }
+
+    fn last_crash(&self) -> Option<CrashReport> {
+        CrashService::last()
+    }
+
+    fn clear_crash(&mut self) {
+        CrashService::clear();
+    }
+
+    fn reboot(&mut self) -> ! {
+        SettingsService::flush();
+        esp_hal::system::software_reset()
+    }
+}
+
+/// Line-oriented command shell on the USB serial port. Type `help` for the commands.
+#[embassy_executor::task]
+pub async fn shell_task(rx: UsbSerialJtagRx<'static, Async>) {
+    monitor(Task::Shell, run(rx)).await
+}
+
+async fn run(mut rx: UsbSerialJtagRx<'static, Async>) {
+    let mut line = LineBuffer::<LINE_SIZE>::new();
+    let mut buf = [0u8; 64];
+    print!("{}", PROMPT);
+
+    loop {
+        let len = match rx.read(&mut buf).await {
+            Ok(len) => len,
+            Err(e) => {
+                warn!("Console read failed: {:?}", e);
+                continue;
+            }
+        };
+        for &byte in &buf[..len] {
+            match line.push(byte) {
+                // Terminals don't echo by themselves. A multi-byte character
+                // is echoed once its last byte is in
+                Edit::Inserted => {
+                    if let Some(c) = line.line().ok().and_then(|text| text.chars().next_back()) {
+                        print!("{}", c);
+                    }
+                }
+                Edit::Erased => print!("\x08 \x08"),
+                Edit::Complete => {
+                    println!();
+                    run_line(&line).await;
+                    line.clear();
+                    print!("{}", PROMPT);
+                }
+                Edit::Ignored => {}
+            }
+        }
+    }
+}
+
+async fn run_line<const N: usize>(line: &LineBuffer<N>) {
+    let mut console = Console;
+    let Ok(text) = line.line() else {
+        println!("error: not valid UTF-8");
+        return;
+    };
+    let result = match parser::parse(text) {
+        Ok(command) => dispatch::execute(command, &mut Device, &mut console).await,
+        Err(ParseError::Empty) => Ok(()),
+        Err(e) => writeln!(console, "error: {}", e),
+    };
+    // Console never fails to write
+    result.unwrap();
+}
@@ -0,0 +1,10 @@
+//! Command shell on the USB serial port.
+//!
+//! The line editor, parser and dispatcher live in `lilka_logic::shell`, where they are
+//! tested on the host. `console` reads the port and implements their `Backend` with the
+//! services, `settings` names the settings for it.
+
+pub mod console;
+pub mod settings;
+
+pub use console::shell_task;
@@ -0,0 +1,148 @@
+//! Settings by name, as shown and typed on the console.
+
+use lilka_logic::shell::settings::{Key, Value};
+
+use crate::display::DisplayRotation;
+use crate::format;
+use crate::i18n::Lang;
+use crate::services::settings::MIN_BRIGHTNESS;
+use crate::services::{Settings, SleepMode};
+use crate::ui::theme::THEMES;
+
+pub static KEYS: [Key<Settings>; 10] = [
+    Key::new(
+        "boot_splash",
+        "on|off",
+        |s| flag(s.boot_splash),
+        |s, v| parse_flag(v).map(|value| s.boot_splash = value),
+    ),
+    Key::new(
+        "startup_sound",
+        "on|off",
+        |s| flag(s.startup_sound),
+        |s, v| parse_flag(v).map(|value| s.startup_sound = value),
+    ),
+    Key::new(
+        "dim_timeout",
+        "seconds, 0 for never",
+        |s| format!(24, "{}", s.dim_timeout_secs),
+        |s, v| v.parse().ok().map(|value| s.dim_timeout_secs = value),
+    ),
+    Key::new(
+        "screen_off_timeout",
+        "seconds, 0 for never",
+        |s| format!(24, "{}", s.screen_off_timeout_secs),
+        |s, v| {
+            v.parse()
+                .ok()
+                .map(|value| s.screen_off_timeout_secs = value)
+        },
+    ),
+    Key::new(
+        "sleep",
+        "off|light|deep",
+        |s| {
+            format!(
+                24,
+                "{}",
+                match s.sleep_mode {
+                    SleepMode::Off => "off",
+                    SleepMode::Light => "light",
+                    SleepMode::Deep => "deep",
+                }
+            )
+        },
+        |s, v| {
+            s.sleep_mode = match v {
+                "off" => SleepMode::Off,
+                "light" => SleepMode::Light,
+                "deep" => SleepMode::Deep,
+                _ => return None,
+            };
+            Some(())
+        },
+    ),
+    Key::new(
+        "brightness",
+        "percent, 10 to 100",
+        |s| format!(24, "{}", s.brightness),
+        |s, v| {
+            let value: u8 = v.parse().ok()?;
+            (MIN_BRIGHTNESS..=100)
+                .contains(&value)
+                .then(|| s.brightness = value)
+        },
+    ),
+    Key::new(
+        "rotation",
+        "0|90|180|270",
+        |s| format!(24, "{}", s.rotation.degrees()),
+        |s, v| {
+            let degrees: u16 = v.parse().ok()?;
+            if degrees % 90 != 0 {
+                return None;
+            }
+            DisplayRotation::from_u8((degrees / 90).try_into().ok()?)
+                .map(|rotation| s.rotation = rotation)
+        },
+    ),
+    Key::new(
+        "theme",
+        "theme name or number",
+        |s| {
+            let name = THEMES
+                .get(s.theme as usize)
+                .map_or("?", |theme| theme.name.text(Lang::En));
+            format!(24, "{}", name)
+        },
+        |s, v| {
+            let idx = match v.parse::<usize>() {
+                Ok(idx) => idx,
+                Err(_) => THEMES
+                    .iter()
+                    .position(|theme| theme.name.text(Lang::En).eq_ignore_ascii_case(v))?,
+            };
+            (idx < THEMES.len()).then(|| s.theme = idx as u8)
+        },
+    ),
+    Key::new(
+        "language",
+        "en|uk",
+        |s| {
+            format!(
+                24,
+                "{}",
+                match s.language {
+                    Lang::En => "en",
+                    Lang::Uk => "uk",
+                }
+            )
+        },
+        |s, v| {
+            s.language = match v {
+                "en" => Lang::En,
+                "uk" => Lang::Uk,
+                _ => return None,
+            };
+            Some(())
+        },
+    ),
+    Key::new(
+        "power_saving",
+        "on|off",
+        |s| flag(s.power_saving),
+        |s, v| parse_flag(v).map(|value| s.power_saving = value),
+    ),
+];
+
+fn flag(value: bool) -> Value {
+    format!(24, "{}", if value { "on" } else { "off" })
+}
+
+fn parse_flag(value: &str) -> Option<bool> {
+    match value {
+        "on" | "true" | "yes" | "1" => Some(true),
+        "off" | "false" | "no" | "0" => Some(false),
+        _ => None,
+    }
+}
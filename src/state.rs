@@ -1,5 +1,13 @@
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::channel::Channel;
+
+use crate::ui::menu::ScreenFactory;
+
 pub const UI_CHANNEL_SIZE: usize = 10;
 
+// Create a channel for UI events (buttons + ticks)
+pub static UI_CHANNEL: Channel<CriticalSectionRawMutex, UIEvent, UI_CHANNEL_SIZE> = Channel::new();
+
 // Define button events
 #[derive(Copy, Clone, Debug)]
 pub enum ButtonEvent {
@@ -18,4 +26,8 @@ pub enum ButtonEvent {
 pub enum UIEvent {
     Button(ButtonEvent),
     Tick,
+    /// Dump the current frame over serial.
+    Screenshot,
+    /// Open a screen on top of the current one, e.g. from the serial console.
+    Open(ScreenFactory),
 }
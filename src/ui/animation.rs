@@ -0,0 +1,100 @@
+//! Animated changes between screens.
+//!
+//! The animation runs between the frame on the panel and the fully drawn frame of the
+//! new screen. Intermediate frames are composed row by row from the compositor's two
+//! frames as they are sent, so no copies of them are made; only the area below the
+//! header moves. The ST7789 hardware scroll is not used: it scrolls along the panel's
+//! native rows, which is the horizontal axis in landscape, so the header would move
+//! along with the content.
+
+use embassy_time::{Duration, Instant, Timer};
+use embedded_graphics::pixelcolor::Rgb565;
+use embedded_graphics::prelude::RgbColor;
+use embedded_graphics::primitives::Rectangle;
+use log::warn;
+
+use crate::ui::compositor::Compositor;
+
+const FRAMES: u32 = 8;
+const FRAME_TIME: Duration = Duration::from_millis(20);
+
+/// How the content area changes from one screen to the next.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Animation {
+    /// Show the new screen at once.
+    None,
+    /// The new screen comes in from the right, used when opening a screen.
+    SlideLeft,
+    /// The new screen comes in from the left, used when going back.
+    SlideRight,
+    /// Cross-fade between the screens.
+    Fade,
+}
+
+/// Play `animation` in `area`, from what the panel shows to what was drawn into the
+/// compositor since the last flush. Ends with the new frame on the panel.
+pub async fn play(canvas: &mut Compositor, area: Rectangle, animation: Animation) {
+    if animation != Animation::None {
+        // The last frame is the new screen itself
+        for frame in 1..=FRAMES {
+            let start = Instant::now();
+            let progress = ease_out(frame * 256 / FRAMES);
+            let result = canvas
+                .flush_transition(&area, |from, to, row| {
+                    animation.compose_row(from, to, row, progress)
+                })
+                .await;
+            if let Err(e) = result {
+                warn!("Failed to flush frame: {}", e);
+            }
+            Timer::at(start + FRAME_TIME).await;
+        }
+        canvas.end_transition(&area);
+    }
+    flush(canvas).await;
+}
+
+async fn flush(canvas: &mut Compositor) {
+    if let Err(e) = canvas.flush().await {
+        warn!("Failed to flush frame: {}", e);
+    }
+}
+
+/// Fast start, slow end. `t` and the result are in 1/256.
+fn ease_out(t: u32) -> u32 {
+    let rest = 256 - t.min(256);
+    256 - rest * rest / 256
+}
+
+impl Animation {
+    /// One row of an intermediate frame, `progress` in 1/256.
+    fn compose_row(self, from: &[Rgb565], to: &[Rgb565], row: &mut [Rgb565], progress: u32) {
+        let width = row.len();
+        let shift = width * progress as usize / 256;
+        match self {
+            Animation::None => row.copy_from_slice(to),
+            Animation::SlideLeft => {
+                row[..width - shift].copy_from_slice(&from[shift..]);
+                row[width - shift..].copy_from_slice(&to[..shift]);
+            }
+            Animation::SlideRight => {
+                row[..shift].copy_from_slice(&to[width - shift..]);
+                row[shift..].copy_from_slice(&from[..width - shift]);
+            }
+            Animation::Fade => {
+                for ((pixel, from), to) in row.iter_mut().zip(from).zip(to) {
+                    *pixel = mix(*from, *to, progress);
+                }
+            }
+        }
+    }
+}
+
+fn mix(from: Rgb565, to: Rgb565, progress: u32) -> Rgb565 {
+    let channel = |a: u8, b: u8| ((a as u32 * (256 - progress) + b as u32 * progress) / 256) as u8;
+    Rgb565::new(
+        channel(from.r(), to.r()),
+        channel(from.g(), to.g()),
+        channel(from.b(), to.b()),
+    )
+}
@@ -0,0 +1,123 @@
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::*,
+    primitives::{PrimitiveStyle, Rectangle, RoundedRectangle},
+    text::{Alignment, Text},
+};
+use embedded_layout::prelude::*;
+
+use crate::i18n::{self, Msg};
+use crate::ui::theme::{self, UiTheme};
+
+/// Subsystems brought up while the splash is shown, in boot order.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BootStage {
+    Display,
+    Wifi,
+    Clock,
+    Done,
+}
+
+impl BootStage {
+    pub fn label(&self) -> Msg {
+        match self {
+            BootStage::Display => Msg::BootDisplay,
+            BootStage::Wifi => Msg::BootWifi,
+            BootStage::Clock => Msg::BootClock,
+            BootStage::Done => Msg::BootReady,
+        }
+    }
+
+    /// Boot progress in percent once this stage is reached.
+    fn progress(&self) -> u32 {
+        match self {
+            BootStage::Display => 25,
+            BootStage::Wifi => 50,
+            BootStage::Clock => 75,
+            BootStage::Done => 100,
+        }
+    }
+}
+
+/// Splash screen drawn directly on the display before the UI task takes over.
+pub struct BootSplash {
+    bounds: Rectangle,
+    theme: &'static UiTheme,
+}
+
+impl BootSplash {
+    pub fn new(display_area: Rectangle) -> Self {
+        Self {
+            bounds: display_area,
+            theme: theme::current(),
+        }
+    }
+
+    pub fn draw_logo<D>(&self, display: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        display.clear(self.theme.background)?;
+
+        let mut frame = Rectangle::new(Point::zero(), Size::new(140, 60)).align_to(
+            &self.bounds,
+            horizontal::Center,
+            vertical::Center,
+        );
+        frame.top_left.y -= 30;
+        RoundedRectangle::with_equal_corners(frame, Size::new(12, 12))
+            .into_styled(PrimitiveStyle::with_stroke(self.theme.accent, 3))
+            .draw(display)?;
+
+        Text::new("LILKA", Point::zero(), self.theme.accent_style())
+            .align_to(&frame, horizontal::Center, vertical::Center)
+            .draw(display)?;
+
+        Text::with_alignment(
+            concat!("lilka-rs v", env!("CARGO_PKG_VERSION")),
+            Point::new(
+                frame.center().x,
+                frame.top_left.y + frame.size.height as i32 + 30,
+            ),
+            self.theme.text_style(),
+            Alignment::Center,
+        )
+        .draw(display)?;
+
+        Ok(())
+    }
+
+    pub fn draw_stage<D>(&self, display: &mut D, stage: BootStage) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let bar = Rectangle::new(
+            Point::new(40, self.bounds.size.height as i32 - 60),
+            Size::new(self.bounds.size.width - 80, 12),
+        );
+        let label_area = Rectangle::new(
+            bar.top_left + Point::new(0, 16),
+            Size::new(bar.size.width, 24),
+        );
+
+        bar.into_styled(PrimitiveStyle::with_stroke(self.theme.accent, 1))
+            .draw(display)?;
+        Rectangle::new(
+            bar.top_left,
+            Size::new(bar.size.width * stage.progress() / 100, bar.size.height),
+        )
+        .into_styled(PrimitiveStyle::with_fill(self.theme.accent))
+        .draw(display)?;
+
+        display.fill_solid(&label_area, self.theme.background)?;
+        Text::new(
+            i18n::tr(stage.label()),
+            Point::zero(),
+            self.theme.text_style(),
+        )
+        .align_to(&label_area, horizontal::Center, vertical::Center)
+        .draw(display)?;
+
+        Ok(())
+    }
+}
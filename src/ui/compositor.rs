@@ -0,0 +1,436 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::convert::Infallible;
+use core::ops::Range;
+
+use embassy_time::{Duration, Instant};
+use embedded_graphics::{
+    pixelcolor::{IntoStorage, Rgb565},
+    prelude::{Dimensions, DrawTarget, OriginDimensions, Point, RgbColor, Size},
+    primitives::Rectangle,
+    Pixel,
+};
+use heapless::Vec as BoundedVec;
+use log::{debug, info};
+
+use crate::display::{DisplayRotation, LilkaDisplay, PanelInterface};
+use crate::error::DisplayError;
+
+// Damaged regions tracked separately before they get merged together
+const MAX_DAMAGE: usize = 8;
+
+// Frame time summary is logged at info level once per this many frames
+const REPORT_EVERY: u32 = 60;
+
+// Size of one DMA transfer; each is converted from the frame right before it is sent
+const TILE_BYTES: usize = 8192;
+
+/// Timing of the most recent frames, for diagnostics.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct FrameStats {
+    pub frames: u32,
+    /// Time spent drawing into the frame buffer.
+    pub last_render: Duration,
+    /// Time spent sending the frame to the panel.
+    pub last_flush: Duration,
+    pub max_flush: Duration,
+    /// Exponential moving average of render + flush.
+    pub avg_frame: Duration,
+    /// Bytes sent over SPI for the last frame.
+    pub last_bytes: usize,
+}
+
+/// Sits between screens and the panel: screens draw into an off-screen frame,
+/// and `flush` sends only the rectangles whose pixels actually changed.
+///
+/// Two frames are kept: `back` is what screens draw into, `front` mirrors what
+/// the panel currently shows. Screens can clear and redraw freely; pixels that
+/// end up unchanged are never sent over SPI. Changed regions are streamed to the
+/// panel tile by tile over DMA, so the UI task yields while the transfer runs.
+///
+/// Both frames are always allocated in PSRAM (about 130 KB each at 240x280).
+/// There is no tiled or direct-to-panel mode: damage tracking needs the full
+/// `front` copy, and the board has PSRAM to spare.
+pub struct Compositor {
+    display: LilkaDisplay,
+    panel: PanelInterface,
+    rotation: DisplayRotation,
+    size: Size,
+    back: Vec<Rgb565>,
+    front: Vec<Rgb565>,
+    damage: BoundedVec<Rectangle, MAX_DAMAGE>,
+    // The panel content is unknown until the first full flush
+    front_valid: bool,
+    tile: Vec<u8>,
+    // One row of a transition frame, composed right before it is sent
+    row: Vec<Rgb565>,
+    frame_start: Instant,
+    stats: FrameStats,
+}
+
+impl Compositor {
+    pub fn new(display: LilkaDisplay, panel: PanelInterface) -> Self {
+        let size = display.bounding_box().size;
+        let len = (size.width * size.height) as usize;
+
+        let mut compositor = Self {
+            display,
+            panel,
+            rotation: DisplayRotation::DEFAULT,
+            size,
+            back: vec![Rgb565::BLACK; len],
+            front: vec![Rgb565::BLACK; len],
+            damage: BoundedVec::new(),
+            front_valid: false,
+            tile: Vec::with_capacity(TILE_BYTES),
+            row: Vec::with_capacity(size.width.max(size.height) as usize),
+            frame_start: Instant::now(),
+            stats: FrameStats::default(),
+        };
+        compositor.add_damage(Rectangle::new(Point::zero(), size));
+        compositor
+    }
+
+    /// Direct access to the panel for commands that bypass the frame (sleep, wake, ...).
+    pub fn display_mut(&mut self) -> &mut LilkaDisplay {
+        &mut self.display
+    }
+
+    pub fn rotation(&self) -> DisplayRotation {
+        self.rotation
+    }
+
+    /// Rotate the panel. The frame is resized to the new orientation and cleared,
+    /// so everything has to be redrawn before the next flush.
+    pub fn set_rotation(&mut self, rotation: DisplayRotation) -> Result<(), DisplayError> {
+        self.display.set_orientation(rotation.orientation())?;
+        self.rotation = rotation;
+        self.size = self.display.bounding_box().size;
+
+        let len = (self.size.width * self.size.height) as usize;
+        self.back.clear();
+        self.back.resize(len, Rgb565::BLACK);
+        self.front.clear();
+        self.front.resize(len, Rgb565::BLACK);
+        self.damage.clear();
+        self.invalidate();
+        Ok(())
+    }
+
+    /// Force the whole frame to be sent on the next flush.
+    pub fn invalidate(&mut self) {
+        self.front_valid = false;
+        self.add_damage(Rectangle::new(Point::zero(), self.size));
+    }
+
+    /// The frame as last sent to the panel, row by row.
+    pub fn frame(&self) -> &[Rgb565] {
+        &self.front
+    }
+
+    pub fn stats(&self) -> FrameStats {
+        self.stats
+    }
+
+    /// Mark the start of rendering a new frame, for frame time measurement.
+    pub fn begin_frame(&mut self) {
+        self.frame_start = Instant::now();
+    }
+
+    /// Send the changed parts of the frame to the panel.
+    pub async fn flush(&mut self) -> Result<(), DisplayError> {
+        let flush_start = Instant::now();
+        let mut bytes = 0;
+
+        let damage = core::mem::take(&mut self.damage);
+        for area in damage {
+            let changed = if self.front_valid {
+                self.changed_area(&area)
+            } else {
+                Some(area)
+            };
+            if let Some(changed) = changed {
+                bytes += self.send(&changed).await?;
+            }
+        }
+        self.front_valid = true;
+
+        self.record_frame(flush_start, bytes);
+        Ok(())
+    }
+
+    fn record_frame(&mut self, flush_start: Instant, bytes: usize) {
+        let now = Instant::now();
+        let stats = &mut self.stats;
+        stats.frames = stats.frames.wrapping_add(1);
+        stats.last_render = flush_start.saturating_duration_since(self.frame_start);
+        stats.last_flush = now - flush_start;
+        stats.max_flush = stats.max_flush.max(stats.last_flush);
+        stats.last_bytes = bytes;
+
+        let frame = (stats.last_render + stats.last_flush).as_micros();
+        let avg = if stats.frames == 1 {
+            frame
+        } else {
+            (stats.avg_frame.as_micros() * 7 + frame) / 8
+        };
+        stats.avg_frame = Duration::from_micros(avg);
+
+        debug!(
+            "Frame {}: render {} us, flush {} us ({} bytes), avg {} us",
+            stats.frames,
+            stats.last_render.as_micros(),
+            stats.last_flush.as_micros(),
+            bytes,
+            avg
+        );
+        if stats.frames % REPORT_EVERY == 0 {
+            info!(
+                "Frame times: avg {} us, max flush {} us",
+                avg,
+                stats.max_flush.as_micros()
+            );
+        }
+    }
+
+    /// Send one frame of a transition in `area` straight to the panel. `f` composes
+    /// each row from the frame the panel showed before the transition, the frame
+    /// drawn since and the row to send. Neither frame changes, so no copies of them
+    /// are needed; call `end_transition` once the panel shows the drawn frame.
+    pub async fn flush_transition(
+        &mut self,
+        area: &Rectangle,
+        mut f: impl FnMut(&[Rgb565], &[Rgb565], &mut [Rgb565]),
+    ) -> Result<(), DisplayError> {
+        let flush_start = Instant::now();
+        let area = area.intersection(&Rectangle::new(Point::zero(), self.size));
+        self.row.clear();
+        self.row.resize(area.size.width as usize, Rgb565::BLACK);
+
+        let bytes = self
+            .stream(&area, |this, row| {
+                f(&this.front[row.clone()], &this.back[row], &mut this.row);
+                for pixel in &this.row {
+                    this.tile
+                        .extend_from_slice(&pixel.into_storage().to_be_bytes());
+                }
+            })
+            .await?;
+
+        self.record_frame(flush_start, bytes);
+        Ok(())
+    }
+
+    /// The last transition frame sent was the drawn frame itself: the panel shows it
+    /// in `area` now.
+    pub fn end_transition(&mut self, area: &Rectangle) {
+        let area = area.intersection(&Rectangle::new(Point::zero(), self.size));
+        let Some(bottom_right) = area.bottom_right() else {
+            return;
+        };
+
+        let width = self.size.width as usize;
+        for y in area.top_left.y..=bottom_right.y {
+            let row = y as usize * width;
+            let row = row + area.top_left.x as usize..=row + bottom_right.x as usize;
+            self.front[row.clone()].copy_from_slice(&self.back[row]);
+        }
+    }
+
+    /// Send one rectangle of the frame to the panel. Returns the number of bytes sent.
+    async fn send(&mut self, area: &Rectangle) -> Result<usize, DisplayError> {
+        self.stream(area, |this, row| {
+            for pixel in &this.back[row.clone()] {
+                this.tile
+                    .extend_from_slice(&pixel.into_storage().to_be_bytes());
+            }
+            this.front[row.clone()].copy_from_slice(&this.back[row]);
+        })
+        .await
+    }
+
+    /// Stream one rectangle to the panel tile by tile. `push_row` appends the bytes of
+    /// one row to the tile, given the range of its pixels in the frame. Returns the
+    /// number of bytes sent.
+    async fn stream(
+        &mut self,
+        area: &Rectangle,
+        mut push_row: impl FnMut(&mut Self, Range<usize>),
+    ) -> Result<usize, DisplayError> {
+        let Some(bottom_right) = area.bottom_right() else {
+            return Ok(0);
+        };
+        self.display.set_pixels(
+            area.top_left.x as u16,
+            area.top_left.y as u16,
+            bottom_right.x as u16,
+            bottom_right.y as u16,
+            core::iter::empty(),
+        )?;
+
+        let width = self.size.width as usize;
+        let x = area.top_left.x as usize;
+        let w = area.size.width as usize;
+        let rows_per_tile = (TILE_BYTES / (w * 2)).max(1);
+
+        let mut y = area.top_left.y as usize;
+        let end = y + area.size.height as usize;
+        while y < end {
+            let tile_end = (y + rows_per_tile).min(end);
+
+            self.tile.clear();
+            for row in y..tile_end {
+                push_row(self, row * width + x..row * width + x + w);
+            }
+            self.panel.write_pixels_async(&self.tile).await?;
+
+            y = tile_end;
+        }
+        Ok(w * area.size.height as usize * 2)
+    }
+
+    /// Shrink a damaged area to the bounding box of pixels that differ from the panel.
+    fn changed_area(&self, area: &Rectangle) -> Option<Rectangle> {
+        let width = self.size.width as usize;
+        let x0 = area.top_left.x as usize;
+        let x1 = x0 + area.size.width as usize;
+        let y0 = area.top_left.y as usize;
+        let y1 = y0 + area.size.height as usize;
+
+        let mut min = Point::new(i32::MAX, i32::MAX);
+        let mut max = Point::new(i32::MIN, i32::MIN);
+        for y in y0..y1 {
+            let row = y * width;
+            let back = &self.back[row + x0..row + x1];
+            let front = &self.front[row + x0..row + x1];
+            let Some(first) = back.iter().zip(front).position(|(b, f)| b != f) else {
+                continue;
+            };
+            let last = back.iter().zip(front).rposition(|(b, f)| b != f).unwrap();
+
+            min.x = min.x.min((x0 + first) as i32);
+            max.x = max.x.max((x0 + last) as i32);
+            min.y = min.y.min(y as i32);
+            max.y = y as i32;
+        }
+
+        (max.x >= min.x).then(|| Rectangle::with_corners(min, max))
+    }
+
+    /// Record a damaged region, merging it with overlapping ones.
+    fn add_damage(&mut self, area: Rectangle) {
+        let mut area = area.intersection(&Rectangle::new(Point::zero(), self.size));
+        if area.is_zero_sized() {
+            return;
+        }
+
+        // Absorb every region that touches the new one
+        let mut idx = 0;
+        while idx < self.damage.len() {
+            if touches(&self.damage[idx], &area) {
+                area = union(&self.damage[idx], &area);
+                self.damage.swap_remove(idx);
+                idx = 0;
+            } else {
+                idx += 1;
+            }
+        }
+
+        if let Err(area) = self.damage.push(area) {
+            // Out of slots: merge with the region that grows the least
+            let (idx, _) = self
+                .damage
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, existing)| area_of(&union(existing, &area)) - area_of(existing))
+                .unwrap();
+            let merged = union(&self.damage[idx], &area);
+            self.damage.swap_remove(idx);
+            self.add_damage(merged);
+        }
+    }
+
+    fn index(&self, point: Point) -> Option<usize> {
+        let (x, y) = (point.x, point.y);
+        if x < 0 || y < 0 || x >= self.size.width as i32 || y >= self.size.height as i32 {
+            return None;
+        }
+        Some(y as usize * self.size.width as usize + x as usize)
+    }
+}
+
+fn touches(a: &Rectangle, b: &Rectangle) -> bool {
+    // Grow by one pixel so adjacent regions are merged too
+    let grown = Rectangle::new(a.top_left - Point::new(1, 1), a.size + Size::new(2, 2));
+    !grown.intersection(b).is_zero_sized()
+}
+
+fn union(a: &Rectangle, b: &Rectangle) -> Rectangle {
+    let (Some(a_br), Some(b_br)) = (a.bottom_right(), b.bottom_right()) else {
+        return if a.is_zero_sized() { *b } else { *a };
+    };
+    Rectangle::with_corners(
+        Point::new(
+            a.top_left.x.min(b.top_left.x),
+            a.top_left.y.min(b.top_left.y),
+        ),
+        Point::new(a_br.x.max(b_br.x), a_br.y.max(b_br.y)),
+    )
+}
+
+fn area_of(rect: &Rectangle) -> u32 {
+    rect.size.width * rect.size.height
+}
+
+impl OriginDimensions for Compositor {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl DrawTarget for Compositor {
+    type Color = Rgb565;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let mut min = Point::new(i32::MAX, i32::MAX);
+        let mut max = Point::new(i32::MIN, i32::MIN);
+
+        for Pixel(point, color) in pixels {
+            if let Some(idx) = self.index(point) {
+                self.back[idx] = color;
+                min = min.component_min(point);
+                max = max.component_max(point);
+            }
+        }
+
+        if max.x >= min.x {
+            self.add_damage(Rectangle::with_corners(min, max));
+        }
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let area = area.intersection(&Rectangle::new(Point::zero(), self.size));
+        let Some(bottom_right) = area.bottom_right() else {
+            return Ok(());
+        };
+
+        let width = self.size.width as usize;
+        for y in area.top_left.y..=bottom_right.y {
+            let row = y as usize * width;
+            self.back[row + area.top_left.x as usize..=row + bottom_right.x as usize].fill(color);
+        }
+        self.add_damage(area);
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.back.fill(color);
+        self.add_damage(Rectangle::new(Point::zero(), self.size));
+        Ok(())
+    }
+}
@@ -0,0 +1,205 @@
+//! Dialogs opened with `Transition::Dialog`.
+//!
+//! A dialog is drawn on top of the screen that opened it and takes all buttons until it
+//! is answered. The answer goes back to that screen through `Screen::on_dialog_result`,
+//! together with the id the screen gave the dialog.
+
+use core::fmt::Write;
+
+use alloc::string::String;
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::{DrawTarget, Point, Size},
+    primitives::Rectangle,
+    text::{Alignment, Baseline, Text, TextStyleBuilder},
+    Drawable,
+};
+
+use crate::i18n::Msg;
+use crate::state::ButtonEvent;
+use crate::ui::theme::{self, UiTheme};
+use crate::ui::widgets::{Icon, ListView, Modal, Response, Widget};
+
+/// Chosen by the screen to tell its dialogs apart.
+pub type DialogId = u16;
+
+/// Answer to a dialog.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DialogResult {
+    /// Confirm: yes or no.
+    Confirmed(bool),
+    /// The alert was acknowledged.
+    Acknowledged,
+    /// Index of the option picked from a choice list.
+    Chosen(usize),
+    /// Value set in a number picker.
+    Number(i32),
+    /// Closed with B without an answer.
+    Cancelled,
+}
+
+enum Kind {
+    Confirm,
+    Alert,
+    Choice(ListView),
+    Number {
+        value: i32,
+        min: i32,
+        max: i32,
+        step: i32,
+    },
+}
+
+pub struct Dialog {
+    id: DialogId,
+    frame: Modal,
+    kind: Kind,
+}
+
+impl Dialog {
+    /// Yes/No question.
+    pub fn confirm(id: DialogId, title: impl Into<String>, message: impl Into<String>) -> Self {
+        let frame = Modal::new(Rectangle::zero(), title, message)
+            .with_buttons(&[Msg::ButtonYes, Msg::ButtonNo]);
+        Self::new(id, frame, Kind::Confirm)
+    }
+
+    /// Message with an OK button.
+    pub fn alert(id: DialogId, title: impl Into<String>, message: impl Into<String>) -> Self {
+        let frame = Modal::new(Rectangle::zero(), title, message);
+        Self::new(id, frame, Kind::Alert)
+    }
+
+    /// Pick one of `options`, starting at `selected`.
+    pub fn choice<I, S>(id: DialogId, title: impl Into<String>, options: I, selected: usize) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut list = ListView::new(Rectangle::zero()).with_rows(options);
+        list.select(selected);
+        let frame = Modal::new(Rectangle::zero(), title, "").with_buttons(&[]);
+        Self::new(id, frame, Kind::Choice(list))
+    }
+
+    /// Pick a number in `min..=max`: Up/Down change it by `step`, Left/Right by ten steps.
+    pub fn number(
+        id: DialogId,
+        title: impl Into<String>,
+        value: i32,
+        min: i32,
+        max: i32,
+        step: i32,
+    ) -> Self {
+        let frame = Modal::new(Rectangle::zero(), title, "").with_buttons(&[Msg::ButtonOk]);
+        let kind = Kind::Number {
+            value: value.clamp(min, max),
+            min,
+            max,
+            step,
+        };
+        Self::new(id, frame, kind)
+    }
+
+    fn new(id: DialogId, frame: Modal, kind: Kind) -> Self {
+        Self { id, frame, kind }
+    }
+
+    pub fn id(&self) -> DialogId {
+        self.id
+    }
+
+    /// Center the dialog in the display bounds.
+    pub fn resize(&mut self, display_bounds: Rectangle) {
+        let theme = theme::current();
+        self.frame.set_bounds(display_bounds);
+        if let Kind::Choice(list) = &mut self.kind {
+            // List rows indent by the full margin, the dialog only needs half of it
+            let mut area = self.frame.content_area(theme);
+            area.top_left.x -= theme.spacing.margin / 2;
+            area.size.width += theme.spacing.margin as u32 / 2;
+            list.set_bounds(area);
+        }
+    }
+
+    /// Handle a button press, returns the answer once the dialog is done.
+    pub fn handle(&mut self, event: ButtonEvent) -> Option<DialogResult> {
+        if let Kind::Number {
+            value,
+            min,
+            max,
+            step,
+        } = &mut self.kind
+        {
+            let delta = match event {
+                ButtonEvent::Up => *step,
+                ButtonEvent::Down => -*step,
+                ButtonEvent::Right => *step * 10,
+                ButtonEvent::Left => -*step * 10,
+                _ => 0,
+            };
+            if delta != 0 {
+                *value = (*value + delta).clamp(*min, *max);
+                return None;
+            }
+        }
+        if let Kind::Choice(list) = &mut self.kind {
+            if list.handle(event) == Response::Consumed {
+                return None;
+            }
+        }
+
+        match self.frame.handle(event) {
+            Response::Activated => Some(match &self.kind {
+                Kind::Confirm => DialogResult::Confirmed(self.frame.selected() == 0),
+                Kind::Alert => DialogResult::Acknowledged,
+                Kind::Choice(list) => DialogResult::Chosen(list.selected()),
+                Kind::Number { value, .. } => DialogResult::Number(*value),
+            }),
+            Response::Cancelled => Some(DialogResult::Cancelled),
+            _ => None,
+        }
+    }
+
+    pub fn draw<D>(&self, display: &mut D, theme: &UiTheme) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        self.frame.draw(display, theme, true)?;
+        let content = self.frame.content_area(theme);
+        match &self.kind {
+            Kind::Confirm | Kind::Alert => {}
+            Kind::Choice(list) => list.draw(display, theme, true)?,
+            Kind::Number { value, .. } => {
+                let mut text = String::new();
+                write!(text, "{}", value).unwrap();
+                let center = content.center();
+                Text::with_text_style(
+                    &text,
+                    center,
+                    theme.accent_style(),
+                    TextStyleBuilder::new()
+                        .alignment(Alignment::Center)
+                        .baseline(Baseline::Middle)
+                        .build(),
+                )
+                .draw(display)?;
+
+                // Hint at the buttons that change the number
+                let icon_size = Size::new_equal(12);
+                let offset = content.size.width as i32 / 3;
+                Icon::Minus.draw(
+                    display,
+                    Rectangle::with_center(center - Point::new(offset, 0), icon_size),
+                    theme.foreground,
+                )?;
+                Icon::Plus.draw(
+                    display,
+                    Rectangle::with_center(center + Point::new(offset, 0), icon_size),
+                    theme.foreground,
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,260 @@
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::{DrawTarget, Point, Size},
+    primitives::Rectangle,
+    text::{
+        renderer::{CharacterStyle, TextMetrics, TextRenderer},
+        Baseline,
+    },
+    Drawable, Pixel,
+};
+use embedded_text::{
+    alignment::{HorizontalAlignment, VerticalAlignment},
+    style::TextBoxStyleBuilder,
+    TextBox,
+};
+
+// Proportional fonts generated by build.rs from assets/fonts/*.bdf:
+// REGULAR (10x20), BOLD (9x18 bold) and SMALL (6x13)
+include!(concat!(env!("OUT_DIR"), "/fonts.rs"));
+
+const CACHE_SIZE: usize = 64;
+// Cache entries pack the glyph index into the low bits and the character above it
+const INDEX_BITS: u32 = 11;
+
+/// One glyph, trimmed to its ink.
+pub struct Glyph {
+    pub code: u32,
+    /// Horizontal distance to the next glyph.
+    pub advance: u8,
+    /// Offset of the first bitmap row from the baseline, negative is above it.
+    pub top: i8,
+    pub width: u8,
+    pub height: u8,
+    /// Bit offset of the glyph in the font bitmap, rows are packed without padding.
+    pub offset: u32,
+}
+
+/// Proportional bitmap font covering Latin and Cyrillic.
+pub struct BitmapFont {
+    pub ascent: u8,
+    pub descent: u8,
+    /// Glyph drawn for characters missing from the font.
+    replacement: usize,
+    cache: &'static GlyphCache,
+    /// Sorted by code point.
+    glyphs: &'static [Glyph],
+    bitmap: &'static [u8],
+}
+
+impl BitmapFont {
+    pub fn line_height(&self) -> u32 {
+        (self.ascent + self.descent) as u32
+    }
+
+    pub fn glyph(&self, c: char) -> &Glyph {
+        let idx = self.cache.get(c).unwrap_or_else(|| {
+            let idx = self
+                .glyphs
+                .binary_search_by_key(&(c as u32), |glyph| glyph.code)
+                .unwrap_or(self.replacement);
+            self.cache.insert(c, idx);
+            idx
+        });
+        &self.glyphs[idx]
+    }
+
+    /// Width of `text` in pixels.
+    pub fn measure(&self, text: &str) -> u32 {
+        text.chars().map(|c| self.glyph(c).advance as u32).sum()
+    }
+
+    fn pixel(&self, glyph: &Glyph, x: u32, y: u32) -> bool {
+        let bit = glyph.offset as usize + (y * glyph.width as u32 + x) as usize;
+        self.bitmap[bit / 8] & (0x80 >> (bit % 8)) != 0
+    }
+}
+
+/// Small direct-mapped cache from character to glyph index, so that drawing the
+/// same letters again skips the binary search over the glyph table.
+pub struct GlyphCache {
+    entries: [AtomicU32; CACHE_SIZE],
+}
+
+impl GlyphCache {
+    pub const fn new() -> Self {
+        Self {
+            entries: [const { AtomicU32::new(0) }; CACHE_SIZE],
+        }
+    }
+
+    fn get(&self, c: char) -> Option<usize> {
+        let entry = self.entries[Self::index(c)].load(Ordering::Relaxed);
+        (entry != 0 && entry >> INDEX_BITS == c as u32)
+            .then_some((entry & ((1 << INDEX_BITS) - 1)) as usize)
+    }
+
+    fn insert(&self, c: char, idx: usize) {
+        let entry = ((c as u32) << INDEX_BITS) | idx as u32;
+        self.entries[Self::index(c)].store(entry, Ordering::Relaxed);
+    }
+
+    fn index(c: char) -> usize {
+        let c = c as usize;
+        (c ^ (c >> 6)) % CACHE_SIZE
+    }
+}
+
+impl Default for GlyphCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Text style for `BitmapFont`, usable with `Text` and `embedded_text::TextBox`.
+#[derive(Copy, Clone)]
+pub struct FontStyle {
+    pub font: &'static BitmapFont,
+    pub text_color: Option<Rgb565>,
+    pub background_color: Option<Rgb565>,
+}
+
+impl FontStyle {
+    pub fn new(font: &'static BitmapFont, text_color: Rgb565) -> Self {
+        Self {
+            font,
+            text_color: Some(text_color),
+            background_color: None,
+        }
+    }
+
+    pub fn with_background(mut self, background_color: Rgb565) -> Self {
+        self.background_color = Some(background_color);
+        self
+    }
+
+    /// Distance from the given baseline to the top of the line box.
+    fn baseline_offset(&self, baseline: Baseline) -> i32 {
+        let height = self.font.line_height() as i32;
+        match baseline {
+            Baseline::Top => 0,
+            Baseline::Bottom => height - 1,
+            Baseline::Middle => (height - 1) / 2,
+            Baseline::Alphabetic => self.font.ascent as i32 - 1,
+        }
+    }
+
+    fn draw_glyph<D>(&self, glyph: &Glyph, position: Point, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        if let Some(background) = self.background_color {
+            let cell = Rectangle::new(
+                position,
+                Size::new(glyph.advance as u32, self.font.line_height()),
+            );
+            target.fill_solid(&cell, background)?;
+        }
+
+        let Some(color) = self.text_color else {
+            return Ok(());
+        };
+        let origin = position + Point::new(0, self.font.ascent as i32 - 1 + glyph.top as i32);
+        let pixels = (0..glyph.height as u32).flat_map(move |y| {
+            (0..glyph.width as u32)
+                .filter(move |&x| self.font.pixel(glyph, x, y))
+                .map(move |x| Pixel(origin + Point::new(x as i32, y as i32), color))
+        });
+        target.draw_iter(pixels)
+    }
+}
+
+impl TextRenderer for FontStyle {
+    type Color = Rgb565;
+
+    fn draw_string<D>(
+        &self,
+        text: &str,
+        position: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let offset = Point::new(0, self.baseline_offset(baseline));
+        let mut pen = position - offset;
+        for c in text.chars() {
+            let glyph = self.font.glyph(c);
+            self.draw_glyph(glyph, pen, target)?;
+            pen.x += glyph.advance as i32;
+        }
+        Ok(pen + offset)
+    }
+
+    fn draw_whitespace<D>(
+        &self,
+        width: u32,
+        position: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let offset = Point::new(0, self.baseline_offset(baseline));
+        let top_left = position - offset;
+        if let Some(background) = self.background_color {
+            let area = Rectangle::new(top_left, Size::new(width, self.font.line_height()));
+            target.fill_solid(&area, background)?;
+        }
+        Ok(position + Point::new(width as i32, 0))
+    }
+
+    fn measure_string(&self, text: &str, position: Point, baseline: Baseline) -> TextMetrics {
+        let top_left = position - Point::new(0, self.baseline_offset(baseline));
+        let width = self.font.measure(text);
+        TextMetrics {
+            bounding_box: Rectangle::new(top_left, Size::new(width, self.font.line_height())),
+            next_position: position + Point::new(width as i32, 0),
+        }
+    }
+
+    fn line_height(&self) -> u32 {
+        self.font.line_height()
+    }
+}
+
+impl CharacterStyle for FontStyle {
+    type Color = Rgb565;
+
+    fn set_text_color(&mut self, text_color: Option<Self::Color>) {
+        self.text_color = text_color;
+    }
+
+    fn set_background_color(&mut self, background_color: Option<Self::Color>) {
+        self.background_color = background_color;
+    }
+}
+
+/// Draw `text` word-wrapped inside `area`, clipping lines that don't fit.
+///
+/// Returns the part of the text that did not fit into the area.
+pub fn draw_wrapped<'a, D>(
+    display: &mut D,
+    text: &'a str,
+    area: Rectangle,
+    style: FontStyle,
+    alignment: HorizontalAlignment,
+) -> Result<&'a str, D::Error>
+where
+    D: DrawTarget<Color = Rgb565>,
+{
+    let textbox_style = TextBoxStyleBuilder::new()
+        .alignment(alignment)
+        .vertical_alignment(VerticalAlignment::Middle)
+        .build();
+    TextBox::with_textbox_style(text, area, style, textbox_style).draw(display)
+}
@@ -0,0 +1,91 @@
+//! Menu model: entries registered by the apps at startup, shown by `MenuScreen`.
+
+use core::cell::RefCell;
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use embassy_sync::blocking_mutex::{raw::CriticalSectionRawMutex, Mutex};
+use embedded_graphics::primitives::Rectangle;
+use heapless::String;
+
+use crate::i18n::Msg;
+use crate::ui::widgets::Icon;
+use crate::ui::Screen;
+
+/// Text shown at the right of a value entry.
+pub type MenuValue = String<32>;
+
+/// Builds the screen opened by an entry, given the display bounds.
+pub type ScreenFactory = fn(Rectangle) -> Box<dyn Screen>;
+
+static ROOT: Mutex<CriticalSectionRawMutex, RefCell<Vec<MenuEntry>>> =
+    Mutex::new(RefCell::new(Vec::new()));
+
+/// What selecting an entry does.
+#[derive(Clone)]
+pub enum MenuItem {
+    /// Open a screen.
+    Screen(ScreenFactory),
+    /// Open a nested menu.
+    Submenu(Vec<MenuEntry>),
+    /// Flip a flag with A, Left or Right.
+    Toggle { get: fn() -> bool, set: fn(bool) },
+    /// Step a value forward with A or Right and back with Left.
+    Value {
+        get: fn() -> MenuValue,
+        change: fn(bool),
+    },
+}
+
+#[derive(Clone)]
+pub struct MenuEntry {
+    pub label: Msg,
+    pub icon: Option<Icon>,
+    pub item: MenuItem,
+}
+
+impl MenuEntry {
+    pub fn screen(label: Msg, factory: ScreenFactory) -> Self {
+        Self::new(label, MenuItem::Screen(factory))
+    }
+
+    pub fn submenu(label: Msg, entries: Vec<MenuEntry>) -> Self {
+        Self::new(label, MenuItem::Submenu(entries))
+    }
+
+    pub fn toggle(label: Msg, get: fn() -> bool, set: fn(bool)) -> Self {
+        Self::new(label, MenuItem::Toggle { get, set })
+    }
+
+    pub fn value(label: Msg, get: fn() -> MenuValue, change: fn(bool)) -> Self {
+        Self::new(label, MenuItem::Value { get, change })
+    }
+
+    pub fn with_icon(mut self, icon: Icon) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    fn new(label: Msg, item: MenuItem) -> Self {
+        Self {
+            label,
+            icon: None,
+            item,
+        }
+    }
+}
+
+/// Top level entries of the main menu.
+pub struct MenuRegistry;
+
+impl MenuRegistry {
+    /// Add an entry to the end of the main menu. Call before the UI task starts.
+    pub fn register(entry: MenuEntry) {
+        ROOT.lock(|root| root.borrow_mut().push(entry));
+    }
+
+    /// Snapshot of the main menu entries.
+    pub fn entries() -> Vec<MenuEntry> {
+        ROOT.lock(|root| root.borrow().clone())
+    }
+}
@@ -1,9 +1,23 @@
+pub mod animation;
+pub mod boot;
+pub mod compositor;
+pub mod dialog;
+pub mod font;
+pub mod menu;
+pub mod navigator;
 pub mod screens;
+pub mod screenshot;
+pub mod theme;
 pub mod widgets;
 
-use crate::display::LilkaDisplay;
 use crate::state::ButtonEvent;
 use alloc::boxed::Box;
+use animation::Animation;
+use compositor::Compositor;
+use core::convert::Infallible;
+use dialog::{Dialog, DialogId, DialogResult};
+use embassy_time::Duration;
+use embedded_graphics::primitives::Rectangle;
 
 /// Transitions tell the navigator what to do after a screen update.
 pub enum Transition {
@@ -15,6 +29,31 @@ pub enum Transition {
     Pop,
     /// Replace the current screen with a new one.
     Replace(Box<dyn Screen>),
+    /// Show a dialog over the current screen, its answer goes to `on_dialog_result`.
+    Dialog(Dialog),
+    /// Another transition with a different animation than its default.
+    Animated(Animation, Box<Transition>),
+}
+
+impl Transition {
+    /// Change how the screen change is animated.
+    pub fn with_animation(self, animation: Animation) -> Self {
+        match self {
+            Transition::Animated(_, inner) => Transition::Animated(animation, inner),
+            other => Transition::Animated(animation, Box::new(other)),
+        }
+    }
+
+    /// Push slides the new screen in, Pop slides back and Replace cross-fades.
+    fn default_animation(&self) -> Animation {
+        match self {
+            Transition::Push(_) => Animation::SlideLeft,
+            Transition::Pop => Animation::SlideRight,
+            Transition::Replace(_) => Animation::Fade,
+            Transition::Animated(animation, _) => *animation,
+            Transition::Stay | Transition::Dialog(_) => Animation::None,
+        }
+    }
 }
 
 /// The core trait for all UI screens.
@@ -22,11 +61,42 @@ pub trait Screen {
     /// Handle input and return a transition.
     fn update(&mut self, event: ButtonEvent) -> Transition;
 
-    /// Draw the screen content.
-    fn draw(&mut self, display: &mut LilkaDisplay, state: &UIState);
+    /// Draw the screen content. The whole content area is redrawn every frame;
+    /// the compositor only sends the pixels that changed to the panel.
+    fn draw(&mut self, display: &mut Compositor, state: &UIState) -> Result<(), Infallible>;
+
+    /// The display was rotated, lay the screen out for the new bounds.
+    fn resize(&mut self, display_bounds: Rectangle);
+
+    /// The screen was pushed onto the stack.
+    fn on_enter(&mut self) {}
+
+    /// The screen was popped or replaced and is about to be dropped.
+    fn on_exit(&mut self) {}
+
+    /// Another screen was pushed over this one.
+    fn on_pause(&mut self) {}
+
+    /// The screen is on top again after the one above it was popped.
+    fn on_resume(&mut self) {}
+
+    /// Called on every UI tick while the screen is on top, with the time since the
+    /// previous tick. The screen is redrawn afterwards.
+    fn on_tick(&mut self, _elapsed: Duration) -> Transition {
+        Transition::Stay
+    }
+
+    /// How often the screen wants `on_tick` and a redraw, e.g. for animations.
+    /// `None` leaves it at the system tick of once per second. Asked again after every
+    /// tick, so it can follow the screen state.
+    fn frame_interval(&self) -> Option<Duration> {
+        None
+    }
 
-    /// Force the screen to redraw everything on the next `draw` call.
-    fn ensure_redraw(&mut self) {}
+    /// A dialog opened by this screen was answered.
+    fn on_dialog_result(&mut self, _id: DialogId, _result: DialogResult) -> Transition {
+        Transition::Stay
+    }
 }
 
 #[derive(Default)]
@@ -0,0 +1,173 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::convert::Infallible;
+use embassy_time::{Duration, Instant};
+use embedded_graphics::prelude::Dimensions;
+
+use crate::services::{NotificationService, Severity};
+use crate::state::ButtonEvent;
+use crate::ui::animation::Animation;
+use crate::ui::compositor::Compositor;
+use crate::ui::dialog::Dialog;
+use crate::ui::theme;
+use crate::ui::widgets::{Toast, Widget};
+use crate::ui::{Screen, Transition, UIState};
+
+const TOAST_DURATION: Duration = Duration::from_secs(3);
+
+/// Stack of open screens and the dialog and notification toast shown over them.
+///
+/// Applies transitions and calls the lifecycle hooks of the screens: `on_enter`/`on_exit`
+/// when a screen is added or removed, `on_pause`/`on_resume` when another screen covers
+/// it or goes away.
+pub struct Navigator {
+    stack: Vec<Box<dyn Screen>>,
+    dialog: Option<Dialog>,
+    toast: Option<Toast>,
+    // Id of the last notification shown as a toast
+    last_notification: u32,
+    last_tick: Instant,
+}
+
+impl Navigator {
+    /// Start with `root` at the bottom of the stack. The root is never popped.
+    pub fn new(mut root: Box<dyn Screen>) -> Self {
+        root.on_enter();
+        let mut stack = Vec::new();
+        stack.push(root);
+        Self {
+            stack,
+            dialog: None,
+            toast: None,
+            last_notification: 0,
+            last_tick: Instant::now(),
+        }
+    }
+
+    /// All open screens, bottom first.
+    pub fn screens_mut(&mut self) -> &mut [Box<dyn Screen>] {
+        &mut self.stack
+    }
+
+    /// Push a screen opened from outside the top screen, e.g. by a global shortcut.
+    pub fn open(&mut self, screen: Box<dyn Screen>) -> Animation {
+        self.apply(Transition::Push(screen))
+    }
+
+    /// Whether a dialog is waiting for an answer.
+    pub fn has_dialog(&self) -> bool {
+        self.dialog.is_some()
+    }
+
+    /// Pass a button press to the dialog if one is open, otherwise to the top screen.
+    /// Returns the animation to play if the top screen changed.
+    pub fn handle(&mut self, event: ButtonEvent) -> Animation {
+        let transition = match self.dialog.as_mut() {
+            Some(dialog) => match dialog.handle(event) {
+                Some(result) => {
+                    let id = dialog.id();
+                    self.dialog = None;
+                    self.top_mut().on_dialog_result(id, result)
+                }
+                None => Transition::Stay,
+            },
+            None => self.top_mut().update(event),
+        };
+        self.apply(transition)
+    }
+
+    /// Tell the top screen how much time passed since its last tick.
+    pub fn tick(&mut self) -> Animation {
+        let now = Instant::now();
+        let elapsed = now - self.last_tick;
+        self.last_tick = now;
+        let transition = self.top_mut().on_tick(elapsed);
+        self.apply(transition)
+    }
+
+    /// When the top screen wants its next tick, if it asked for a frame rate.
+    pub fn next_frame(&self) -> Option<Instant> {
+        let interval = self.stack.last()?.frame_interval()?;
+        Some(self.last_tick + interval)
+    }
+
+    /// Draw the top screen, the dialog and the latest notification over it.
+    pub fn draw(&mut self, display: &mut Compositor, state: &UIState) -> Result<(), Infallible> {
+        let theme = theme::current();
+        let bounds = display.bounding_box();
+        self.top_mut().draw(display, state)?;
+        if let Some(dialog) = self.dialog.as_mut() {
+            // Follows rotation and theme changes like the screens do
+            dialog.resize(bounds);
+            dialog.draw(display, theme)?;
+        }
+
+        if let Some(notification) = NotificationService::latest() {
+            if notification.id > self.last_notification {
+                self.last_notification = notification.id;
+                let toast = Toast::new(bounds, notification.text, TOAST_DURATION);
+                self.toast = Some(match notification.severity {
+                    Severity::Info => toast,
+                    Severity::Warning | Severity::Error => toast.urgent(),
+                });
+            }
+        }
+        if self.toast.as_ref().is_some_and(Toast::is_expired) {
+            self.toast = None;
+        }
+        if let Some(toast) = self.toast.as_mut() {
+            toast.set_bounds(theme.content_area(bounds));
+            toast.draw(display, theme, false)?;
+        }
+
+        Ok(())
+    }
+
+    fn top_mut(&mut self) -> &mut Box<dyn Screen> {
+        // The root screen is never popped
+        self.stack.last_mut().unwrap()
+    }
+
+    /// Apply a transition, returns how the change of the top screen should be animated.
+    fn apply(&mut self, transition: Transition) -> Animation {
+        let animation = transition.default_animation();
+        match transition {
+            Transition::Stay => return Animation::None,
+            Transition::Push(mut screen) => {
+                self.top_mut().on_pause();
+                screen.on_enter();
+                self.stack.push(screen);
+            }
+            Transition::Pop => {
+                if self.stack.len() == 1 {
+                    return Animation::None;
+                }
+                if let Some(mut screen) = self.stack.pop() {
+                    screen.on_exit();
+                }
+                self.top_mut().on_resume();
+            }
+            Transition::Replace(mut screen) => {
+                if let Some(mut old) = self.stack.pop() {
+                    old.on_exit();
+                }
+                screen.on_enter();
+                self.stack.push(screen);
+            }
+            Transition::Dialog(dialog) => {
+                self.dialog = Some(dialog);
+                return Animation::None;
+            }
+            Transition::Animated(animation, inner) => {
+                // Nothing to animate if the inner transition changed nothing
+                return match self.apply(*inner) {
+                    Animation::None => Animation::None,
+                    _ => animation,
+                };
+            }
+        }
+        // Ticks measure the time spent on the current screen only
+        self.last_tick = Instant::now();
+        animation
+    }
+}
@@ -0,0 +1,145 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::Infallible;
+use core::fmt::Write;
+
+use crate::i18n::{self, Msg};
+use crate::services::{CrashReport, CrashService};
+use crate::state::ButtonEvent;
+use crate::ui::compositor::Compositor;
+use crate::ui::dialog::{Dialog, DialogId, DialogResult};
+use crate::ui::theme::{self, UiTheme};
+use crate::ui::widgets::{ListView, Widget};
+use crate::ui::{Screen, Transition, UIState};
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::Rectangle;
+use embedded_graphics::text::{Alignment, Baseline, Text, TextStyleBuilder};
+
+use super::{detail, text};
+
+// Gap between the header and the title
+const PADDING: i32 = 10;
+const MESSAGE_DIALOG: DialogId = 1;
+const CLEAR_DIALOG: DialogId = 2;
+
+/// The last crash report kept by `CrashService`. Opens by itself on the boot after a
+/// crash. A shows the whole panic message, C forgets the report.
+pub struct CrashScreen {
+    display_bounds: Rectangle,
+    report: Option<CrashReport>,
+    list: ListView,
+}
+
+impl CrashScreen {
+    pub fn new(display_bounds: Rectangle) -> Self {
+        let report = CrashService::last();
+        let mut list = ListView::new(Rectangle::zero());
+        if let Some(report) = &report {
+            list.set_rows(rows(report));
+        }
+        Self {
+            display_bounds,
+            report,
+            list,
+        }
+    }
+
+    fn body(&self, theme: &UiTheme) -> Rectangle {
+        let content_area = theme.content_area(self.display_bounds);
+        let top = content_area.top_left.y + PADDING + theme.spacing.row_height;
+        let bottom = content_area.top_left.y + content_area.size.height as i32;
+        Rectangle::new(
+            Point::new(content_area.top_left.x, top),
+            Size::new(content_area.size.width, (bottom - top).max(0) as u32),
+        )
+    }
+}
+
+fn rows(report: &CrashReport) -> Vec<String> {
+    let secs = report.uptime.as_secs();
+    let mut rows = Vec::new();
+    rows.push(detail(Msg::CrashMessage, &report.message));
+    if !report.location.is_empty() {
+        rows.push(detail(Msg::CrashLocation, &report.location));
+    }
+    rows.push(detail(
+        Msg::CrashUptime,
+        &text(|s| write!(s, "{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)),
+    ));
+    rows.push(detail(Msg::CrashBacktrace, ""));
+    for pc in &report.backtrace {
+        rows.push(text(|s| write!(s, "  0x{:08x}", pc)));
+    }
+    rows
+}
+
+impl Screen for CrashScreen {
+    fn update(&mut self, event: ButtonEvent) -> Transition {
+        match event {
+            ButtonEvent::Up | ButtonEvent::Down => {
+                self.list.handle(event);
+                Transition::Stay
+            }
+            ButtonEvent::A => match &self.report {
+                // Rows are cut off at the right edge, the dialog wraps
+                Some(report) => Transition::Dialog(Dialog::alert(
+                    MESSAGE_DIALOG,
+                    i18n::tr(Msg::CrashMessage),
+                    report.message.as_str(),
+                )),
+                None => Transition::Stay,
+            },
+            ButtonEvent::C if self.report.is_some() => Transition::Dialog(Dialog::confirm(
+                CLEAR_DIALOG,
+                i18n::tr(Msg::CrashTitle),
+                i18n::tr(Msg::CrashClearQuestion),
+            )),
+            ButtonEvent::B => Transition::Pop,
+            _ => Transition::Stay,
+        }
+    }
+
+    fn on_dialog_result(&mut self, id: DialogId, result: DialogResult) -> Transition {
+        if id == CLEAR_DIALOG && result == DialogResult::Confirmed(true) {
+            CrashService::clear();
+            self.report = None;
+            self.list.set_rows(core::iter::empty::<&str>());
+        }
+        Transition::Stay
+    }
+
+    fn resize(&mut self, display_bounds: Rectangle) {
+        self.display_bounds = display_bounds;
+    }
+
+    fn draw(&mut self, display: &mut Compositor, _state: &UIState) -> Result<(), Infallible> {
+        let theme = theme::current();
+        let content_area = theme.content_area(self.display_bounds);
+        display.fill_solid(&content_area, theme.background)?;
+
+        Text::with_baseline(
+            i18n::tr(Msg::CrashTitle),
+            Point::new(theme.spacing.margin, content_area.top_left.y + PADDING),
+            theme.accent_style(),
+            Baseline::Top,
+        )
+        .draw(display)?;
+
+        self.list.set_bounds(self.body(theme));
+        if self.report.is_none() {
+            Text::with_text_style(
+                i18n::tr(Msg::CrashNone),
+                self.list.bounds().center(),
+                theme.text_style(),
+                TextStyleBuilder::new()
+                    .alignment(Alignment::Center)
+                    .baseline(Baseline::Middle)
+                    .build(),
+            )
+            .draw(display)?;
+            return;
+        }
+        // A report to read, nothing to pick
+        self.list.draw(display, theme, false)
+    }
+}
@@ -0,0 +1,163 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::Infallible;
+use core::fmt::Write;
+
+use crate::i18n::{self, Msg};
+use crate::services::diagnostics::Report;
+use crate::services::DiagnosticsService;
+use crate::state::ButtonEvent;
+use crate::ui::compositor::Compositor;
+use crate::ui::theme::{self, UiTheme};
+use crate::ui::widgets::{ListView, Widget};
+use crate::ui::{Screen, Transition, UIState};
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::Rectangle;
+use embedded_graphics::text::{Baseline, Text};
+
+use super::{detail, text};
+
+// Gap between the header and the title
+const PADDING: i32 = 10;
+
+/// Heap, stack, executor and queue statistics from `DiagnosticsService`,
+/// refreshed every second. Up/Down scroll.
+pub struct DiagnosticsScreen {
+    display_bounds: Rectangle,
+    list: ListView,
+}
+
+impl DiagnosticsScreen {
+    pub fn new(display_bounds: Rectangle) -> Self {
+        Self {
+            display_bounds,
+            list: ListView::new(Rectangle::zero()),
+        }
+    }
+
+    fn body(&self, theme: &UiTheme) -> Rectangle {
+        let content_area = theme.content_area(self.display_bounds);
+        let top = content_area.top_left.y + PADDING + theme.spacing.row_height;
+        let bottom = content_area.top_left.y + content_area.size.height as i32;
+        Rectangle::new(
+            Point::new(content_area.top_left.x, top),
+            Size::new(content_area.size.width, (bottom - top).max(0) as u32),
+        )
+    }
+}
+
+fn rows(report: &Report) -> Vec<String> {
+    let kb = i18n::tr(Msg::UnitKilobytes);
+    let ms = i18n::tr(Msg::UnitMilliseconds);
+    let heap = report.heap;
+    let mut rows = Vec::new();
+    rows.push(detail(
+        Msg::InfoHeapUsed,
+        &text(|s| write!(s, "{} {}", heap.used / 1024, kb)),
+    ));
+    rows.push(detail(
+        Msg::InfoHeapFree,
+        &text(|s| write!(s, "{} {}", heap.free / 1024, kb)),
+    ));
+    rows.push(detail(
+        Msg::DiagLargestBlock,
+        &text(|s| write!(s, "{} {}", heap.largest_free / 1024, kb)),
+    ));
+    rows.push(detail(
+        Msg::DiagFragmentation,
+        &text(|s| write!(s, "{}%", heap.fragmentation())),
+    ));
+    rows.push(detail(
+        Msg::DiagStack,
+        &text(|s| {
+            write!(
+                s,
+                "{}/{} {}",
+                report.stack.peak / 1024,
+                report.stack.size / 1024,
+                kb
+            )
+        }),
+    ));
+    rows.push(detail(
+        Msg::DiagLatency,
+        &text(|s| {
+            write!(
+                s,
+                "{} / {} {}",
+                millis(report.latency.avg_us),
+                millis(report.latency.max_us),
+                ms
+            )
+        }),
+    ));
+
+    rows.push(detail(Msg::DiagTasks, i18n::tr(Msg::DiagTasksLegend)));
+    for task in &report.tasks {
+        rows.push(text(|s| {
+            write!(
+                s,
+                "  {}: {}.{}%, {} {}, {}",
+                task.task.name(),
+                task.load / 10,
+                task.load % 10,
+                millis(task.max_poll_us),
+                ms,
+                task.polls
+            )
+        }));
+    }
+
+    rows.push(detail(Msg::DiagQueues, i18n::tr(Msg::DiagQueuesLegend)));
+    for channel in &report.channels {
+        rows.push(text(|s| {
+            write!(
+                s,
+                "  {}: {}/{}, {}",
+                channel.name, channel.len, channel.capacity, channel.peak
+            )
+        }));
+    }
+    rows
+}
+
+/// Microseconds as milliseconds with one decimal.
+fn millis(us: u32) -> String {
+    text(|s| write!(s, "{}.{}", us / 1000, us / 100 % 10))
+}
+
+impl Screen for DiagnosticsScreen {
+    fn update(&mut self, event: ButtonEvent) -> Transition {
+        match event {
+            ButtonEvent::Up | ButtonEvent::Down => {
+                self.list.handle(event);
+                Transition::Stay
+            }
+            ButtonEvent::B => Transition::Pop,
+            _ => Transition::Stay,
+        }
+    }
+
+    fn resize(&mut self, display_bounds: Rectangle) {
+        self.display_bounds = display_bounds;
+    }
+
+    fn draw(&mut self, display: &mut Compositor, _state: &UIState) -> Result<(), Infallible> {
+        let theme = theme::current();
+        let content_area = theme.content_area(self.display_bounds);
+        display.fill_solid(&content_area, theme.background)?;
+
+        Text::with_baseline(
+            i18n::tr(Msg::MenuDiagnostics),
+            Point::new(theme.spacing.margin, content_area.top_left.y + PADDING),
+            theme.accent_style(),
+            Baseline::Top,
+        )
+        .draw(display)?;
+
+        self.list.set_bounds(self.body(theme));
+        self.list.set_rows(rows(&DiagnosticsService::report()));
+        // Read-only statistics, nothing is highlighted
+        self.list.draw(display, theme, false)
+    }
+}
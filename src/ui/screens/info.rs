@@ -1,57 +1,313 @@
-use crate::display::LilkaDisplay;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::Infallible;
+use core::fmt::Write;
+
+use crate::i18n::{self, Msg};
+use crate::services::system::{self, ResetCause};
+use crate::services::{ClockService, NetworkService, SystemService};
 use crate::state::ButtonEvent;
+use crate::ui::compositor::Compositor;
+use crate::ui::font;
+use crate::ui::theme::{self, UiTheme};
+use crate::ui::widgets::{ListView, Widget};
 use crate::ui::{Screen, Transition, UIState};
-use embedded_graphics::primitives::Rectangle;
-use embedded_graphics::{
-    mono_font::iso_8859_10::FONT_10X20, mono_font::MonoTextStyle, pixelcolor::Rgb565, prelude::*,
-    text::Text,
-};
-use embedded_layout::prelude::*;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{Circle, PrimitiveStyle, Rectangle};
+use embedded_graphics::text::{Alignment, Baseline, Text, TextStyleBuilder};
+use embedded_text::alignment::HorizontalAlignment;
+use jiff::tz::TimeZone;
+
+use super::{detail, text};
+
+// Gap between the header and the page title
+const PADDING: i32 = 10;
+const DOT_SIZE: u32 = 6;
+
+/// One page of the info screen.
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Page {
+    Device,
+    Memory,
+    Network,
+    Time,
+    About,
+}
+
+const PAGES: [Page; 5] = [
+    Page::Device,
+    Page::Memory,
+    Page::Network,
+    Page::Time,
+    Page::About,
+];
+
+impl Page {
+    fn title(self) -> Msg {
+        match self {
+            Page::Device => Msg::InfoDevice,
+            Page::Memory => Msg::InfoMemory,
+            Page::Network => Msg::MenuNetwork,
+            Page::Time => Msg::InfoTime,
+            Page::About => Msg::InfoAboutTitle,
+        }
+    }
+
+    /// The details shown on the page, read fresh for every frame.
+    fn rows(self) -> Vec<String> {
+        let mut rows = Vec::new();
+        match self {
+            Page::Device => {
+                let (major, minor) = SystemService::chip_revision();
+                let mac = SystemService::mac_address();
+                rows.push(detail(Msg::InfoVersion, system::VERSION));
+                rows.push(detail(Msg::InfoBuild, system::GIT_HASH));
+                rows.push(detail(
+                    Msg::InfoChip,
+                    &text(|s| write!(s, "ESP32-S3 v{}.{}", major, minor)),
+                ));
+                rows.push(detail(
+                    Msg::InfoMac,
+                    &text(|s| {
+                        write!(
+                            s,
+                            "{:02X}:{:02X}:{:02X}:{:02X}:{:02X}:{:02X}",
+                            mac[0], mac[1], mac[2], mac[3], mac[4], mac[5]
+                        )
+                    }),
+                ));
+                rows.push(detail(
+                    Msg::InfoReset,
+                    i18n::tr(reset_cause_label(SystemService::reset_cause())),
+                ));
+            }
+            Page::Memory => {
+                let secs = SystemService::uptime().as_secs();
+                let heap = SystemService::heap();
+                let kb = i18n::tr(Msg::UnitKilobytes);
+                rows.push(detail(
+                    Msg::InfoUptime,
+                    &text(|s| write!(s, "{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)),
+                ));
+                rows.push(detail(
+                    Msg::InfoHeapUsed,
+                    &text(|s| write!(s, "{} {}", heap.used / 1024, kb)),
+                ));
+                rows.push(detail(
+                    Msg::InfoHeapFree,
+                    &text(|s| write!(s, "{} {}", heap.free / 1024, kb)),
+                ));
+            }
+            Page::Network => {
+                let config = NetworkService::stack().and_then(|stack| stack.config_v4());
+                rows.push(detail(Msg::WifiNetwork, &NetworkService::ssid()));
+                match config {
+                    Some(config) => {
+                        rows.push(detail(
+                            Msg::WifiAddress,
+                            &text(|s| write!(s, "{}", config.address)),
+                        ));
+                        let gateway = text(|s| match config.gateway {
+                            Some(gateway) => write!(s, "{}", gateway),
+                            None => write!(s, "-"),
+                        });
+                        rows.push(detail(Msg::InfoGateway, &gateway));
+                        for server in &config.dns_servers {
+                            rows.push(detail(Msg::InfoDns, &text(|s| write!(s, "{}", server))));
+                        }
+                    }
+                    None => rows.push(detail(Msg::WifiStatus, i18n::tr(Msg::WifiDisconnected))),
+                }
+            }
+            Page::Time => {
+                let synced = if ClockService::is_synced() {
+                    Msg::ValueYes
+                } else {
+                    Msg::ValueNo
+                };
+                match ClockService::get_current_time() {
+                    Some(now) => {
+                        let time = now.to_zoned(TimeZone::UTC);
+                        let date = text(|s| {
+                            write!(
+                                s,
+                                "{:04}-{:02}-{:02}",
+                                time.year(),
+                                time.month(),
+                                time.day()
+                            )
+                        });
+                        let clock = text(|s| {
+                            write!(
+                                s,
+                                "{:02}:{:02}:{:02} UTC",
+                                time.hour(),
+                                time.minute(),
+                                time.second()
+                            )
+                        });
+                        rows.push(detail(Msg::InfoDate, &date));
+                        rows.push(detail(Msg::InfoTime, &clock));
+                    }
+                    None => {
+                        rows.push(detail(Msg::InfoDate, "-"));
+                        rows.push(detail(Msg::InfoTime, "-"));
+                    }
+                }
+                rows.push(detail(Msg::InfoTimeSynced, i18n::tr(synced)));
+            }
+            // Drawn as wrapped text instead of rows
+            Page::About => {}
+        }
+        rows
+    }
+}
+
+fn reset_cause_label(cause: ResetCause) -> Msg {
+    match cause {
+        ResetCause::PowerOn => Msg::ResetPowerOn,
+        ResetCause::Software => Msg::ResetSoftware,
+        ResetCause::DeepSleep => Msg::ResetDeepSleep,
+        ResetCause::Watchdog => Msg::ResetWatchdog,
+        ResetCause::BrownOut => Msg::ResetBrownOut,
+        ResetCause::Usb => Msg::ResetUsb,
+        ResetCause::Unknown => Msg::ResetUnknown,
+    }
+}
 
+/// Device, memory, network and clock details on pages switched with Left/Right.
+/// Up/Down scroll pages that don't fit.
 pub struct InfoScreen {
     display_bounds: Rectangle,
-    initial_draw: bool,
+    page: usize,
+    list: ListView,
 }
 
 impl InfoScreen {
     pub fn new(display_bounds: Rectangle) -> Self {
         Self {
             display_bounds,
-            initial_draw: true,
+            page: 0,
+            list: ListView::new(Rectangle::zero()),
         }
     }
+
+    fn turn_page(&mut self, forward: bool) {
+        let count = PAGES.len();
+        self.page = if forward {
+            (self.page + 1) % count
+        } else {
+            (self.page + count - 1) % count
+        };
+        self.list.select(0);
+    }
+
+    /// Area below the page title.
+    fn body(&self, theme: &UiTheme) -> Rectangle {
+        let content_area = theme.content_area(self.display_bounds);
+        let top = content_area.top_left.y + PADDING + theme.spacing.row_height;
+        let bottom =
+            content_area.top_left.y + content_area.size.height as i32 - DOT_SIZE as i32 * 2;
+        Rectangle::new(
+            Point::new(content_area.top_left.x, top),
+            Size::new(content_area.size.width, (bottom - top).max(0) as u32),
+        )
+    }
+
+    /// One dot per page at the bottom, the current one filled.
+    fn draw_page_dots(&self, display: &mut Compositor, theme: &UiTheme) -> Result<(), Infallible> {
+        let content_area = theme.content_area(self.display_bounds);
+        let spacing = DOT_SIZE as i32 * 2;
+        let y = content_area.top_left.y + content_area.size.height as i32 - DOT_SIZE as i32;
+        let left = content_area.center().x - spacing * (PAGES.len() as i32 - 1) / 2;
+        for idx in 0..PAGES.len() {
+            let style = if idx == self.page {
+                PrimitiveStyle::with_fill(theme.accent)
+            } else {
+                PrimitiveStyle::with_stroke(theme.accent, 1)
+            };
+            Circle::with_center(Point::new(left + spacing * idx as i32, y), DOT_SIZE)
+                .into_styled(style)
+                .draw(display)?;
+        }
+
+        Ok(())
+    }
 }
 
 impl Screen for InfoScreen {
     fn update(&mut self, event: ButtonEvent) -> Transition {
         match event {
+            ButtonEvent::Up | ButtonEvent::Down => {
+                self.list.handle(event);
+                Transition::Stay
+            }
+            ButtonEvent::Right | ButtonEvent::A => {
+                self.turn_page(true);
+                Transition::Stay
+            }
+            ButtonEvent::Left => {
+                self.turn_page(false);
+                Transition::Stay
+            }
             ButtonEvent::B => Transition::Pop,
             _ => Transition::Stay,
         }
     }
 
-    fn draw(&mut self, display: &mut LilkaDisplay, _state: &UIState) {
-        if self.initial_draw {
-            let content_area = Rectangle::new(
-                Point::new(0, 30),
+    fn resize(&mut self, display_bounds: Rectangle) {
+        self.display_bounds = display_bounds;
+    }
+
+    fn draw(&mut self, display: &mut Compositor, _state: &UIState) -> Result<(), Infallible> {
+        let theme = theme::current();
+        let content_area = theme.content_area(self.display_bounds);
+        display.fill_solid(&content_area, theme.background)?;
+
+        let page = PAGES[self.page];
+        let margin = theme.spacing.margin;
+        let title_top = content_area.top_left.y + PADDING;
+        Text::with_baseline(
+            i18n::tr(page.title()),
+            Point::new(margin, title_top),
+            theme.accent_style(),
+            Baseline::Top,
+        )
+        .draw(display)?;
+        let position = text(|s| write!(s, "{}/{}", self.page + 1, PAGES.len()));
+        Text::with_text_style(
+            &position,
+            Point::new(content_area.size.width as i32 - margin, title_top),
+            theme.text_style(),
+            TextStyleBuilder::new()
+                .alignment(Alignment::Right)
+                .baseline(Baseline::Top)
+                .build(),
+        )
+        .draw(display)?;
+
+        let body = self.body(theme);
+        if page == Page::About {
+            let text_area = Rectangle::new(
+                body.top_left + Point::new(margin, 0),
                 Size::new(
-                    self.display_bounds.size.width,
-                    self.display_bounds.size.height - 30,
+                    body.size.width.saturating_sub(margin as u32 * 2),
+                    body.size.height,
                 ),
             );
-            display.fill_solid(&content_area, Rgb565::BLACK).unwrap();
-
-            let text_style = MonoTextStyle::new(&FONT_10X20, Rgb565::WHITE);
-            Text::new("Info Screen", Point::zero(), text_style)
-                .align_to(&content_area, horizontal::Center, vertical::Center)
-                .draw(display)
-                .unwrap();
-
-            self.initial_draw = false;
+            font::draw_wrapped(
+                display,
+                i18n::tr(Msg::InfoAbout),
+                text_area,
+                theme.text_style(),
+                HorizontalAlignment::Center,
+            )?;
+        } else {
+            self.list.set_bounds(body);
+            self.list.set_rows(page.rows());
+            // Read-only details, nothing is highlighted
+            self.list.draw(display, theme, false)?;
         }
-    }
-
-    fn ensure_redraw(&mut self) {
-        self.initial_draw = true;
+        self.draw_page_dots(display, theme)
     }
 }
@@ -0,0 +1,230 @@
+use alloc::vec::Vec;
+use core::convert::Infallible;
+use embassy_time::Duration;
+use embedded_graphics::primitives::Rectangle;
+use embedded_graphics::{
+    prelude::*,
+    text::{Alignment, Baseline, Text, TextStyleBuilder},
+};
+use heapless::String;
+use log::Level;
+
+use crate::format;
+use crate::i18n::{self, Msg};
+use crate::services::{LogRecord, LogService};
+use crate::state::ButtonEvent;
+use crate::ui::compositor::Compositor;
+use crate::ui::dialog::Dialog;
+use crate::ui::theme::{self, UiTheme};
+use crate::ui::widgets::{ListView, Widget};
+use crate::ui::{Screen, Transition, UIState};
+
+// Gap between the header and the title
+const PADDING: i32 = 10;
+// Room kept at the left of a row for the level letter
+const LEVEL_WIDTH: i32 = 14;
+const DETAILS_DIALOG: u16 = 1;
+
+/// Least severe level shown by each filter step, Left/Right switch between them.
+const FILTERS: [(Level, Msg); 4] = [
+    (Level::Trace, Msg::LogFilterAll),
+    (Level::Info, Msg::LogFilterInfo),
+    (Level::Warn, Msg::LogFilterWarnings),
+    (Level::Error, Msg::LogFilterErrors),
+];
+
+/// Recent log records, newest first. A shows a whole record, C clears the log.
+pub struct LogScreen {
+    display_bounds: Rectangle,
+    filter: usize,
+    records: Vec<LogRecord>,
+    // `LogService::written` at the last refresh
+    seen: u32,
+    list: ListView,
+}
+
+impl LogScreen {
+    pub fn new(display_bounds: Rectangle) -> Self {
+        let mut screen = Self {
+            display_bounds,
+            filter: 1,
+            records: Vec::new(),
+            seen: 0,
+            list: ListView::new(Rectangle::zero()),
+        };
+        screen.refresh();
+        screen
+    }
+
+    fn refresh(&mut self) {
+        self.seen = LogService::written();
+        self.records = LogService::records(FILTERS[self.filter].0);
+        self.records.reverse();
+        self.list.set_rows(self.records.iter().map(|_| ""));
+    }
+
+    fn layout(&mut self, theme: &UiTheme) {
+        let content_area = theme.content_area(self.display_bounds);
+        let top = content_area.top_left.y + PADDING + theme.spacing.row_height;
+        let bottom = content_area.top_left.y + content_area.size.height as i32;
+        self.list.set_bounds(Rectangle::new(
+            Point::new(content_area.top_left.x, top),
+            Size::new(content_area.size.width, (bottom - top).max(0) as u32),
+        ));
+    }
+
+    fn draw_row(
+        &self,
+        display: &mut Compositor,
+        theme: &UiTheme,
+        record: &LogRecord,
+        row: Rectangle,
+        selected: bool,
+    ) -> Result<(), Infallible> {
+        let margin = theme.spacing.margin;
+        let center_y = row.center().y;
+        let (letter, letter_color) = match record.level {
+            Level::Error => ("E", theme.warning),
+            Level::Warn => ("W", theme.warning),
+            Level::Info => ("I", theme.accent),
+            Level::Debug => ("D", theme.foreground),
+            Level::Trace => ("T", theme.foreground),
+        };
+        Text::with_baseline(
+            letter,
+            Point::new(row.top_left.x + margin, center_y),
+            theme.style(letter_color),
+            Baseline::Middle,
+        )
+        .draw(display)?;
+
+        // Long messages are cut off at the right edge
+        let left = row.top_left.x + margin + LEVEL_WIDTH;
+        let text_area = Rectangle::new(
+            Point::new(left, row.top_left.y),
+            Size::new(
+                (row.top_left.x + row.size.width as i32 - margin - left).max(0) as u32,
+                row.size.height,
+            ),
+        );
+        Text::with_baseline(
+            &summary(record),
+            Point::new(left, center_y),
+            theme.style(theme.item_color(selected)),
+            Baseline::Middle,
+        )
+        .draw(&mut display.clipped(&text_area))?;
+
+        Ok(())
+    }
+}
+
+/// Time since boot and module in front of the message, as on the serial console.
+fn summary(record: &LogRecord) -> String<128> {
+    let secs = record.timestamp.as_secs();
+    format!(
+        128,
+        "{}:{:02}:{:02} {}: {}",
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60,
+        record.module,
+        record.message
+    )
+}
+
+impl Screen for LogScreen {
+    fn update(&mut self, event: ButtonEvent) -> Transition {
+        match event {
+            ButtonEvent::Up | ButtonEvent::Down => {
+                self.list.handle(event);
+                Transition::Stay
+            }
+            ButtonEvent::Left | ButtonEvent::Right => {
+                let count = FILTERS.len();
+                self.filter = if matches!(event, ButtonEvent::Right) {
+                    (self.filter + 1) % count
+                } else {
+                    (self.filter + count - 1) % count
+                };
+                self.list.select(0);
+                self.refresh();
+                Transition::Stay
+            }
+            ButtonEvent::A => match self.records.get(self.list.selected()) {
+                Some(record) => Transition::Dialog(Dialog::alert(
+                    DETAILS_DIALOG,
+                    record.module,
+                    summary(record).as_str(),
+                )),
+                None => Transition::Stay,
+            },
+            ButtonEvent::C => {
+                LogService::clear();
+                self.refresh();
+                Transition::Stay
+            }
+            ButtonEvent::B => Transition::Pop,
+            _ => Transition::Stay,
+        }
+    }
+
+    fn on_tick(&mut self, _elapsed: Duration) -> Transition {
+        // Pick up records logged while the screen is shown
+        if LogService::written() != self.seen {
+            self.refresh();
+        }
+        Transition::Stay
+    }
+
+    fn resize(&mut self, display_bounds: Rectangle) {
+        self.display_bounds = display_bounds;
+    }
+
+    fn draw(&mut self, display: &mut Compositor, _state: &UIState) -> Result<(), Infallible> {
+        let theme = theme::current();
+        let content_area = theme.content_area(self.display_bounds);
+        display.fill_solid(&content_area, theme.background)?;
+        self.layout(theme);
+
+        let margin = theme.spacing.margin;
+        let title_top = content_area.top_left.y + PADDING;
+        Text::with_baseline(
+            i18n::tr(Msg::MenuLog),
+            Point::new(margin, title_top),
+            theme.accent_style(),
+            Baseline::Top,
+        )
+        .draw(display)?;
+        Text::with_text_style(
+            &format!(48, "< {} >", i18n::tr(FILTERS[self.filter].1)),
+            Point::new(content_area.size.width as i32 - margin, title_top),
+            theme.text_style(),
+            TextStyleBuilder::new()
+                .alignment(Alignment::Right)
+                .baseline(Baseline::Top)
+                .build(),
+        )
+        .draw(display)?;
+
+        if self.records.is_empty() {
+            Text::with_text_style(
+                i18n::tr(Msg::LogEmpty),
+                self.list.bounds().center(),
+                theme.text_style(),
+                TextStyleBuilder::new()
+                    .alignment(Alignment::Center)
+                    .baseline(Baseline::Middle)
+                    .build(),
+            )
+            .draw(display)?;
+            return;
+        }
+
+        for (idx, row) in self.list.rows_in_view(theme) {
+            let selected = idx == self.list.selected();
+            self.draw_row(display, theme, &self.records[idx], row, selected)?;
+        }
+        self.list.draw_scrollbar(display, theme)
+    }
+}
@@ -1,151 +1,185 @@
-use crate::display::LilkaDisplay;
-use crate::state::ButtonEvent;
-use crate::ui::screens::{InfoScreen, WifiScreen};
-use crate::ui::{Screen, Transition, UIState};
 use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::convert::Infallible;
 use embedded_graphics::primitives::Rectangle;
-
-use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
-use embedded_graphics::mono_font::iso_8859_10::FONT_10X20;
-use embedded_menu::{
-    interaction::{programmed::Programmed, Interaction, Navigation},
-    items::MenuItem,
-    theme::Theme,
-    Menu, MenuStyle,
+use embedded_graphics::{
+    prelude::*,
+    text::{Alignment, Baseline, Text, TextStyleBuilder},
 };
 
-#[derive(Copy, Clone)]
-pub struct MenuColor {
-    pub main_color: Rgb565,
-    pub selected_text_color: Rgb565,
-}
-
-impl Theme for MenuColor {
-    type Color = Rgb565;
-    fn text_color(&self) -> Self::Color {
-        self.main_color
-    }
-    fn selected_text_color(&self) -> Self::Color {
-        self.selected_text_color
-    }
-    fn selection_color(&self) -> Self::Color {
-        self.main_color
-    }
-}
+use crate::i18n::{self, Msg};
+use crate::state::ButtonEvent;
+use crate::ui::compositor::Compositor;
+use crate::ui::menu::{MenuEntry, MenuItem, MenuRegistry};
+use crate::ui::theme::{self, UiTheme};
+use crate::ui::widgets::{ListView, Toggle, Widget};
+use crate::ui::{Screen, Transition, UIState};
 
-pub type MainMenuType = Menu<
-    &'static str,
-    Programmed,
-    embedded_layout::prelude::Chain<
-        embedded_menu::collection::MenuItems<
-            [MenuItem<&'static str, (), &'static str, true>; 2],
-            MenuItem<&'static str, (), &'static str, true>,
-            (),
-        >,
-    >,
-    (),
-    embedded_menu::selection_indicator::StaticPosition,
-    embedded_menu::selection_indicator::style::Line,
-    MenuColor,
->;
+// Gap between the header and the first entry
+const LIST_PADDING: i32 = 10;
+const ICON_SIZE: u32 = 14;
 
+/// A list of menu entries: the main menu, or a submenu when it has a title.
 pub struct MenuScreen {
-    menu: MainMenuType,
-    selected_idx: usize,
+    entries: Vec<MenuEntry>,
+    title: Option<Msg>,
+    list: ListView,
     display_bounds: Rectangle,
-    initial_draw: bool,
-    menu_dirty: bool,
 }
 
 impl MenuScreen {
+    /// The main menu with the entries registered at startup.
     pub fn new(display_bounds: Rectangle) -> Self {
-        let style = MenuStyle::new(MenuColor {
-            main_color: Rgb565::new(51, 255, 153),
-            selected_text_color: Rgb565::RED,
-        })
-        .with_font(&FONT_10X20)
-        .with_title_font(&FONT_10X20);
-
-        let menu = Menu::with_style("", style)
-            .add_menu_items([MenuItem::new("Info", ">"), MenuItem::new("Network", ">")])
-            .build();
-
-        Self {
-            menu,
-            selected_idx: 0,
+        Self::with_entries(display_bounds, None, MenuRegistry::entries())
+    }
+
+    fn with_entries(
+        display_bounds: Rectangle,
+        title: Option<Msg>,
+        entries: Vec<MenuEntry>,
+    ) -> Self {
+        let mut screen = Self {
+            list: ListView::with_len(Rectangle::zero(), entries.len()),
+            entries,
+            title,
             display_bounds,
-            initial_draw: true,
-            menu_dirty: true,
+        };
+        screen.layout(theme::current());
+        screen
+    }
+
+    fn layout(&mut self, theme: &UiTheme) {
+        let content_area = theme.content_area(self.display_bounds);
+        let mut top = content_area.top_left.y + LIST_PADDING;
+        if self.title.is_some() {
+            top += theme.spacing.row_height;
         }
+        let bottom = content_area.top_left.y + content_area.size.height as i32;
+        self.list.set_bounds(Rectangle::new(
+            Point::new(content_area.top_left.x, top),
+            Size::new(content_area.size.width, (bottom - top).max(0) as u32),
+        ));
     }
-}
 
-impl Screen for MenuScreen {
-    fn update(&mut self, event: ButtonEvent) -> Transition {
-        match event {
-            ButtonEvent::Up => {
-                self.menu
-                    .interact(Interaction::Navigation(Navigation::Previous));
-                self.selected_idx = self.selected_idx.saturating_sub(1);
-                self.menu_dirty = true;
+    /// Run the selected entry: open its screen or submenu, or change its value.
+    fn activate(&self, forward: bool) -> Transition {
+        let Some(entry) = self.entries.get(self.list.selected()) else {
+            return Transition::Stay;
+        };
+        match &entry.item {
+            MenuItem::Screen(factory) if forward => Transition::Push(factory(self.display_bounds)),
+            MenuItem::Submenu(entries) if forward => Transition::Push(Box::new(
+                Self::with_entries(self.display_bounds, Some(entry.label), entries.clone()),
+            )),
+            MenuItem::Toggle { get, set } => {
+                set(!get());
                 Transition::Stay
             }
-            ButtonEvent::Down => {
-                self.menu
-                    .interact(Interaction::Navigation(Navigation::Next));
-                self.selected_idx = (self.selected_idx + 1) % 2;
-                self.menu_dirty = true;
+            MenuItem::Value { change, .. } => {
+                change(forward);
                 Transition::Stay
             }
-            ButtonEvent::Right | ButtonEvent::A => match self.selected_idx {
-                0 => Transition::Push(Box::new(InfoScreen::new(self.display_bounds))),
-                1 => Transition::Push(Box::new(WifiScreen::new(self.display_bounds))),
-                _ => Transition::Stay,
-            },
             _ => Transition::Stay,
         }
     }
 
-    fn draw(&mut self, display: &mut LilkaDisplay, state: &UIState) {
-        // Define the "Slot" for the menu: everything below the header
-        let offset = Point::new(20, 50);
-        let menu_area = Rectangle::new(
-            offset,
-            Size::new(
-                self.display_bounds.size.width - (offset.x as u32) * 2,
-                self.display_bounds.size.height - offset.y as u32,
-            ),
-        );
-
-        if self.initial_draw {
-            // Clear only the content area below the header, not the full display
-            let content_area = Rectangle::new(
-                Point::new(0, 30),
-                Size::new(
-                    self.display_bounds.size.width,
-                    self.display_bounds.size.height - 30,
-                ),
+    fn draw_entry(
+        &self,
+        display: &mut Compositor,
+        theme: &UiTheme,
+        entry: &MenuEntry,
+        row: Rectangle,
+        selected: bool,
+    ) -> Result<(), Infallible> {
+        let color = theme.item_color(selected);
+        let margin = theme.spacing.margin;
+        let center_y = row.center().y;
+
+        if let MenuItem::Toggle { get, .. } = entry.item {
+            // The toggle widget draws its own label
+            Toggle::new(row, entry.label, get()).draw(display, theme, selected)?;
+        } else {
+            Text::with_baseline(
+                i18n::tr(entry.label),
+                Point::new(row.top_left.x + margin, center_y),
+                theme.style(color),
+                Baseline::Middle,
+            )
+            .draw(display)?;
+
+            let right = row.top_left.x + row.size.width as i32 - margin;
+            let value = match &entry.item {
+                MenuItem::Value { get, .. } => Some(get()),
+                _ => None,
+            };
+            Text::with_text_style(
+                value.as_deref().unwrap_or(">"),
+                Point::new(right, center_y),
+                theme.style(color),
+                TextStyleBuilder::new()
+                    .alignment(Alignment::Right)
+                    .baseline(Baseline::Middle)
+                    .build(),
+            )
+            .draw(display)?;
+        }
+
+        // Icons sit in the left margin
+        if let Some(icon) = entry.icon {
+            let area = Rectangle::with_center(
+                Point::new(row.top_left.x + margin / 2, center_y),
+                Size::new_equal(ICON_SIZE.min(margin as u32 - 4)),
             );
-            display.fill_solid(&content_area, Rgb565::BLACK).unwrap();
+            icon.draw(display, area, color)?;
+        }
 
-            let mut menu_display = crate::menu::MenuDisplay::new(display, menu_area);
-            self.menu.update(&menu_display);
-            self.menu.draw(&mut menu_display).unwrap();
+        Ok(())
+    }
+}
 
-            self.initial_draw = false;
-            self.menu_dirty = false;
-        } else if self.menu_dirty {
-            display.fill_solid(&menu_area, Rgb565::BLACK).unwrap();
+impl Screen for MenuScreen {
+    fn update(&mut self, event: ButtonEvent) -> Transition {
+        match event {
+            ButtonEvent::Up | ButtonEvent::Down => {
+                self.list.handle(event);
+                Transition::Stay
+            }
+            ButtonEvent::A | ButtonEvent::Right => self.activate(true),
+            ButtonEvent::Left => self.activate(false),
+            // The main menu is the bottom of the stack, only submenus close
+            ButtonEvent::B if self.title.is_some() => Transition::Pop,
+            _ => Transition::Stay,
+        }
+    }
 
-            let mut menu_display = crate::menu::MenuDisplay::new(display, menu_area);
-            self.menu.update(&menu_display);
-            self.menu.draw(&mut menu_display).unwrap();
+    fn resize(&mut self, display_bounds: Rectangle) {
+        self.display_bounds = display_bounds;
+        self.layout(theme::current());
+    }
 
-            self.menu_dirty = false;
+    fn draw(&mut self, display: &mut Compositor, _state: &UIState) -> Result<(), Infallible> {
+        let theme = theme::current();
+
+        // Clear only the content area below the header, not the full display
+        let content_area = theme.content_area(self.display_bounds);
+        display.fill_solid(&content_area, theme.background)?;
+
+        // The theme decides the header height and row size
+        self.layout(theme);
+        if let Some(title) = self.title {
+            Text::with_baseline(
+                i18n::tr(title),
+                Point::new(theme.spacing.margin, content_area.top_left.y + LIST_PADDING),
+                theme.accent_style(),
+                Baseline::Top,
+            )
+            .draw(display)?;
         }
-    }
 
-    fn ensure_redraw(&mut self) {
-        self.initial_draw = true;
+        for (idx, row) in self.list.rows_in_view(theme) {
+            let selected = idx == self.list.selected();
+            self.draw_entry(display, theme, &self.entries[idx], row, selected)?;
+        }
+        self.list.draw_scrollbar(display, theme)
     }
 }
@@ -1,7 +1,70 @@
+pub mod crash;
+pub mod diagnostics;
 pub mod info;
+pub mod log_viewer;
 pub mod main_menu;
+pub mod music;
+pub mod notifications;
+pub mod settings;
 pub mod wifi;
 
+pub use crash::CrashScreen;
+pub use diagnostics::DiagnosticsScreen;
 pub use info::InfoScreen;
+pub use log_viewer::LogScreen;
 pub use main_menu::MenuScreen;
+pub use music::MusicScreen;
+pub use notifications::NotificationsScreen;
+pub use settings::SettingsScreen;
 pub use wifi::WifiScreen;
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use core::fmt::Write;
+
+use crate::i18n::{self, Msg};
+use crate::ui::menu::{MenuEntry, MenuRegistry};
+use crate::ui::widgets::Icon;
+
+/// Put the built-in apps into the main menu. Call once at startup, before the UI task runs.
+pub fn register_menu() {
+    MenuRegistry::register(
+        MenuEntry::screen(Msg::MenuInfo, |bounds| Box::new(InfoScreen::new(bounds)))
+            .with_icon(Icon::Info),
+    );
+    MenuRegistry::register(
+        MenuEntry::screen(Msg::MenuNetwork, |bounds| Box::new(WifiScreen::new(bounds)))
+            .with_icon(Icon::Wifi),
+    );
+    MenuRegistry::register(
+        MenuEntry::screen(Msg::MenuMusic, |bounds| Box::new(MusicScreen::new(bounds)))
+            .with_icon(Icon::Play),
+    );
+    MenuRegistry::register(
+        MenuEntry::screen(Msg::MenuDiagnostics, |bounds| {
+            Box::new(DiagnosticsScreen::new(bounds))
+        })
+        .with_icon(Icon::Chart),
+    );
+    MenuRegistry::register(
+        MenuEntry::screen(Msg::MenuLog, |bounds| Box::new(LogScreen::new(bounds)))
+            .with_icon(Icon::Log),
+    );
+    MenuRegistry::register(
+        MenuEntry::submenu(Msg::MenuSettings, settings::menu_entries()).with_icon(Icon::Gear),
+    );
+}
+
+/// A "Label: value" row for the detail lists.
+fn detail(label: Msg, value: &str) -> String {
+    let mut row = String::new();
+    write!(row, "{}: {}", i18n::tr(label), value).unwrap();
+    row
+}
+
+/// A string written with `write!`, for rows that need formatting.
+fn text(f: impl FnOnce(&mut String) -> core::fmt::Result) -> String {
+    let mut s = String::new();
+    f(&mut s).unwrap();
+    s
+}
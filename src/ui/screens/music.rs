@@ -0,0 +1,245 @@
+use core::convert::Infallible;
+use core::fmt::Write;
+
+use crate::error::AudioError;
+use crate::format;
+use crate::i18n::{self, Msg};
+use crate::music::notes::{NOTE_B0, REST};
+use crate::music::LIBRARY;
+use crate::services::audio::{PlaybackState, PlayerStatus};
+use crate::services::{AudioService, NotificationService};
+use crate::state::ButtonEvent;
+use crate::ui::compositor::Compositor;
+use crate::ui::theme::{self, UiTheme};
+use crate::ui::widgets::{Icon, ListView, ProgressBar, Widget};
+use crate::ui::{Screen, Transition, UIState};
+use embassy_time::Duration;
+use embedded_graphics::primitives::{PrimitiveStyle, Rectangle};
+use embedded_graphics::{
+    prelude::*,
+    text::{Alignment, Text},
+};
+
+// Gap between the header and the song list
+const LIST_PADDING: i32 = 10;
+// Songs shown at once, the list scrolls beyond that
+const MAX_VISIBLE_SONGS: usize = 4;
+
+// Range covered by the note visualization, in octaves above NOTE_B0
+const NOTE_OCTAVES: f64 = 7.5;
+
+// Redraw rate while playing, so the note bar follows the melody
+const PLAYING_FRAME: Duration = Duration::from_millis(100);
+
+pub struct MusicScreen {
+    display_bounds: Rectangle,
+    list: ListView,
+    progress: ProgressBar,
+}
+
+impl MusicScreen {
+    pub fn new(display_bounds: Rectangle) -> Self {
+        let mut screen = Self {
+            display_bounds,
+            list: ListView::new(Rectangle::zero())
+                .with_rows(LIBRARY.iter().map(|entry| entry.name)),
+            progress: ProgressBar::new(Rectangle::zero()),
+        };
+        screen.layout(theme::current());
+        screen
+    }
+
+    /// Place the widgets, the theme decides the row height.
+    fn layout(&mut self, theme: &UiTheme) {
+        let rows = LIBRARY.len().min(MAX_VISIBLE_SONGS) as u32;
+        let content_area = theme.content_area(self.display_bounds);
+        self.list.set_bounds(Rectangle::new(
+            content_area.top_left + Point::new(0, LIST_PADDING),
+            Size::new(
+                content_area.size.width,
+                rows * theme.spacing.row_height as u32,
+            ),
+        ));
+
+        let margin = theme.spacing.margin;
+        let player = self.player_area(theme);
+        self.progress.set_bounds(Rectangle::new(
+            player.top_left + Point::new(margin, 30),
+            Size::new(player.size.width - margin as u32 * 2, 10),
+        ));
+    }
+
+    fn player_area(&self, theme: &UiTheme) -> Rectangle {
+        let list = self.list.bounds();
+        let top = list.top_left.y + list.size.height as i32 + 10;
+        Rectangle::new(
+            Point::new(0, top),
+            Size::new(
+                self.display_bounds.size.width,
+                self.display_bounds.size.height - top as u32,
+            ),
+        )
+    }
+
+    fn draw_list(
+        &self,
+        display: &mut Compositor,
+        theme: &UiTheme,
+        status: &PlayerStatus,
+    ) -> Result<(), Infallible> {
+        self.list.draw(display, theme, true)?;
+
+        // Mark the song that is playing in the left margin
+        let Some(song) = status.song else {
+            return;
+        };
+        if let Some((_, row)) = self.list.rows_in_view(theme).find(|&(idx, _)| idx == song) {
+            let marker = Rectangle::new(
+                row.top_left + Point::new(4, row.size.height as i32 / 2 - 5),
+                Size::new_equal(10),
+            );
+            let icon = match status.state {
+                PlaybackState::Paused => Icon::Pause,
+                _ => Icon::Play,
+            };
+            icon.draw(display, marker, theme.highlight)?;
+        }
+
+        Ok(())
+    }
+
+    fn draw_player(
+        &mut self,
+        display: &mut Compositor,
+        theme: &UiTheme,
+        status: &PlayerStatus,
+    ) -> Result<(), Infallible> {
+        let area = self.player_area(theme);
+        let margin = theme.spacing.margin;
+
+        let text_style = theme.text_style();
+        let width = area.size.width as i32 - margin * 2;
+
+        let label = match status.state {
+            PlaybackState::Playing => Msg::MusicPlaying,
+            PlaybackState::Paused => Msg::MusicPaused,
+            PlaybackState::Stopped => Msg::MusicStopped,
+        };
+        let total_ms = status
+            .song
+            .map(|song| LIBRARY[song].duration_ms())
+            .unwrap_or(0);
+        let time = format!(
+            32,
+            "{} {}:{:02}/{}:{:02}",
+            i18n::tr(label),
+            status.elapsed_ms / 60_000,
+            (status.elapsed_ms / 1000) % 60,
+            total_ms / 60_000,
+            (total_ms / 1000) % 60
+        );
+        Text::new(&time, area.top_left + Point::new(margin, 16), text_style).draw(display)?;
+
+        self.progress.set_fraction(status.elapsed_ms, total_ms);
+        self.progress.draw(display, theme, false)?;
+
+        // Current note: frequency readout and a level bar on a log scale
+        if status.note != REST {
+            let note = format!(16, "{} Hz", status.note as u32);
+            let bottom = area.top_left.y + area.size.height as i32;
+            Text::with_alignment(
+                &note,
+                Point::new(area.size.width as i32 - margin, bottom - 8),
+                text_style,
+                Alignment::Right,
+            )
+            .draw(display)?;
+
+            let level = (width as f64 * 0.6 * note_level(status.note)) as u32;
+            Rectangle::new(
+                Point::new(area.top_left.x + margin, bottom - 24),
+                Size::new(level.max(2), 16),
+            )
+            .into_styled(PrimitiveStyle::with_fill(theme.warning))
+            .draw(display)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Position of a note on a 0..1 scale, roughly logarithmic so that every octave gets the same width.
+fn note_level(freq: f64) -> f64 {
+    let mut ratio = freq / NOTE_B0;
+    let mut octaves = 0.0;
+    while ratio >= 2.0 {
+        ratio /= 2.0;
+        octaves += 1.0;
+    }
+    ((octaves + ratio - 1.0) / NOTE_OCTAVES).clamp(0.0, 1.0)
+}
+
+fn notify_failure(result: Result<(), AudioError>) {
+    if let Err(e) = result {
+        NotificationService::failed(Msg::NotifyPlaybackFailed, e.msg());
+    }
+}
+
+impl Screen for MusicScreen {
+    fn update(&mut self, event: ButtonEvent) -> Transition {
+        match event {
+            ButtonEvent::Up | ButtonEvent::Down => {
+                self.list.handle(event);
+                Transition::Stay
+            }
+            ButtonEvent::A => {
+                let status = AudioService::status();
+                if status.song == Some(self.list.selected())
+                    && status.state != PlaybackState::Stopped
+                {
+                    notify_failure(AudioService::toggle_pause());
+                } else {
+                    notify_failure(AudioService::play(self.list.selected()));
+                }
+                Transition::Stay
+            }
+            ButtonEvent::Right => {
+                let next = AudioService::status()
+                    .song
+                    .map(|song| (song + 1) % LIBRARY.len())
+                    .unwrap_or(0);
+                self.list.select(next);
+                notify_failure(AudioService::next());
+                Transition::Stay
+            }
+            ButtonEvent::C => {
+                notify_failure(AudioService::stop());
+                Transition::Stay
+            }
+            ButtonEvent::B => Transition::Pop,
+            _ => Transition::Stay,
+        }
+    }
+
+    fn resize(&mut self, display_bounds: Rectangle) {
+        self.display_bounds = display_bounds;
+        self.layout(theme::current());
+    }
+
+    fn frame_interval(&self) -> Option<Duration> {
+        match AudioService::status().state {
+            PlaybackState::Playing => Some(PLAYING_FRAME),
+            _ => None,
+        }
+    }
+
+    fn draw(&mut self, display: &mut Compositor, _state: &UIState) -> Result<(), Infallible> {
+        let status = AudioService::status();
+        let theme = theme::current();
+
+        display.fill_solid(&theme.content_area(self.display_bounds), theme.background)?;
+        self.layout(theme);
+        self.draw_list(display, theme, &status)?;
+        self.draw_player(display, theme, &status)
+    }
+}
@@ -0,0 +1,229 @@
+use core::convert::Infallible;
+use core::fmt::Write;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use alloc::vec::Vec;
+use embassy_time::{Duration, Instant};
+use embedded_graphics::primitives::Rectangle;
+use embedded_graphics::{
+    prelude::*,
+    text::{Alignment, Baseline, Text, TextStyleBuilder},
+};
+use heapless::String;
+
+use crate::format;
+use crate::i18n::{self, Msg};
+use crate::services::{Notification, NotificationService, Severity};
+use crate::state::ButtonEvent;
+use crate::ui::compositor::Compositor;
+use crate::ui::theme::{self, UiTheme};
+use crate::ui::widgets::{Icon, ListView, Widget};
+use crate::ui::{Screen, Transition, UIState};
+
+// Gap between the header and the title
+const PADDING: i32 = 10;
+const ICON_SIZE: u32 = 12;
+// Room kept at the right of a row for the age
+const AGE_WIDTH: i32 = 64;
+
+static OPEN: AtomicBool = AtomicBool::new(false);
+
+/// Notifications posted by the services, newest first.
+///
+/// A opens the screen attached to a notification, C clears the list.
+pub struct NotificationsScreen {
+    display_bounds: Rectangle,
+    notifications: Vec<Notification>,
+    list: ListView,
+}
+
+impl NotificationsScreen {
+    pub fn new(display_bounds: Rectangle) -> Self {
+        let mut screen = Self {
+            display_bounds,
+            notifications: Vec::new(),
+            list: ListView::new(Rectangle::zero()),
+        };
+        screen.refresh();
+        screen
+    }
+
+    /// Whether the list is on the stack, so the shortcut does not open it twice.
+    pub fn is_open() -> bool {
+        OPEN.load(Ordering::Acquire)
+    }
+
+    fn refresh(&mut self) {
+        self.notifications = NotificationService::history();
+        self.list.set_rows(self.notifications.iter().map(|_| ""));
+    }
+
+    fn layout(&mut self, theme: &UiTheme) {
+        let content_area = theme.content_area(self.display_bounds);
+        let top = content_area.top_left.y + PADDING + theme.spacing.row_height;
+        let bottom = content_area.top_left.y + content_area.size.height as i32;
+        self.list.set_bounds(Rectangle::new(
+            Point::new(content_area.top_left.x, top),
+            Size::new(content_area.size.width, (bottom - top).max(0) as u32),
+        ));
+    }
+
+    fn draw_row(
+        &self,
+        display: &mut Compositor,
+        theme: &UiTheme,
+        notification: &Notification,
+        row: Rectangle,
+        selected: bool,
+    ) -> Result<(), Infallible> {
+        let margin = theme.spacing.margin;
+        let center_y = row.center().y;
+        let (icon, icon_color) = match notification.severity {
+            Severity::Info => (Icon::Info, theme.accent),
+            Severity::Warning | Severity::Error => (Icon::Cross, theme.warning),
+        };
+        icon.draw(
+            display,
+            Rectangle::with_center(
+                Point::new(row.top_left.x + margin / 2, center_y),
+                Size::new_equal(ICON_SIZE),
+            ),
+            icon_color,
+        )?;
+
+        // Long messages are cut off before the age
+        let right = row.top_left.x + row.size.width as i32 - margin;
+        let text_area = Rectangle::new(
+            Point::new(row.top_left.x + margin, row.top_left.y),
+            Size::new(
+                (right - AGE_WIDTH - row.top_left.x - margin).max(0) as u32,
+                row.size.height,
+            ),
+        );
+        let color = theme.item_color(selected);
+        Text::with_baseline(
+            &notification.text,
+            Point::new(text_area.top_left.x, center_y),
+            theme.style(color),
+            Baseline::Middle,
+        )
+        .draw(&mut display.clipped(&text_area))?;
+
+        let mut age = age(notification.posted_at);
+        if notification.action.is_some() {
+            let _ = age.push_str(" >");
+        }
+        Text::with_text_style(
+            &age,
+            Point::new(right, center_y),
+            theme.style(color),
+            TextStyleBuilder::new()
+                .alignment(Alignment::Right)
+                .baseline(Baseline::Middle)
+                .build(),
+        )
+        .draw(display)?;
+
+        Ok(())
+    }
+}
+
+/// How long ago a notification was posted, in the largest whole unit.
+fn age(posted_at: Instant) -> String<24> {
+    let minutes = posted_at.elapsed().as_secs() / 60;
+    match minutes {
+        0 => format!(24, "{}", i18n::tr(Msg::AgeNow)),
+        1..=59 => format!(24, "{} {}", minutes, i18n::tr(Msg::AgeMinutes)),
+        _ => format!(24, "{} {}", minutes / 60, i18n::tr(Msg::AgeHours)),
+    }
+}
+
+impl Screen for NotificationsScreen {
+    fn update(&mut self, event: ButtonEvent) -> Transition {
+        match event {
+            ButtonEvent::Up | ButtonEvent::Down => {
+                self.list.handle(event);
+                Transition::Stay
+            }
+            ButtonEvent::A | ButtonEvent::Right => {
+                match self
+                    .notifications
+                    .get(self.list.selected())
+                    .and_then(|notification| notification.action)
+                {
+                    Some(open) => Transition::Push(open(self.display_bounds)),
+                    None => Transition::Stay,
+                }
+            }
+            ButtonEvent::C => {
+                NotificationService::clear();
+                self.refresh();
+                Transition::Stay
+            }
+            ButtonEvent::B | ButtonEvent::D => Transition::Pop,
+            _ => Transition::Stay,
+        }
+    }
+
+    fn on_enter(&mut self) {
+        OPEN.store(true, Ordering::Release);
+        NotificationService::mark_read();
+    }
+
+    fn on_exit(&mut self) {
+        OPEN.store(false, Ordering::Release);
+        NotificationService::mark_read();
+    }
+
+    fn on_resume(&mut self) {
+        NotificationService::mark_read();
+    }
+
+    fn on_tick(&mut self, _elapsed: Duration) -> Transition {
+        // Pick up notifications posted while the list is shown
+        if NotificationService::unread() > 0 {
+            NotificationService::mark_read();
+            self.refresh();
+        }
+        Transition::Stay
+    }
+
+    fn resize(&mut self, display_bounds: Rectangle) {
+        self.display_bounds = display_bounds;
+    }
+
+    fn draw(&mut self, display: &mut Compositor, _state: &UIState) -> Result<(), Infallible> {
+        let theme = theme::current();
+        let content_area = theme.content_area(self.display_bounds);
+        display.fill_solid(&content_area, theme.background)?;
+        self.layout(theme);
+
+        Text::with_baseline(
+            i18n::tr(Msg::NotificationsTitle),
+            Point::new(theme.spacing.margin, content_area.top_left.y + PADDING),
+            theme.accent_style(),
+            Baseline::Top,
+        )
+        .draw(display)?;
+
+        if self.notifications.is_empty() {
+            Text::with_text_style(
+                i18n::tr(Msg::NotificationsEmpty),
+                self.list.bounds().center(),
+                theme.text_style(),
+                TextStyleBuilder::new()
+                    .alignment(Alignment::Center)
+                    .baseline(Baseline::Middle)
+                    .build(),
+            )
+            .draw(display)?;
+            return;
+        }
+
+        for (idx, row) in self.list.rows_in_view(theme) {
+            let selected = idx == self.list.selected();
+            self.draw_row(display, theme, &self.notifications[idx], row, selected)?;
+        }
+        self.list.draw_scrollbar(display, theme)
+    }
+}
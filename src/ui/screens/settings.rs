@@ -0,0 +1,423 @@
+use core::convert::Infallible;
+use core::fmt::Write;
+
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::format;
+use crate::i18n::{self, Lang, Msg, Plural};
+use crate::services::settings::MIN_BRIGHTNESS;
+use crate::services::{Settings, SettingsService, SleepMode};
+use crate::state::ButtonEvent;
+use crate::ui::compositor::Compositor;
+use crate::ui::dialog::{Dialog, DialogId, DialogResult};
+use crate::ui::menu::MenuEntry;
+use crate::ui::theme::{self, THEMES};
+use crate::ui::widgets::{ListView, Slider, Widget};
+use crate::ui::{Screen, Transition, UIState};
+use embedded_graphics::primitives::Rectangle;
+use embedded_graphics::{
+    prelude::*,
+    text::{Alignment, Baseline, Text, TextStyleBuilder},
+};
+use heapless::String;
+
+// Gap between the header and the first row
+const LIST_PADDING: i32 = 14;
+
+// Choices offered for the inactivity timeouts, 0 means never
+const TIMEOUT_STEPS: [u16; 7] = [0, 15, 30, 60, 120, 300, 600];
+const BRIGHTNESS_STEP: u8 = 10;
+// Value texts are UTF-8, Cyrillic letters take two bytes each
+const VALUE_LEN: usize = 32;
+
+/// A preference shown as one row of the settings list.
+struct SettingItem {
+    label: Msg,
+    value: fn(&Settings) -> String<VALUE_LEN>,
+    /// Step the value forward (`true`) or backward (`false`).
+    change: fn(&mut Settings, bool),
+    /// Percentage to show as a slider instead of the value text.
+    slider: Option<fn(&Settings) -> u8>,
+    /// Dialog opened with A instead of stepping the value.
+    picker: Option<Picker>,
+}
+
+/// Picks a setting value in a dialog.
+struct Picker {
+    open: fn(DialogId, &Settings) -> Dialog,
+    apply: fn(&mut Settings, DialogResult),
+}
+
+// Dialog ids below the item count are pickers of that item
+const RESET_DIALOG: DialogId = 100;
+const RESET_DONE_DIALOG: DialogId = 101;
+
+const BOOT_SPLASH: SettingItem = SettingItem {
+    label: Msg::SettingBootSplash,
+    value: |s| on_off(s.boot_splash),
+    change: |s, _| s.boot_splash = !s.boot_splash,
+    slider: None,
+    picker: None,
+};
+
+const BOOT_SOUND: SettingItem = SettingItem {
+    label: Msg::SettingBootSound,
+    value: |s| on_off(s.startup_sound),
+    change: |s, _| s.startup_sound = !s.startup_sound,
+    slider: None,
+    picker: None,
+};
+
+const DIM_AFTER: SettingItem = SettingItem {
+    label: Msg::SettingDim,
+    value: |s| timeout(s.dim_timeout_secs),
+    change: |s, forward| s.dim_timeout_secs = step_timeout(s.dim_timeout_secs, forward),
+    slider: None,
+    picker: None,
+};
+
+const SCREEN_OFF: SettingItem = SettingItem {
+    label: Msg::SettingScreenOff,
+    value: |s| timeout(s.screen_off_timeout_secs),
+    change: |s, forward| {
+        s.screen_off_timeout_secs = step_timeout(s.screen_off_timeout_secs, forward)
+    },
+    slider: None,
+    picker: None,
+};
+
+const SLEEP: SettingItem = SettingItem {
+    label: Msg::SettingSleep,
+    value: |s| {
+        let label = match s.sleep_mode {
+            SleepMode::Off => Msg::ValueOff,
+            SleepMode::Light => Msg::SleepLight,
+            SleepMode::Deep => Msg::SleepDeep,
+        };
+        format!(VALUE_LEN, "{}", i18n::tr(label))
+    },
+    change: |s, forward| {
+        s.sleep_mode = match (s.sleep_mode, forward) {
+            (SleepMode::Off, true) | (SleepMode::Deep, false) => SleepMode::Light,
+            (SleepMode::Light, true) | (SleepMode::Off, false) => SleepMode::Deep,
+            (SleepMode::Deep, true) | (SleepMode::Light, false) => SleepMode::Off,
+        }
+    },
+    slider: None,
+    picker: None,
+};
+
+const BRIGHTNESS: SettingItem = SettingItem {
+    label: Msg::SettingBrightness,
+    value: |s| format!(VALUE_LEN, "{}%", s.brightness),
+    change: |s, forward| {
+        s.brightness = if forward {
+            (s.brightness + BRIGHTNESS_STEP).min(100)
+        } else {
+            s.brightness
+                .saturating_sub(BRIGHTNESS_STEP)
+                .max(MIN_BRIGHTNESS)
+        }
+    },
+    slider: Some(|s| s.brightness),
+    picker: Some(Picker {
+        open: |id, s| {
+            let title = i18n::tr(Msg::SettingBrightness);
+            let value = s.brightness as i32;
+            Dialog::number(id, title, value, MIN_BRIGHTNESS as i32, 100, 5)
+        },
+        apply: |s, result| {
+            if let DialogResult::Number(value) = result {
+                s.brightness = value.clamp(MIN_BRIGHTNESS as i32, 100) as u8;
+            }
+        },
+    }),
+};
+
+const ROTATION: SettingItem = SettingItem {
+    label: Msg::SettingRotation,
+    value: |s| format!(VALUE_LEN, "{}°", s.rotation.degrees()),
+    change: |s, forward| s.rotation = s.rotation.step(forward),
+    slider: None,
+    picker: None,
+};
+
+const THEME: SettingItem = SettingItem {
+    label: Msg::SettingTheme,
+    value: |s| {
+        let theme = &THEMES[s.theme as usize % THEMES.len()];
+        format!(VALUE_LEN, "{}", i18n::tr(theme.name))
+    },
+    change: |s, forward| {
+        let count = THEMES.len() as u8;
+        let current = s.theme % count;
+        s.theme = if forward {
+            (current + 1) % count
+        } else {
+            (current + count - 1) % count
+        }
+    },
+    slider: None,
+    picker: Some(Picker {
+        open: |id, s| {
+            let names = THEMES.iter().map(|theme| i18n::tr(theme.name));
+            let selected = s.theme as usize % THEMES.len();
+            Dialog::choice(id, i18n::tr(Msg::SettingTheme), names, selected)
+        },
+        apply: |s, result| {
+            if let DialogResult::Chosen(idx) = result {
+                s.theme = idx as u8;
+            }
+        },
+    }),
+};
+
+const LANGUAGE: SettingItem = SettingItem {
+    label: Msg::SettingLanguage,
+    value: |s| format!(VALUE_LEN, "{}", s.language.native_name()),
+    change: |s, forward| s.language = s.language.step(forward),
+    slider: None,
+    picker: Some(Picker {
+        open: |id, s| {
+            let names = Lang::ALL.iter().map(|lang| lang.native_name());
+            let selected = Lang::ALL.iter().position(|&lang| lang == s.language);
+            let title = i18n::tr(Msg::SettingLanguage);
+            Dialog::choice(id, title, names, selected.unwrap_or(0))
+        },
+        apply: |s, result| {
+            if let DialogResult::Chosen(idx) = result {
+                s.language = Lang::ALL[idx];
+            }
+        },
+    }),
+};
+
+const POWER_SAVING: SettingItem = SettingItem {
+    label: Msg::SettingPowerSaving,
+    value: |s| on_off(s.power_saving),
+    change: |s, _| s.power_saving = !s.power_saving,
+    slider: None,
+    picker: None,
+};
+
+const ITEMS: [SettingItem; 10] = [
+    BOOT_SPLASH,
+    BOOT_SOUND,
+    DIM_AFTER,
+    SCREEN_OFF,
+    SLEEP,
+    BRIGHTNESS,
+    ROTATION,
+    THEME,
+    LANGUAGE,
+    POWER_SAVING,
+];
+
+fn on_off(value: bool) -> String<VALUE_LEN> {
+    let msg = if value { Msg::ValueOn } else { Msg::ValueOff };
+    format!(VALUE_LEN, "{}", i18n::tr(msg))
+}
+
+fn timeout(secs: u16) -> String<VALUE_LEN> {
+    let (count, unit) = match secs {
+        0 => return format!(VALUE_LEN, "{}", i18n::tr(Msg::ValueNever)),
+        s if s % 60 == 0 => (s as u32 / 60, Plural::Minutes),
+        s => (s as u32, Plural::Seconds),
+    };
+    format!(VALUE_LEN, "{} {}", count, i18n::plural(unit, count))
+}
+
+fn step_timeout(secs: u16, forward: bool) -> u16 {
+    let idx = TIMEOUT_STEPS
+        .iter()
+        .position(|&step| step >= secs)
+        .unwrap_or(0);
+    let next = if forward {
+        (idx + 1) % TIMEOUT_STEPS.len()
+    } else {
+        idx.checked_sub(1).unwrap_or(TIMEOUT_STEPS.len() - 1)
+    };
+    TIMEOUT_STEPS[next]
+}
+
+/// Menu entry showing one of the settings items, stepped with Left/Right.
+macro_rules! setting_entry {
+    ($item:ident) => {
+        MenuEntry::value(
+            $item.label,
+            || ($item.value)(&SettingsService::get()),
+            |forward| SettingsService::update(|settings| ($item.change)(settings, forward)),
+        )
+    };
+}
+
+/// The settings submenu of the main menu: common settings grouped by topic and the full list.
+pub fn menu_entries() -> Vec<MenuEntry> {
+    vec![
+        MenuEntry::submenu(
+            Msg::MenuDisplay,
+            vec![
+                setting_entry!(BRIGHTNESS),
+                setting_entry!(ROTATION),
+                setting_entry!(THEME),
+            ],
+        ),
+        MenuEntry::toggle(
+            Msg::SettingBootSound,
+            || SettingsService::get().startup_sound,
+            |on| SettingsService::update(|settings| settings.startup_sound = on),
+        ),
+        setting_entry!(LANGUAGE),
+        MenuEntry::screen(Msg::MenuAllSettings, |bounds| {
+            Box::new(SettingsScreen::new(bounds))
+        }),
+    ]
+}
+
+pub struct SettingsScreen {
+    display_bounds: Rectangle,
+    list: ListView,
+}
+
+impl SettingsScreen {
+    pub fn new(display_bounds: Rectangle) -> Self {
+        let mut screen = Self {
+            display_bounds,
+            list: ListView::with_len(Rectangle::zero(), ITEMS.len()),
+        };
+        screen.layout();
+        screen
+    }
+
+    fn layout(&mut self) {
+        let content_area = theme::current().content_area(self.display_bounds);
+        self.list.set_bounds(Rectangle::new(
+            content_area.top_left + Point::new(0, LIST_PADDING),
+            Size::new(
+                content_area.size.width,
+                content_area.size.height.saturating_sub(LIST_PADDING as u32),
+            ),
+        ));
+    }
+
+    fn change_selected(&mut self, forward: bool) {
+        let item = &ITEMS[self.list.selected()];
+        SettingsService::update(|settings| (item.change)(settings, forward));
+    }
+
+    /// Open the picker of the selected item, or step it if it has none.
+    fn activate_selected(&mut self) -> Transition {
+        let idx = self.list.selected();
+        match &ITEMS[idx].picker {
+            Some(picker) => {
+                Transition::Dialog((picker.open)(idx as DialogId, &SettingsService::get()))
+            }
+            None => {
+                self.change_selected(true);
+                Transition::Stay
+            }
+        }
+    }
+}
+
+impl Screen for SettingsScreen {
+    fn update(&mut self, event: ButtonEvent) -> Transition {
+        match event {
+            ButtonEvent::Up | ButtonEvent::Down => {
+                self.list.handle(event);
+                Transition::Stay
+            }
+            ButtonEvent::A => self.activate_selected(),
+            ButtonEvent::Right => {
+                self.change_selected(true);
+                Transition::Stay
+            }
+            ButtonEvent::Left => {
+                self.change_selected(false);
+                Transition::Stay
+            }
+            ButtonEvent::B => Transition::Pop,
+            ButtonEvent::C => Transition::Dialog(Dialog::confirm(
+                RESET_DIALOG,
+                i18n::tr(Msg::SettingsReset),
+                i18n::tr(Msg::SettingsResetQuestion),
+            )),
+            _ => Transition::Stay,
+        }
+    }
+
+    fn on_dialog_result(&mut self, id: DialogId, result: DialogResult) -> Transition {
+        match (id, result) {
+            (_, DialogResult::Cancelled) | (RESET_DONE_DIALOG, _) => Transition::Stay,
+            (RESET_DIALOG, DialogResult::Confirmed(true)) => {
+                SettingsService::update(|settings| *settings = Settings::DEFAULT);
+                Transition::Dialog(Dialog::alert(
+                    RESET_DONE_DIALOG,
+                    i18n::tr(Msg::SettingsReset),
+                    i18n::tr(Msg::SettingsResetDone),
+                ))
+            }
+            (id, result) => {
+                if let Some(picker) = ITEMS.get(id as usize).and_then(|item| item.picker.as_ref()) {
+                    SettingsService::update(|settings| (picker.apply)(settings, result));
+                }
+                Transition::Stay
+            }
+        }
+    }
+
+    fn on_exit(&mut self) {
+        // Done changing things, no need to wait for the save delay
+        SettingsService::flush();
+    }
+
+    fn resize(&mut self, display_bounds: Rectangle) {
+        self.display_bounds = display_bounds;
+        self.layout();
+    }
+
+    fn draw(&mut self, display: &mut Compositor, _state: &UIState) -> Result<(), Infallible> {
+        let theme = theme::current();
+        let content_area = theme.content_area(self.display_bounds);
+        display.fill_solid(&content_area, theme.background)?;
+
+        // The theme decides the header height and row size
+        self.layout();
+        let settings = SettingsService::get();
+        for (idx, row) in self.list.rows_in_view(theme) {
+            let item = &ITEMS[idx];
+            let selected = idx == self.list.selected();
+
+            if let Some(slider) = item.slider {
+                Slider::new(row, item.label, 0, 100, 1)
+                    .with_value(slider(&settings) as i32)
+                    .draw(display, theme, selected)?;
+                continue;
+            }
+
+            let text_style = theme.style(theme.item_color(selected));
+            let center_y = row.center().y;
+            Text::with_baseline(
+                i18n::tr(item.label),
+                Point::new(row.top_left.x + theme.spacing.margin, center_y),
+                text_style,
+                Baseline::Middle,
+            )
+            .draw(display)?;
+            let right = row.top_left.x + row.size.width as i32 - theme.spacing.margin;
+            Text::with_text_style(
+                &(item.value)(&settings),
+                Point::new(right, center_y),
+                text_style,
+                TextStyleBuilder::new()
+                    .alignment(Alignment::Right)
+                    .baseline(Baseline::Middle)
+                    .build(),
+            )
+            .draw(display)?;
+        }
+        self.list.draw_scrollbar(display, theme)
+    }
+}
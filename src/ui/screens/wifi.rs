@@ -1,25 +1,55 @@
-use crate::display::LilkaDisplay;
+use alloc::string::String;
+use core::convert::Infallible;
+use core::fmt::Write;
+
+use crate::i18n::{self, Msg};
+use crate::services::NetworkService;
 use crate::state::ButtonEvent;
+use crate::ui::compositor::Compositor;
+use crate::ui::theme;
+use crate::ui::widgets::{ListView, Widget};
 use crate::ui::{Screen, Transition, UIState};
 use embedded_graphics::primitives::Rectangle;
 use embedded_graphics::{
-    mono_font::iso_8859_10::FONT_10X20, mono_font::MonoTextStyle, pixelcolor::Rgb565, prelude::*,
-    text::Text,
+    prelude::*,
+    text::{Baseline, Text},
 };
-use embedded_layout::prelude::*;
 
+// Gap between the header and the title
+const PADDING: i32 = 10;
+
+/// Connection details of the Wi-Fi link.
 pub struct WifiScreen {
     display_bounds: Rectangle,
-    initial_draw: bool,
+    details: ListView,
 }
 
 impl WifiScreen {
     pub fn new(display_bounds: Rectangle) -> Self {
         Self {
             display_bounds,
-            initial_draw: true,
+            details: ListView::new(Rectangle::zero()),
         }
     }
+
+    fn rows(state: &UIState) -> [String; 3] {
+        let status = if state.wifi_connected {
+            Msg::WifiConnected
+        } else {
+            Msg::WifiDisconnected
+        };
+        let mut address = String::new();
+        match NetworkService::stack().and_then(|stack| stack.config_v4()) {
+            Some(config) => write!(address, "{}", config.address.address()).unwrap(),
+            None => address.push('-'),
+        }
+
+        [
+            detail(Msg::WifiNetwork, &NetworkService::ssid()),
+            detail(Msg::WifiStatus, i18n::tr(status)),
+            detail(Msg::WifiAddress, &address),
+        ]
+    }
 }
 
 impl Screen for WifiScreen {
@@ -30,28 +60,36 @@ impl Screen for WifiScreen {
         }
     }
 
-    fn draw(&mut self, display: &mut LilkaDisplay, _state: &UIState) {
-        if self.initial_draw {
-            let content_area = Rectangle::new(
-                Point::new(0, 30),
-                Size::new(
-                    self.display_bounds.size.width,
-                    self.display_bounds.size.height - 30,
-                ),
-            );
-            display.fill_solid(&content_area, Rgb565::BLACK).unwrap();
-
-            let text_style = MonoTextStyle::new(&FONT_10X20, Rgb565::WHITE);
-            Text::new("Wifi Config", Point::zero(), text_style)
-                .align_to(&content_area, horizontal::Center, vertical::Center)
-                .draw(display)
-                .unwrap();
-
-            self.initial_draw = false;
-        }
+    fn resize(&mut self, display_bounds: Rectangle) {
+        self.display_bounds = display_bounds;
     }
 
-    fn ensure_redraw(&mut self) {
-        self.initial_draw = true;
+    fn draw(&mut self, display: &mut Compositor, state: &UIState) -> Result<(), Infallible> {
+        let theme = theme::current();
+        let content_area = theme.content_area(self.display_bounds);
+        display.fill_solid(&content_area, theme.background)?;
+
+        let row_height = theme.spacing.row_height;
+        let title_top = content_area.top_left.y + PADDING;
+        Text::with_baseline(
+            i18n::tr(Msg::WifiTitle),
+            Point::new(theme.spacing.margin, title_top),
+            theme.accent_style(),
+            Baseline::Top,
+        )
+        .draw(display)?;
+
+        let list_top = title_top + row_height + PADDING;
+        self.details.set_bounds(Rectangle::new(
+            Point::new(content_area.top_left.x, list_top),
+            Size::new(
+                content_area.size.width,
+                (content_area.size.height as i32 - (list_top - content_area.top_left.y)).max(0)
+                    as u32,
+            ),
+        ));
+        self.details.set_rows(Self::rows(state));
+        // Read-only details, nothing is highlighted
+        self.details.draw(display, theme, false)
     }
 }
@@ -0,0 +1,132 @@
+use embassy_futures::yield_now;
+use embedded_graphics::pixelcolor::IntoStorage;
+use embedded_graphics::prelude::OriginDimensions;
+use esp_println::println;
+use heapless::{String, Vec};
+use log::info;
+
+use crate::ui::compositor::Compositor;
+
+// Raw bytes per output line, encodes to 76 base64 characters
+const LINE_BYTES: usize = 57;
+const LINE_CHARS: usize = LINE_BYTES / 3 * 4;
+
+// Longest run of identical pixels stored in a single RLE record
+const MAX_RUN: usize = 256;
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Stream the frame currently shown on the panel over the serial console.
+///
+/// The frame is run-length encoded as `[run - 1, hi, lo]` records of big-endian RGB565
+/// and wrapped in base64 lines between `SCREENSHOT BEGIN`/`END` markers, so it survives
+/// being interleaved with log output. `tools/screenshot.py` turns the capture into a PNG.
+pub async fn capture(canvas: &Compositor) {
+    let size = canvas.size();
+    info!("Capturing screenshot {}x{}", size.width, size.height);
+    println!(
+        "=== SCREENSHOT BEGIN {} {} rgb565-rle ===",
+        size.width, size.height
+    );
+
+    let mut writer = LineWriter::new();
+    let frame = canvas.frame();
+    let mut idx = 0;
+    while idx < frame.len() {
+        let color = frame[idx];
+        let run = frame[idx..]
+            .iter()
+            .take(MAX_RUN)
+            .take_while(|&&c| c == color)
+            .count();
+        let [hi, lo] = color.into_storage().to_be_bytes();
+        for byte in [(run - 1) as u8, hi, lo] {
+            if writer.push(byte) {
+                // Let other tasks run between lines, a full capture takes a while
+                yield_now().await;
+            }
+        }
+        idx += run;
+    }
+    writer.finish();
+
+    println!(
+        "=== SCREENSHOT END {} {:08x} ===",
+        writer.total,
+        writer.crc ^ 0xFFFF_FFFF
+    );
+}
+
+/// Buffers raw bytes and prints them as base64 lines, keeping a running CRC-32.
+struct LineWriter {
+    line: Vec<u8, LINE_BYTES>,
+    total: usize,
+    crc: u32,
+}
+
+impl LineWriter {
+    fn new() -> Self {
+        Self {
+            line: Vec::new(),
+            total: 0,
+            crc: 0xFFFF_FFFF,
+        }
+    }
+
+    /// Add a byte. Returns true when a line was printed.
+    fn push(&mut self, byte: u8) -> bool {
+        self.crc = crc32_update(self.crc, byte);
+        self.total += 1;
+        // Never fails: the line is flushed as soon as it is full
+        let _ = self.line.push(byte);
+        if self.line.is_full() {
+            self.flush();
+            return true;
+        }
+        false
+    }
+
+    fn finish(&mut self) {
+        if !self.line.is_empty() {
+            self.flush();
+        }
+    }
+
+    fn flush(&mut self) {
+        println!("{}", encode_base64(&self.line));
+        self.line.clear();
+    }
+}
+
+fn encode_base64(data: &[u8]) -> String<LINE_CHARS> {
+    let mut out = String::new();
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            let c = if i <= chunk.len() {
+                BASE64[(n >> (18 - i * 6)) as usize & 0x3F] as char
+            } else {
+                '='
+            };
+            let _ = out.push(c);
+        }
+    }
+    out
+}
+
+fn crc32_update(crc: u32, byte: u8) -> u32 {
+    let mut crc = crc ^ byte as u32;
+    for _ in 0..8 {
+        crc = if crc & 1 != 0 {
+            (crc >> 1) ^ 0xEDB8_8320
+        } else {
+            crc >> 1
+        };
+    }
+    crc
+}
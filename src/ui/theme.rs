@@ -0,0 +1,136 @@
+use embedded_graphics::pixelcolor::Rgb565;
+use embedded_graphics::prelude::{Point, RgbColor, Size};
+use embedded_graphics::primitives::Rectangle;
+
+use crate::i18n::Msg;
+use crate::services::SettingsService;
+use crate::ui::font::{self, BitmapFont, FontStyle};
+
+/// Distances shared by all screens.
+#[derive(Copy, Clone, Debug)]
+pub struct Spacing {
+    /// Height of the status header, screen content starts below it.
+    pub header_height: u32,
+    /// Horizontal padding of list rows and text.
+    pub margin: i32,
+    /// Height of one list row.
+    pub row_height: i32,
+}
+
+/// Colors, fonts and spacing used to draw the UI.
+#[derive(Copy, Clone)]
+pub struct UiTheme {
+    pub name: Msg,
+    pub background: Rgb565,
+    pub foreground: Rgb565,
+    /// Header, selection and progress bars.
+    pub accent: Rgb565,
+    /// Text drawn on top of the accent color, e.g. the selected menu entry.
+    pub on_accent: Rgb565,
+    /// Errors and low battery.
+    pub warning: Rgb565,
+    /// Secondary indicators such as the charging bolt.
+    pub highlight: Rgb565,
+    /// Proportional font for all screen text.
+    pub text_font: &'static BitmapFont,
+    pub spacing: Spacing,
+}
+
+impl UiTheme {
+    pub fn text_style(&self) -> FontStyle {
+        self.style(self.foreground)
+    }
+
+    pub fn accent_style(&self) -> FontStyle {
+        self.style(self.accent)
+    }
+
+    /// Text style of the theme font in the given color.
+    pub fn style(&self, color: Rgb565) -> FontStyle {
+        FontStyle::new(self.text_font, color)
+    }
+
+    /// Text color of a list row.
+    pub fn item_color(&self, selected: bool) -> Rgb565 {
+        if selected {
+            self.accent
+        } else {
+            self.foreground
+        }
+    }
+
+    /// The part of the display below the header.
+    pub fn content_area(&self, display_bounds: Rectangle) -> Rectangle {
+        let top = self.spacing.header_height;
+        Rectangle::new(
+            display_bounds.top_left + Point::new(0, top as i32),
+            Size::new(
+                display_bounds.size.width,
+                display_bounds.size.height.saturating_sub(top),
+            ),
+        )
+    }
+}
+
+const SPACING: Spacing = Spacing {
+    header_height: 30,
+    margin: 20,
+    row_height: 24,
+};
+
+pub static THEMES: [UiTheme; 4] = [
+    UiTheme {
+        name: Msg::ThemeMint,
+        background: Rgb565::BLACK,
+        foreground: Rgb565::WHITE,
+        accent: Rgb565::new(51, 255, 153),
+        on_accent: Rgb565::BLACK,
+        warning: Rgb565::RED,
+        highlight: Rgb565::YELLOW,
+        text_font: &font::REGULAR,
+        spacing: SPACING,
+    },
+    UiTheme {
+        name: Msg::ThemeContrast,
+        background: Rgb565::BLACK,
+        foreground: Rgb565::WHITE,
+        accent: Rgb565::YELLOW,
+        on_accent: Rgb565::BLACK,
+        warning: Rgb565::RED,
+        highlight: Rgb565::CYAN,
+        text_font: &font::BOLD,
+        spacing: SPACING,
+    },
+    UiTheme {
+        name: Msg::ThemeLight,
+        background: Rgb565::WHITE,
+        foreground: Rgb565::BLACK,
+        accent: Rgb565::new(0, 20, 24),
+        on_accent: Rgb565::WHITE,
+        warning: Rgb565::new(25, 0, 0),
+        highlight: Rgb565::new(31, 40, 0),
+        text_font: &font::REGULAR,
+        spacing: SPACING,
+    },
+    UiTheme {
+        name: Msg::ThemeAmber,
+        background: Rgb565::BLACK,
+        foreground: Rgb565::new(31, 48, 8),
+        accent: Rgb565::new(31, 32, 0),
+        on_accent: Rgb565::BLACK,
+        warning: Rgb565::RED,
+        highlight: Rgb565::WHITE,
+        text_font: &font::REGULAR,
+        spacing: SPACING,
+    },
+];
+
+/// Index of the theme selected in settings.
+pub fn current_index() -> usize {
+    SettingsService::get().theme as usize % THEMES.len()
+}
+
+/// The theme selected in settings.
+pub fn current() -> &'static UiTheme {
+    &THEMES[current_index()]
+}
@@ -0,0 +1,274 @@
+use core::fmt::Write;
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::{Angle, DrawTarget, Point, Primitive, Size},
+    primitives::{Arc, Line, Polyline, PrimitiveStyle, Rectangle},
+    text::{Baseline, Text},
+    Drawable,
+};
+use embedded_layout::{
+    align::{horizontal, vertical, Align},
+    View,
+};
+use esp_println::println;
+use jiff::tz::TimeZone;
+
+use super::Icon;
+use crate::format;
+use crate::services::{BatteryService, ClockService, NotificationService};
+use crate::ui::font::FontStyle;
+use crate::ui::theme::{self, UiTheme};
+use crate::ui::UIState;
+
+pub struct Header {
+    theme: &'static UiTheme,
+    color: Rgb565,
+    text_style: FontStyle,
+    bounds: Rectangle,
+}
+
+impl Header {
+    /// Create a header using the current theme. Recreate it when the theme changes.
+    pub fn new(display_area: Rectangle) -> Self {
+        let theme = theme::current();
+        let color = theme.accent;
+        // Create a style with background color to ensure overwrites don't require clearing
+        let text_style = theme.style(color).with_background(theme.background);
+
+        Self {
+            theme,
+            color,
+            text_style,
+            bounds: Rectangle::new(
+                Point::new(0, 0),
+                Size::new(display_area.size().width, theme.spacing.header_height),
+            ),
+        }
+    }
+}
+
+impl View for Header {
+    #[inline]
+    fn translate_impl(&mut self, by: Point) {
+        <Rectangle as embedded_graphics::prelude::Transform>::translate_mut(&mut self.bounds, by);
+    }
+
+    #[inline]
+    fn bounds(&self) -> Rectangle {
+        self.bounds
+    }
+}
+
+impl Header {
+    pub fn draw<D>(&self, display: &mut D, state: &UIState) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let line_style = PrimitiveStyle::with_stroke(self.color, 1);
+        display.fill_solid(&self.bounds, self.theme.background)?;
+
+        let bottom_left = Point::new(
+            self.bounds.top_left.x,
+            self.bounds.top_left.y + self.bounds.size.height as i32 - 1,
+        );
+        let bottom_right = Point::new(
+            self.bounds.top_left.x + self.bounds.size.width as i32,
+            self.bounds.top_left.y + self.bounds.size.height as i32 - 1,
+        );
+        let bottom_line = Line::new(bottom_left, bottom_right).into_styled(line_style);
+
+        bottom_line.draw(display)?;
+        self.draw_clock(display, state)?;
+        self.draw_wifi(display, state)?;
+        self.draw_notifications(display, state)?;
+        self.draw_battery(display, state)?;
+
+        Ok(())
+    }
+
+    pub fn draw_wifi<D>(&self, display: &mut D, state: &UIState) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let icon_area = Rectangle::new(self.bounds.top_left + Point::new(5, 2), Size::new(35, 25));
+        let center = icon_area.top_left + Point::new(20, 18);
+        let style = PrimitiveStyle::with_stroke(self.color, 1);
+
+        // Clear the icon area so stale pixels from previous frame are gone
+        icon_area
+            .into_styled(PrimitiveStyle::with_fill(self.theme.background))
+            .draw(display)?;
+
+        // Draw dot at the bottom
+        embedded_graphics::primitives::Circle::new(center - Point::new(1, 1), 3)
+            .into_styled(PrimitiveStyle::with_fill(self.color))
+            .draw(display)?;
+
+        // Draw 3 curves
+        for r in [6i32, 10i32, 14i32] {
+            Arc::new(
+                center - Point::new(r, r),
+                r as u32 * 2,
+                Angle::from_degrees(225.0),
+                Angle::from_degrees(90.0),
+            )
+            .into_styled(style)
+            .draw(display)?;
+        }
+
+        if !state.wifi_connected {
+            Line::new(
+                icon_area.top_left + Point::new(10, 2),
+                icon_area.top_left + Point::new(28, 18),
+            )
+            .into_styled(PrimitiveStyle::with_stroke(self.theme.warning, 1))
+            .draw(display)?;
+        }
+
+        Ok(())
+    }
+
+    /// Bell with the number of unread notifications, hidden when there are none.
+    pub fn draw_notifications<D>(&self, display: &mut D, _state: &UIState) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let area = Rectangle::new(self.bounds.top_left + Point::new(44, 2), Size::new(40, 25));
+        area.into_styled(PrimitiveStyle::with_fill(self.theme.background))
+            .draw(display)?;
+
+        let unread = NotificationService::unread();
+        if unread == 0 {
+            return Ok(());
+        }
+        let bell = Rectangle::new(
+            Point::new(area.top_left.x, area.center().y - 7),
+            Size::new_equal(14),
+        );
+        Icon::Bell.draw(display, bell, self.color)?;
+        let count = format!(4, "{}", unread);
+        Text::with_baseline(
+            &count,
+            Point::new(
+                bell.top_left.x + bell.size.width as i32 + 3,
+                bell.center().y,
+            ),
+            self.text_style,
+            Baseline::Middle,
+        )
+        .draw(display)?;
+        Ok(())
+    }
+
+    pub fn draw_battery<D>(&self, display: &mut D, _state: &UIState) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let status = BatteryService::status();
+        let right = self.bounds.top_left.x + self.bounds.size.width as i32;
+        let area = Rectangle::new(
+            Point::new(right - 80, self.bounds.top_left.y + 2),
+            Size::new(78, 25),
+        );
+        let body = Rectangle::new(
+            Point::new(right - 30, area.center().y - 6),
+            Size::new(24, 12),
+        );
+
+        // Clear the indicator so a shorter percentage doesn't leave stale digits
+        area.into_styled(PrimitiveStyle::with_fill(self.theme.background))
+            .draw(display)?;
+
+        let color = match status {
+            Some(status) if status.is_low() => self.theme.warning,
+            _ => self.color,
+        };
+
+        body.into_styled(PrimitiveStyle::with_stroke(color, 1))
+            .draw(display)?;
+        Rectangle::new(
+            Point::new(
+                body.top_left.x + body.size.width as i32,
+                body.top_left.y + 3,
+            ),
+            Size::new(2, 6),
+        )
+        .into_styled(PrimitiveStyle::with_fill(color))
+        .draw(display)?;
+
+        let Some(status) = status else {
+            Text::new("--%", Point::zero(), self.text_style)
+                .align_to(&body, horizontal::RightToLeft, vertical::Center)
+                .draw(display)?;
+            return Ok(());
+        };
+
+        let fill_width = (body.size.width - 4) * status.percent as u32 / 100;
+        Rectangle::new(
+            body.top_left + Point::new(2, 2),
+            Size::new(fill_width.max(1), body.size.height - 4),
+        )
+        .into_styled(PrimitiveStyle::with_fill(color))
+        .draw(display)?;
+
+        if status.charging {
+            self.draw_charging_bolt(display, &body)?;
+        }
+
+        let text = format!(4, "{}%", status.percent);
+        let text_style = self
+            .theme
+            .style(color)
+            .with_background(self.theme.background);
+        Text::new(&text, Point::zero(), text_style)
+            .align_to(&body, horizontal::RightToLeft, vertical::Center)
+            .draw(display)?;
+
+        Ok(())
+    }
+
+    fn draw_charging_bolt<D>(&self, display: &mut D, body: &Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let origin = body.top_left;
+        let points = [
+            origin + Point::new(13, 1),
+            origin + Point::new(9, 6),
+            origin + Point::new(14, 6),
+            origin + Point::new(10, 11),
+        ];
+        Polyline::new(&points)
+            .into_styled(PrimitiveStyle::with_stroke(self.theme.highlight, 2))
+            .draw(display)?;
+
+        Ok(())
+    }
+
+    pub fn draw_clock<D>(&self, display: &mut D, _state: &UIState) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let time_text = match ClockService::get_current_time() {
+            Some(timestamp) => {
+                let time = timestamp.to_zoned(TimeZone::UTC);
+                format!(
+                    8,
+                    "{:02}:{:02}:{:02}",
+                    time.hour(),
+                    time.minute(),
+                    time.second()
+                )
+            }
+            None => format!(8, "--:--:--"),
+        };
+        let time_widget = Text::new(&time_text, Point::zero(), self.text_style).align_to(
+            &self.bounds,
+            horizontal::Center,
+            vertical::Center,
+        );
+
+        time_widget.draw(display)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,316 @@
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::{Angle, DrawTarget, Point, Primitive, Size},
+    primitives::{
+        Arc, Circle, Line, Polyline, PrimitiveStyle, Rectangle, RoundedRectangle, Triangle,
+    },
+    text::{Baseline, Text},
+    Drawable,
+};
+
+use super::{Response, Widget};
+use crate::i18n::{self, Msg};
+use crate::state::ButtonEvent;
+use crate::ui::theme::UiTheme;
+
+/// Pictograms drawn from primitives, so they scale with the area they are given.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Icon {
+    Back,
+    Play,
+    Pause,
+    Stop,
+    Next,
+    Check,
+    Cross,
+    Plus,
+    Minus,
+    Info,
+    Wifi,
+    Gear,
+    Bell,
+    Chart,
+    Log,
+}
+
+impl Icon {
+    /// Draw the icon into the largest square centered in `area`.
+    pub fn draw<D>(&self, display: &mut D, area: Rectangle, color: Rgb565) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let side = area.size.width.min(area.size.height) as i32;
+        let center = area.center();
+        let (l, t) = (center.x - side / 2, center.y - side / 2);
+        let (r, b) = (l + side - 1, t + side - 1);
+        let mid = center.y;
+        let fill = PrimitiveStyle::with_fill(color);
+        let stroke = PrimitiveStyle::with_stroke(color, 2);
+
+        match self {
+            Icon::Back => {
+                let points = [
+                    Point::new(r - side / 4, t),
+                    Point::new(l + side / 4, mid),
+                    Point::new(r - side / 4, b),
+                ];
+                Polyline::new(&points).into_styled(stroke).draw(display)?;
+            }
+            Icon::Play => {
+                Triangle::new(
+                    Point::new(l + side / 6, t),
+                    Point::new(l + side / 6, b),
+                    Point::new(r, mid),
+                )
+                .into_styled(fill)
+                .draw(display)?;
+            }
+            Icon::Pause => {
+                let bar = Size::new(side as u32 / 3, side as u32);
+                Rectangle::new(Point::new(l, t), bar)
+                    .into_styled(fill)
+                    .draw(display)?;
+                Rectangle::new(Point::new(r + 1 - bar.width as i32, t), bar)
+                    .into_styled(fill)
+                    .draw(display)?;
+            }
+            Icon::Stop => {
+                let inset = side / 8;
+                Rectangle::new(
+                    Point::new(l + inset, t + inset),
+                    Size::new_equal((side - inset * 2) as u32),
+                )
+                .into_styled(fill)
+                .draw(display)?;
+            }
+            Icon::Next => {
+                let bar = side / 5;
+                Triangle::new(Point::new(l, t), Point::new(l, b), Point::new(r - bar, mid))
+                    .into_styled(fill)
+                    .draw(display)?;
+                Rectangle::new(
+                    Point::new(r + 1 - bar, t),
+                    Size::new(bar as u32, side as u32),
+                )
+                .into_styled(fill)
+                .draw(display)?;
+            }
+            Icon::Check => {
+                let points = [
+                    Point::new(l, mid),
+                    Point::new(l + side / 3, b),
+                    Point::new(r, t),
+                ];
+                Polyline::new(&points).into_styled(stroke).draw(display)?;
+            }
+            Icon::Cross => {
+                Line::new(Point::new(l, t), Point::new(r, b))
+                    .into_styled(stroke)
+                    .draw(display)?;
+                Line::new(Point::new(l, b), Point::new(r, t))
+                    .into_styled(stroke)
+                    .draw(display)?;
+            }
+            Icon::Plus => {
+                Line::new(Point::new(l, mid), Point::new(r, mid))
+                    .into_styled(stroke)
+                    .draw(display)?;
+                Line::new(Point::new(center.x, t), Point::new(center.x, b))
+                    .into_styled(stroke)
+                    .draw(display)?;
+            }
+            Icon::Minus => {
+                Line::new(Point::new(l, mid), Point::new(r, mid))
+                    .into_styled(stroke)
+                    .draw(display)?;
+            }
+            Icon::Info => {
+                Circle::with_center(center, side as u32)
+                    .into_styled(PrimitiveStyle::with_stroke(color, 1))
+                    .draw(display)?;
+                Line::new(
+                    Point::new(center.x, mid - side / 8),
+                    Point::new(center.x, b - side / 5),
+                )
+                .into_styled(stroke)
+                .draw(display)?;
+                Rectangle::with_center(Point::new(center.x, t + side / 4), Size::new_equal(2))
+                    .into_styled(fill)
+                    .draw(display)?;
+            }
+            Icon::Wifi => {
+                let origin = Point::new(center.x, b - 1);
+                for radius in [side / 3, side * 2 / 3] {
+                    Arc::with_center(
+                        origin,
+                        radius as u32 * 2,
+                        Angle::from_degrees(225.0),
+                        Angle::from_degrees(90.0),
+                    )
+                    .into_styled(stroke)
+                    .draw(display)?;
+                }
+                Circle::with_center(origin, 3)
+                    .into_styled(fill)
+                    .draw(display)?;
+            }
+            Icon::Gear => {
+                // Eight teeth around a ring, directions scaled by ten
+                const SPOKES: [(i32, i32); 8] = [
+                    (10, 0),
+                    (7, 7),
+                    (0, 10),
+                    (-7, 7),
+                    (-10, 0),
+                    (-7, -7),
+                    (0, -10),
+                    (7, -7),
+                ];
+                let (inner, outer) = (side / 4, side / 2);
+                for (dx, dy) in SPOKES {
+                    Line::new(
+                        center + Point::new(dx * inner / 10, dy * inner / 10),
+                        center + Point::new(dx * outer / 10, dy * outer / 10),
+                    )
+                    .into_styled(stroke)
+                    .draw(display)?;
+                }
+                Circle::with_center(center, (side * 2 / 3) as u32)
+                    .into_styled(stroke)
+                    .draw(display)?;
+            }
+            Icon::Bell => {
+                let rim = b - side / 5;
+                RoundedRectangle::with_equal_corners(
+                    Rectangle::new(
+                        Point::new(l + side / 5, t),
+                        Size::new((side - side * 2 / 5) as u32, (rim - t) as u32),
+                    ),
+                    Size::new_equal((side / 3) as u32),
+                )
+                .into_styled(fill)
+                .draw(display)?;
+                Line::new(Point::new(l, rim), Point::new(r, rim))
+                    .into_styled(stroke)
+                    .draw(display)?;
+                Circle::with_center(Point::new(center.x, b - 1), (side / 4) as u32)
+                    .into_styled(fill)
+                    .draw(display)?;
+            }
+            Icon::Chart => {
+                // Three rising bars on a base line
+                let bar = side / 5;
+                for (idx, height) in [side / 3, side * 2 / 3, side - 2].into_iter().enumerate() {
+                    let x = l + bar / 2 + idx as i32 * bar * 3 / 2;
+                    Rectangle::new(
+                        Point::new(x, b - 1 - height),
+                        Size::new(bar as u32, height as u32),
+                    )
+                    .into_styled(fill)
+                    .draw(display)?;
+                }
+                Line::new(Point::new(l, b), Point::new(r, b))
+                    .into_styled(PrimitiveStyle::with_stroke(color, 1))
+                    .draw(display)?;
+            }
+            Icon::Log => {
+                // Lines of text of different length
+                for (idx, end) in [r, r - side / 3, r, r - side / 2].into_iter().enumerate() {
+                    let y = t + side / 8 + idx as i32 * side / 4;
+                    Line::new(Point::new(l, y), Point::new(end, y))
+                        .into_styled(stroke)
+                        .draw(display)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Button showing an icon and an optional label, pressed with A.
+pub struct IconButton {
+    bounds: Rectangle,
+    icon: Icon,
+    label: Option<Msg>,
+}
+
+impl IconButton {
+    pub fn new(bounds: Rectangle, icon: Icon) -> Self {
+        Self {
+            bounds,
+            icon,
+            label: None,
+        }
+    }
+
+    pub fn with_label(mut self, label: Msg) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    pub fn set_icon(&mut self, icon: Icon) {
+        self.icon = icon;
+    }
+}
+
+impl Widget for IconButton {
+    fn bounds(&self) -> Rectangle {
+        self.bounds
+    }
+
+    fn set_bounds(&mut self, bounds: Rectangle) {
+        self.bounds = bounds;
+    }
+
+    fn handle(&mut self, event: ButtonEvent) -> Response {
+        match event {
+            ButtonEvent::A => Response::Activated,
+            _ => Response::Ignored,
+        }
+    }
+
+    fn draw<D>(&self, display: &mut D, theme: &UiTheme, focused: bool) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let frame = RoundedRectangle::with_equal_corners(self.bounds, Size::new_equal(6));
+        let color = if focused {
+            frame
+                .into_styled(PrimitiveStyle::with_fill(theme.accent))
+                .draw(display)?;
+            theme.on_accent
+        } else {
+            frame
+                .into_styled(PrimitiveStyle::with_stroke(theme.foreground, 1))
+                .draw(display)?;
+            theme.foreground
+        };
+
+        // The icon takes a square at the left when labelled, the whole button otherwise
+        let inset = 6;
+        let side = self.bounds.size.height.saturating_sub(inset * 2);
+        let icon_area = match self.label {
+            Some(_) => Rectangle::new(
+                self.bounds.top_left + Point::new(inset as i32, inset as i32),
+                Size::new_equal(side),
+            ),
+            None => Rectangle::with_center(
+                self.bounds.center(),
+                Size::new_equal(side.min(self.bounds.size.width.saturating_sub(inset * 2))),
+            ),
+        };
+        self.icon.draw(display, icon_area, color)?;
+
+        if let Some(label) = self.label {
+            let x = icon_area.top_left.x + side as i32 + inset as i32;
+            Text::with_baseline(
+                i18n::tr(label),
+                Point::new(x, self.bounds.center().y),
+                theme.style(color),
+                Baseline::Middle,
+            )
+            .draw(display)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,177 @@
+use core::cell::Cell;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::{DrawTarget, Point, Primitive, Size},
+    primitives::{PrimitiveStyle, Rectangle},
+    text::{Baseline, Text},
+    Drawable,
+};
+
+use super::{Response, Widget};
+use crate::state::ButtonEvent;
+use crate::ui::theme::UiTheme;
+
+const SCROLLBAR_WIDTH: u32 = 3;
+
+/// Vertical list of text rows that scrolls to keep the selected row visible.
+///
+/// Screens with richer rows can keep the navigation from the list and draw the rows
+/// themselves with `rows_in_view`.
+pub struct ListView {
+    bounds: Rectangle,
+    rows: Vec<String>,
+    selected: usize,
+    // First visible row, follows the selection when drawing
+    scroll: Cell<usize>,
+}
+
+impl ListView {
+    pub fn new(bounds: Rectangle) -> Self {
+        Self {
+            bounds,
+            rows: Vec::new(),
+            selected: 0,
+            scroll: Cell::new(0),
+        }
+    }
+
+    pub fn with_rows<I, S>(mut self, rows: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.set_rows(rows);
+        self
+    }
+
+    /// Replace the rows, keeping the selection if it is still in range.
+    pub fn set_rows<I, S>(&mut self, rows: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.rows = rows.into_iter().map(Into::into).collect();
+        self.select(self.selected);
+    }
+
+    /// Create an empty list with `len` rows, for screens that draw the rows themselves.
+    pub fn with_len(bounds: Rectangle, len: usize) -> Self {
+        Self::new(bounds).with_rows((0..len).map(|_| String::new()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn select(&mut self, idx: usize) {
+        self.selected = idx.min(self.rows.len().saturating_sub(1));
+    }
+
+    /// Number of rows that fit into the list.
+    pub fn visible_rows(&self, theme: &UiTheme) -> usize {
+        (self.bounds.size.height as i32 / theme.spacing.row_height).max(1) as usize
+    }
+
+    /// Index and area of every row currently on screen.
+    pub fn rows_in_view(&self, theme: &UiTheme) -> impl Iterator<Item = (usize, Rectangle)> {
+        let visible = self.visible_rows(theme);
+        let mut scroll = self.scroll.get();
+        if self.selected < scroll {
+            scroll = self.selected;
+        } else if self.selected >= scroll + visible {
+            scroll = self.selected + 1 - visible;
+        }
+        self.scroll.set(scroll);
+
+        let row_height = theme.spacing.row_height;
+        let top_left = self.bounds.top_left;
+        let size = Size::new(self.bounds.size.width, row_height as u32);
+        (scroll..self.rows.len().min(scroll + visible)).map(move |idx| {
+            let row = (idx - scroll) as i32;
+            (
+                idx,
+                Rectangle::new(top_left + Point::new(0, row * row_height), size),
+            )
+        })
+    }
+
+    /// Draw the scroll position at the right edge if not all rows fit.
+    pub fn draw_scrollbar<D>(&self, display: &mut D, theme: &UiTheme) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let visible = self.visible_rows(theme);
+        let len = self.rows.len();
+        if len <= visible {
+            return Ok(());
+        }
+
+        let height = self.bounds.size.height;
+        let thumb = (height * visible as u32 / len as u32).max(4);
+        let top = (height - thumb) * self.scroll.get() as u32 / (len - visible) as u32;
+        let x = self.bounds.top_left.x + (self.bounds.size.width - SCROLLBAR_WIDTH) as i32;
+        Rectangle::new(
+            Point::new(x, self.bounds.top_left.y + top as i32),
+            Size::new(SCROLLBAR_WIDTH, thumb),
+        )
+        .into_styled(PrimitiveStyle::with_fill(theme.accent))
+        .draw(display)?;
+        Ok(())
+    }
+}
+
+impl Widget for ListView {
+    fn bounds(&self) -> Rectangle {
+        self.bounds
+    }
+
+    fn set_bounds(&mut self, bounds: Rectangle) {
+        self.bounds = bounds;
+    }
+
+    fn handle(&mut self, event: ButtonEvent) -> Response {
+        if self.rows.is_empty() {
+            return Response::Ignored;
+        }
+        match event {
+            ButtonEvent::Up => {
+                self.selected = self.selected.checked_sub(1).unwrap_or(self.rows.len() - 1);
+                Response::Consumed
+            }
+            ButtonEvent::Down => {
+                self.selected = (self.selected + 1) % self.rows.len();
+                Response::Consumed
+            }
+            ButtonEvent::A | ButtonEvent::Right => Response::Activated,
+            _ => Response::Ignored,
+        }
+    }
+
+    fn draw<D>(&self, display: &mut D, theme: &UiTheme, focused: bool) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        for (idx, row) in self.rows_in_view(theme) {
+            let color = theme.item_color(focused && idx == self.selected);
+            Text::with_baseline(
+                &self.rows[idx],
+                Point::new(row.top_left.x + theme.spacing.margin, row.center().y),
+                theme.style(color),
+                Baseline::Middle,
+            )
+            .draw(display)?;
+        }
+        self.draw_scrollbar(display, theme)
+    }
+}
@@ -0,0 +1,105 @@
+//! Building blocks for screens.
+//!
+//! Widgets own their layout and state, react to the buttons while they have focus and
+//! draw themselves with the current theme. Screens decide which widget is focused,
+//! usually with a `FocusChain`, and forward button events to it.
+
+pub mod header;
+pub mod icon_button;
+pub mod list;
+pub mod modal;
+pub mod progress;
+pub mod slider;
+pub mod text_input;
+pub mod toast;
+pub mod toggle;
+
+pub use header::Header;
+pub use icon_button::{Icon, IconButton};
+pub use list::ListView;
+pub use modal::Modal;
+pub use progress::ProgressBar;
+pub use slider::Slider;
+pub use text_input::TextInput;
+pub use toast::Toast;
+pub use toggle::Toggle;
+
+use embedded_graphics::{pixelcolor::Rgb565, prelude::DrawTarget, primitives::Rectangle};
+
+use crate::state::ButtonEvent;
+use crate::ui::theme::UiTheme;
+
+/// What a widget did with a button press.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Response {
+    /// The button means nothing to the widget, the screen may use it, e.g. to move focus.
+    Ignored,
+    /// The widget used the button without changing its value, e.g. to move a cursor.
+    Consumed,
+    /// The value of the widget changed.
+    Changed,
+    /// A was pressed on the widget: a button was clicked or a list row picked.
+    Activated,
+    /// B was pressed on a widget that can be backed out of, such as a dialog.
+    Cancelled,
+}
+
+pub trait Widget {
+    fn bounds(&self) -> Rectangle;
+
+    /// Move the widget, e.g. after the display was rotated.
+    fn set_bounds(&mut self, bounds: Rectangle);
+
+    /// Handle a button press while the widget has focus.
+    fn handle(&mut self, _event: ButtonEvent) -> Response {
+        Response::Ignored
+    }
+
+    /// Draw the widget. Focused widgets are drawn in the accent color.
+    fn draw<D>(&self, display: &mut D, theme: &UiTheme, focused: bool) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>;
+}
+
+/// Moves focus between the widgets of a screen with Up and Down.
+#[derive(Copy, Clone, Debug)]
+pub struct FocusChain {
+    len: usize,
+    focused: usize,
+}
+
+impl FocusChain {
+    pub fn new(len: usize) -> Self {
+        Self { len, focused: 0 }
+    }
+
+    pub fn focused(&self) -> usize {
+        self.focused
+    }
+
+    pub fn is_focused(&self, idx: usize) -> bool {
+        self.focused == idx
+    }
+
+    pub fn focus(&mut self, idx: usize) {
+        self.focused = idx.min(self.len.saturating_sub(1));
+    }
+
+    /// Move focus on Up/Down, wrapping around. Returns whether the event was used.
+    pub fn handle(&mut self, event: ButtonEvent) -> bool {
+        if self.len == 0 {
+            return false;
+        }
+        match event {
+            ButtonEvent::Up => {
+                self.focused = self.focused.checked_sub(1).unwrap_or(self.len - 1);
+                true
+            }
+            ButtonEvent::Down => {
+                self.focused = (self.focused + 1) % self.len;
+                true
+            }
+            _ => false,
+        }
+    }
+}
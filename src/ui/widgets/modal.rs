@@ -0,0 +1,205 @@
+use alloc::string::String;
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::{DrawTarget, OffsetOutline, Point, Primitive, Size},
+    primitives::{Line, PrimitiveStyle, Rectangle, RoundedRectangle},
+    text::{Alignment, Baseline, Text, TextStyleBuilder},
+    Drawable,
+};
+use embedded_text::alignment::HorizontalAlignment;
+
+use super::{Response, Widget};
+use crate::i18n::{self, Msg};
+use crate::state::ButtonEvent;
+use crate::ui::font;
+use crate::ui::theme::UiTheme;
+
+const MAX_HEIGHT: u32 = 170;
+const BUTTON_HEIGHT: u32 = 26;
+
+/// Dialog box with a title, a wrapped message and a row of buttons.
+///
+/// While shown it takes every button: Left/Right pick a button, A presses it and
+/// B dismisses the dialog.
+pub struct Modal {
+    bounds: Rectangle,
+    title: String,
+    message: String,
+    buttons: &'static [Msg],
+    selected: usize,
+}
+
+impl Modal {
+    /// `bounds` is the area the dialog is centered in, usually the screen content area.
+    pub fn new(bounds: Rectangle, title: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            bounds,
+            title: title.into(),
+            message: message.into(),
+            buttons: &[Msg::ButtonOk],
+            selected: 0,
+        }
+    }
+
+    pub fn with_buttons(mut self, buttons: &'static [Msg]) -> Self {
+        self.buttons = buttons;
+        self.selected = 0;
+        self
+    }
+
+    /// Index of the highlighted button.
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// The dialog box itself.
+    fn frame(&self, theme: &UiTheme) -> Rectangle {
+        let margin = theme.spacing.margin as u32;
+        let size = Size::new(
+            self.bounds.size.width.saturating_sub(margin * 2),
+            self.bounds
+                .size
+                .height
+                .saturating_sub(margin * 2)
+                .min(MAX_HEIGHT),
+        );
+        Rectangle::with_center(self.bounds.center(), size)
+    }
+
+    /// Top of the button row, the bottom of the frame when there are no buttons.
+    fn buttons_top(&self, theme: &UiTheme) -> i32 {
+        let frame = self.frame(theme);
+        let padding = theme.spacing.margin / 2;
+        let bottom = frame.top_left.y + frame.size.height as i32 - padding;
+        if self.buttons.is_empty() {
+            bottom
+        } else {
+            bottom - BUTTON_HEIGHT as i32
+        }
+    }
+
+    /// Area between the title and the buttons, where the message is drawn.
+    /// Dialogs with custom content leave the message empty and draw here.
+    pub fn content_area(&self, theme: &UiTheme) -> Rectangle {
+        let frame = self.frame(theme);
+        let padding = theme.spacing.margin / 2;
+        let title_bottom = frame.top_left.y + theme.spacing.row_height + padding / 2;
+        Rectangle::new(
+            Point::new(frame.top_left.x + padding, title_bottom + padding / 2),
+            Size::new(
+                frame.size.width.saturating_sub(padding as u32 * 2),
+                (self.buttons_top(theme) - title_bottom - padding).max(0) as u32,
+            ),
+        )
+    }
+}
+
+impl Widget for Modal {
+    fn bounds(&self) -> Rectangle {
+        self.bounds
+    }
+
+    fn set_bounds(&mut self, bounds: Rectangle) {
+        self.bounds = bounds;
+    }
+
+    fn handle(&mut self, event: ButtonEvent) -> Response {
+        if self.buttons.is_empty() {
+            return match event {
+                ButtonEvent::A => Response::Activated,
+                ButtonEvent::B => Response::Cancelled,
+                _ => Response::Ignored,
+            };
+        }
+        let count = self.buttons.len();
+        match event {
+            ButtonEvent::Left => {
+                self.selected = self.selected.checked_sub(1).unwrap_or(count - 1);
+                Response::Consumed
+            }
+            ButtonEvent::Right => {
+                self.selected = (self.selected + 1) % count;
+                Response::Consumed
+            }
+            ButtonEvent::A => Response::Activated,
+            ButtonEvent::B => Response::Cancelled,
+            _ => Response::Consumed,
+        }
+    }
+
+    fn draw<D>(&self, display: &mut D, theme: &UiTheme, _focused: bool) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let frame = self.frame(theme);
+        let padding = theme.spacing.margin / 2;
+        let corners = Size::new_equal(8);
+        RoundedRectangle::with_equal_corners(frame, corners)
+            .into_styled(PrimitiveStyle::with_fill(theme.background))
+            .draw(display)?;
+        RoundedRectangle::with_equal_corners(frame, corners)
+            .into_styled(PrimitiveStyle::with_stroke(theme.accent, 2))
+            .draw(display)?;
+
+        let left = frame.top_left.x + padding;
+        let right = frame.top_left.x + frame.size.width as i32 - padding;
+        let title_bottom = frame.top_left.y + theme.spacing.row_height + padding / 2;
+        Text::with_baseline(
+            &self.title,
+            Point::new(left, frame.top_left.y + padding / 2),
+            theme.accent_style(),
+            Baseline::Top,
+        )
+        .draw(display)?;
+        Line::new(
+            Point::new(left, title_bottom),
+            Point::new(right, title_bottom),
+        )
+        .into_styled(PrimitiveStyle::with_stroke(theme.accent, 1))
+        .draw(display)?;
+
+        if !self.message.is_empty() {
+            font::draw_wrapped(
+                display,
+                &self.message,
+                self.content_area(theme),
+                theme.text_style(),
+                HorizontalAlignment::Center,
+            )?;
+        }
+
+        // Buttons share the bottom row equally
+        let buttons_top = self.buttons_top(theme);
+        let count = self.buttons.len().max(1) as i32;
+        let slot_width = (right - left) / count;
+        let label_style = TextStyleBuilder::new()
+            .alignment(Alignment::Center)
+            .baseline(Baseline::Middle)
+            .build();
+        for (idx, button) in self.buttons.iter().enumerate() {
+            let slot = Rectangle::new(
+                Point::new(left + slot_width * idx as i32, buttons_top),
+                Size::new(slot_width as u32, BUTTON_HEIGHT),
+            );
+            let selected = idx == self.selected;
+            if selected {
+                RoundedRectangle::with_equal_corners(slot.offset(-2), Size::new_equal(6))
+                    .into_styled(PrimitiveStyle::with_fill(theme.accent))
+                    .draw(display)?;
+            }
+            let color = if selected {
+                theme.on_accent
+            } else {
+                theme.foreground
+            };
+            Text::with_text_style(
+                i18n::tr(*button),
+                slot.center(),
+                theme.style(color),
+                label_style,
+            )
+            .draw(display)?;
+        }
+        Ok(())
+    }
+}
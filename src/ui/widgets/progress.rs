@@ -0,0 +1,68 @@
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::{DrawTarget, Primitive, Size},
+    primitives::{PrimitiveStyle, Rectangle},
+    Drawable,
+};
+
+use super::Widget;
+use crate::ui::theme::UiTheme;
+
+/// Horizontal bar filled up to a percentage.
+pub struct ProgressBar {
+    bounds: Rectangle,
+    percent: u8,
+}
+
+impl ProgressBar {
+    pub fn new(bounds: Rectangle) -> Self {
+        Self { bounds, percent: 0 }
+    }
+
+    pub fn percent(&self) -> u8 {
+        self.percent
+    }
+
+    pub fn set_percent(&mut self, percent: u8) {
+        self.percent = percent.min(100);
+    }
+
+    /// Set the fill from a position within a total, e.g. elapsed and song length.
+    pub fn set_fraction(&mut self, value: u32, total: u32) {
+        let percent = if total == 0 {
+            0
+        } else {
+            (value.min(total) as u64 * 100 / total as u64) as u8
+        };
+        self.set_percent(percent);
+    }
+}
+
+impl Widget for ProgressBar {
+    fn bounds(&self) -> Rectangle {
+        self.bounds
+    }
+
+    fn set_bounds(&mut self, bounds: Rectangle) {
+        self.bounds = bounds;
+    }
+
+    fn draw<D>(&self, display: &mut D, theme: &UiTheme, _focused: bool) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        self.bounds
+            .into_styled(PrimitiveStyle::with_stroke(theme.accent, 1))
+            .draw(display)?;
+        let filled = self.bounds.size.width * self.percent as u32 / 100;
+        if filled > 0 {
+            Rectangle::new(
+                self.bounds.top_left,
+                Size::new(filled, self.bounds.size.height),
+            )
+            .into_styled(PrimitiveStyle::with_fill(theme.accent))
+            .draw(display)?;
+        }
+        Ok(())
+    }
+}
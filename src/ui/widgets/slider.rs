@@ -0,0 +1,111 @@
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::{DrawTarget, Point, Primitive, Size},
+    primitives::{PrimitiveStyle, Rectangle},
+    text::{Baseline, Text},
+    Drawable,
+};
+
+use super::{Response, Widget};
+use crate::i18n::{self, Msg};
+use crate::state::ButtonEvent;
+use crate::ui::theme::UiTheme;
+
+const TRACK_SIZE: Size = Size::new(100, 10);
+
+/// Labelled value in a range, changed in steps with Left and Right.
+pub struct Slider {
+    bounds: Rectangle,
+    label: Msg,
+    value: i32,
+    min: i32,
+    max: i32,
+    step: i32,
+}
+
+impl Slider {
+    pub fn new(bounds: Rectangle, label: Msg, min: i32, max: i32, step: i32) -> Self {
+        Self {
+            bounds,
+            label,
+            value: min,
+            min,
+            max,
+            step,
+        }
+    }
+
+    pub fn with_value(mut self, value: i32) -> Self {
+        self.set_value(value);
+        self
+    }
+
+    pub fn value(&self) -> i32 {
+        self.value
+    }
+
+    pub fn set_value(&mut self, value: i32) {
+        self.value = value.clamp(self.min, self.max);
+    }
+
+    /// Fill of the track in pixels.
+    fn filled(&self) -> u32 {
+        let range = (self.max - self.min).max(1);
+        (TRACK_SIZE.width as i32 * (self.value - self.min) / range) as u32
+    }
+}
+
+impl Widget for Slider {
+    fn bounds(&self) -> Rectangle {
+        self.bounds
+    }
+
+    fn set_bounds(&mut self, bounds: Rectangle) {
+        self.bounds = bounds;
+    }
+
+    fn handle(&mut self, event: ButtonEvent) -> Response {
+        let old = self.value;
+        match event {
+            ButtonEvent::Left => self.set_value(self.value - self.step),
+            ButtonEvent::Right => self.set_value(self.value + self.step),
+            _ => return Response::Ignored,
+        }
+        if self.value == old {
+            Response::Consumed
+        } else {
+            Response::Changed
+        }
+    }
+
+    fn draw<D>(&self, display: &mut D, theme: &UiTheme, focused: bool) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let color = theme.item_color(focused);
+        let center_y = self.bounds.center().y;
+        Text::with_baseline(
+            i18n::tr(self.label),
+            Point::new(self.bounds.top_left.x + theme.spacing.margin, center_y),
+            theme.style(color),
+            Baseline::Middle,
+        )
+        .draw(display)?;
+
+        let right = self.bounds.top_left.x + self.bounds.size.width as i32 - theme.spacing.margin;
+        let track = Rectangle::new(
+            Point::new(
+                right - TRACK_SIZE.width as i32,
+                center_y - TRACK_SIZE.height as i32 / 2,
+            ),
+            TRACK_SIZE,
+        );
+        track
+            .into_styled(PrimitiveStyle::with_stroke(color, 1))
+            .draw(display)?;
+        Rectangle::new(track.top_left, Size::new(self.filled(), TRACK_SIZE.height))
+            .into_styled(PrimitiveStyle::with_fill(color))
+            .draw(display)?;
+        Ok(())
+    }
+}
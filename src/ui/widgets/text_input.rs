@@ -0,0 +1,199 @@
+use alloc::string::String;
+use embedded_graphics::{
+    draw_target::DrawTargetExt,
+    pixelcolor::Rgb565,
+    prelude::{DrawTarget, OffsetOutline, Point, Primitive, Size},
+    primitives::{Line, PrimitiveStyle, Rectangle},
+    text::{Baseline, Text},
+    Drawable,
+};
+
+use super::{Response, Widget};
+use crate::i18n::{self, Msg};
+use crate::state::ButtonEvent;
+use crate::ui::theme::UiTheme;
+
+/// Characters the D-pad cycles through, ASCII only so byte and character positions match.
+const CHARSET: &[u8] =
+    b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789 .-_@!?#$%&*+=/:";
+const PADDING: i32 = 4;
+
+/// Single-line text entry for a D-pad.
+///
+/// Up/Down change the character under the cursor, Left/Right move the cursor,
+/// C deletes, A submits and B cancels.
+pub struct TextInput {
+    bounds: Rectangle,
+    label: Msg,
+    text: String,
+    cursor: usize,
+    max_len: usize,
+    /// Show `*` instead of all characters but the one being edited.
+    masked: bool,
+}
+
+impl TextInput {
+    pub fn new(bounds: Rectangle, label: Msg, max_len: usize) -> Self {
+        Self {
+            bounds,
+            label,
+            text: String::new(),
+            cursor: 0,
+            max_len,
+            masked: false,
+        }
+    }
+
+    pub fn masked(mut self) -> Self {
+        self.masked = true;
+        self
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Replace the text, dropping characters that can't be typed.
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text
+            .bytes()
+            .filter(|c| CHARSET.contains(c))
+            .take(self.max_len)
+            .map(char::from)
+            .collect();
+        self.cursor = self.text.len().min(self.max_len.saturating_sub(1));
+    }
+
+    /// Step the character under the cursor through the charset, appending one at the end.
+    fn cycle(&mut self, forward: bool) -> Response {
+        if self.cursor == self.text.len() {
+            if self.text.len() >= self.max_len {
+                return Response::Consumed;
+            }
+            self.text.push(CHARSET[0] as char);
+            return Response::Changed;
+        }
+
+        let current = self.text.as_bytes()[self.cursor];
+        let idx = CHARSET.iter().position(|&c| c == current).unwrap_or(0);
+        let next = if forward {
+            (idx + 1) % CHARSET.len()
+        } else {
+            idx.checked_sub(1).unwrap_or(CHARSET.len() - 1)
+        };
+        let next = CHARSET[next] as char;
+        self.text
+            .replace_range(self.cursor..self.cursor + 1, next.encode_utf8(&mut [0; 4]));
+        Response::Changed
+    }
+
+    fn delete(&mut self) -> Response {
+        if self.cursor == self.text.len() {
+            if self.text.pop().is_none() {
+                return Response::Consumed;
+            }
+            self.cursor = self.text.len();
+        } else {
+            self.text.remove(self.cursor);
+        }
+        Response::Changed
+    }
+
+    /// Text as drawn: masked inputs only reveal the character being edited.
+    fn shown_text(&self) -> String {
+        if !self.masked {
+            return self.text.clone();
+        }
+        self.text
+            .chars()
+            .enumerate()
+            .map(|(idx, c)| if idx == self.cursor { c } else { '*' })
+            .collect()
+    }
+}
+
+impl Widget for TextInput {
+    fn bounds(&self) -> Rectangle {
+        self.bounds
+    }
+
+    fn set_bounds(&mut self, bounds: Rectangle) {
+        self.bounds = bounds;
+    }
+
+    fn handle(&mut self, event: ButtonEvent) -> Response {
+        match event {
+            ButtonEvent::Up => self.cycle(true),
+            ButtonEvent::Down => self.cycle(false),
+            ButtonEvent::Left => {
+                self.cursor = self.cursor.saturating_sub(1);
+                Response::Consumed
+            }
+            ButtonEvent::Right => {
+                let last = self.text.len().min(self.max_len.saturating_sub(1));
+                self.cursor = (self.cursor + 1).min(last);
+                Response::Consumed
+            }
+            ButtonEvent::C => self.delete(),
+            ButtonEvent::A => Response::Activated,
+            ButtonEvent::B => Response::Cancelled,
+            ButtonEvent::D => Response::Ignored,
+        }
+    }
+
+    fn draw<D>(&self, display: &mut D, theme: &UiTheme, focused: bool) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let color = theme.item_color(focused);
+        let font = theme.text_font;
+        let row_height = theme.spacing.row_height;
+        let margin = theme.spacing.margin;
+
+        Text::with_baseline(
+            i18n::tr(self.label),
+            self.bounds.top_left + Point::new(margin, row_height / 2),
+            theme.style(color),
+            Baseline::Middle,
+        )
+        .draw(display)?;
+
+        let field = Rectangle::new(
+            self.bounds.top_left + Point::new(margin, row_height),
+            Size::new(
+                self.bounds.size.width.saturating_sub(margin as u32 * 2),
+                font.line_height() + PADDING as u32 * 2,
+            ),
+        );
+        field
+            .into_styled(PrimitiveStyle::with_stroke(color, 1))
+            .draw(display)?;
+
+        // Scroll the text left once the cursor would leave the field
+        let shown = self.shown_text();
+        let inner = field.offset(-PADDING);
+        let before_cursor = font.measure(&shown[..self.cursor]) as i32;
+        let cursor_width = match shown[self.cursor..].chars().next() {
+            Some(c) => font.glyph(c).advance as i32,
+            None => font.glyph(' ').advance as i32 * 2,
+        };
+        let scroll = (before_cursor + cursor_width - inner.size.width as i32).max(0);
+
+        let mut clipped = display.clipped(&inner);
+        let origin = inner.top_left - Point::new(scroll, 0);
+        Text::with_baseline(&shown, origin, theme.style(theme.foreground), Baseline::Top)
+            .draw(&mut clipped)?;
+
+        if focused {
+            let underline_y = inner.top_left.y + font.line_height() as i32;
+            let x = origin.x + before_cursor;
+            Line::new(
+                Point::new(x, underline_y),
+                Point::new(x + cursor_width - 2, underline_y),
+            )
+            .into_styled(PrimitiveStyle::with_stroke(theme.accent, 2))
+            .draw(&mut clipped)?;
+        }
+        Ok(())
+    }
+}
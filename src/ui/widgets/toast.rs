@@ -0,0 +1,108 @@
+use alloc::string::String;
+use embassy_time::{Duration, Instant};
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::{DrawTarget, Point, Primitive, Size},
+    primitives::{PrimitiveStyle, Rectangle, RoundedRectangle},
+    text::{Alignment, Baseline, Text, TextStyleBuilder},
+    Drawable,
+};
+
+use super::Widget;
+use crate::ui::theme::UiTheme;
+
+const PADDING: u32 = 8;
+
+/// Short message shown at the bottom of the screen for a while, never takes focus.
+pub struct Toast {
+    bounds: Rectangle,
+    message: String,
+    until: Instant,
+    urgent: bool,
+}
+
+impl Toast {
+    /// `bounds` is the area the toast is placed in, usually the screen content area.
+    pub fn new(bounds: Rectangle, message: impl Into<String>, duration: Duration) -> Self {
+        Self {
+            bounds,
+            message: message.into(),
+            until: Instant::now() + duration,
+            urgent: false,
+        }
+    }
+
+    /// Draw in the warning color, for problems the user should notice.
+    pub fn urgent(mut self) -> Self {
+        self.urgent = true;
+        self
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn is_expired(&self) -> bool {
+        Instant::now() >= self.until
+    }
+
+    /// The bubble around the message, centered at the bottom of the bounds.
+    fn bubble(&self, theme: &UiTheme) -> Rectangle {
+        let font = theme.text_font;
+        let max_width = self
+            .bounds
+            .size
+            .width
+            .saturating_sub(theme.spacing.margin as u32 * 2);
+        let size = Size::new(
+            (font.measure(&self.message) + PADDING * 2).min(max_width),
+            font.line_height() + PADDING,
+        );
+        let bottom = self.bounds.top_left.y + self.bounds.size.height as i32 - theme.spacing.margin;
+        Rectangle::new(
+            Point::new(
+                self.bounds.center().x - size.width as i32 / 2,
+                bottom - size.height as i32,
+            ),
+            size,
+        )
+    }
+}
+
+impl Widget for Toast {
+    fn bounds(&self) -> Rectangle {
+        self.bounds
+    }
+
+    fn set_bounds(&mut self, bounds: Rectangle) {
+        self.bounds = bounds;
+    }
+
+    fn draw<D>(&self, display: &mut D, theme: &UiTheme, _focused: bool) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let bubble = self.bubble(theme);
+        let color = if self.urgent {
+            theme.warning
+        } else {
+            theme.accent
+        };
+        RoundedRectangle::with_equal_corners(bubble, Size::new_equal(8))
+            .into_styled(PrimitiveStyle::with_fill(color))
+            .draw(display)?;
+
+        let text_style = TextStyleBuilder::new()
+            .alignment(Alignment::Center)
+            .baseline(Baseline::Middle)
+            .build();
+        Text::with_text_style(
+            &self.message,
+            bubble.center(),
+            theme.style(theme.on_accent),
+            text_style,
+        )
+        .draw(display)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,100 @@
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::{DrawTarget, Point, Primitive, Size},
+    primitives::{Circle, PrimitiveStyle, Rectangle, RoundedRectangle},
+    text::{Baseline, Text},
+    Drawable,
+};
+
+use super::{Response, Widget};
+use crate::i18n::{self, Msg};
+use crate::state::ButtonEvent;
+use crate::ui::theme::UiTheme;
+
+const SWITCH_SIZE: Size = Size::new(36, 18);
+
+/// On/off switch with a label, flipped with A, Left or Right.
+pub struct Toggle {
+    bounds: Rectangle,
+    label: Msg,
+    on: bool,
+}
+
+impl Toggle {
+    pub fn new(bounds: Rectangle, label: Msg, on: bool) -> Self {
+        Self { bounds, label, on }
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.on
+    }
+
+    pub fn set(&mut self, on: bool) {
+        self.on = on;
+    }
+}
+
+impl Widget for Toggle {
+    fn bounds(&self) -> Rectangle {
+        self.bounds
+    }
+
+    fn set_bounds(&mut self, bounds: Rectangle) {
+        self.bounds = bounds;
+    }
+
+    fn handle(&mut self, event: ButtonEvent) -> Response {
+        match event {
+            ButtonEvent::A | ButtonEvent::Left | ButtonEvent::Right => {
+                self.on = !self.on;
+                Response::Changed
+            }
+            _ => Response::Ignored,
+        }
+    }
+
+    fn draw<D>(&self, display: &mut D, theme: &UiTheme, focused: bool) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let color = theme.item_color(focused);
+        let center_y = self.bounds.center().y;
+        Text::with_baseline(
+            i18n::tr(self.label),
+            Point::new(self.bounds.top_left.x + theme.spacing.margin, center_y),
+            theme.style(color),
+            Baseline::Middle,
+        )
+        .draw(display)?;
+
+        let right = self.bounds.top_left.x + self.bounds.size.width as i32 - theme.spacing.margin;
+        let track = Rectangle::new(
+            Point::new(
+                right - SWITCH_SIZE.width as i32,
+                center_y - SWITCH_SIZE.height as i32 / 2,
+            ),
+            SWITCH_SIZE,
+        );
+        let radius = Size::new_equal(SWITCH_SIZE.height / 2);
+        let track_style = if self.on {
+            PrimitiveStyle::with_fill(theme.accent)
+        } else {
+            PrimitiveStyle::with_stroke(color, 1)
+        };
+        RoundedRectangle::with_equal_corners(track, radius)
+            .into_styled(track_style)
+            .draw(display)?;
+
+        let knob = SWITCH_SIZE.height - 6;
+        let knob_x = if self.on {
+            track.top_left.x + (SWITCH_SIZE.width - knob) as i32 - 3
+        } else {
+            track.top_left.x + 3
+        };
+        let knob_color = if self.on { theme.on_accent } else { color };
+        Circle::new(Point::new(knob_x, track.top_left.y + 3), knob)
+            .into_styled(PrimitiveStyle::with_fill(knob_color))
+            .draw(display)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+#!/usr/bin/env python3
+"""Turn a screenshot captured over the serial console into a PNG.
+
+Press C + D on the device to capture. The firmware prints the frame between
+`=== SCREENSHOT BEGIN ... ===` and `=== SCREENSHOT END ... ===` markers.
+
+Usage:
+    # read a saved monitor log (or `-` for stdin)
+    tools/screenshot.py monitor.log -o screen.png
+    # listen on the serial port directly (needs pyserial)
+    tools/screenshot.py --port /dev/ttyACM0 -o screen.png
+"""
+
+import argparse
+import base64
+import struct
+import sys
+import zlib
+
+BEGIN = "=== SCREENSHOT BEGIN"
+END = "=== SCREENSHOT END"
+
+
+def serial_lines(port, baud):
+    import serial  # pyserial, only needed for live capture
+
+    with serial.Serial(port, baud, timeout=1) as conn:
+        print(f"Waiting for a screenshot on {port}, press C + D on the device", file=sys.stderr)
+        while True:
+            line = conn.readline()
+            if line:
+                yield line.decode("utf-8", errors="replace")
+
+
+def file_lines(path):
+    if path == "-":
+        yield from sys.stdin
+    else:
+        with open(path, encoding="utf-8", errors="replace") as f:
+            yield from f
+
+
+def read_capture(lines):
+    """Return (width, height, payload bytes) of the first complete capture."""
+    header = None
+    chunks = []
+    for line in lines:
+        line = line.strip()
+        if BEGIN in line:
+            fields = line[line.index(BEGIN) + len(BEGIN):].split()
+            width, height, fmt = int(fields[0]), int(fields[1]), fields[2]
+            if fmt != "rgb565-rle":
+                raise SystemExit(f"Unsupported format: {fmt}")
+            header = (width, height)
+            chunks = []
+        elif END in line and header:
+            fields = line[line.index(END) + len(END):].split()
+            size, crc = int(fields[0]), int(fields[1], 16)
+            payload = b"".join(chunks)
+            if len(payload) != size:
+                raise SystemExit(f"Truncated capture: got {len(payload)} of {size} bytes")
+            if zlib.crc32(payload) != crc:
+                raise SystemExit("Checksum mismatch, capture is corrupted")
+            return header[0], header[1], payload
+        elif header and line:
+            try:
+                chunks.append(base64.b64decode(line, validate=True))
+            except ValueError:
+                # A log line interleaved with the capture
+                continue
+    raise SystemExit("No complete screenshot found")
+
+
+def decode_rle(payload, width, height):
+    """Expand [run - 1, hi, lo] records of RGB565 into RGB888 rows."""
+    pixels = bytearray()
+    for i in range(0, len(payload), 3):
+        run, value = payload[i] + 1, (payload[i + 1] << 8) | payload[i + 2]
+        r = (value >> 11) & 0x1F
+        g = (value >> 5) & 0x3F
+        b = value & 0x1F
+        rgb = bytes(((r * 255 + 15) // 31, (g * 255 + 31) // 63, (b * 255 + 15) // 31))
+        pixels += rgb * run
+    if len(pixels) != width * height * 3:
+        raise SystemExit(f"Expected {width * height} pixels, got {len(pixels) // 3}")
+    stride = width * 3
+    return [pixels[y * stride:(y + 1) * stride] for y in range(height)]
+
+
+def write_png(path, width, height, rows):
+    def chunk(kind, data):
+        return (
+            struct.pack(">I", len(data))
+            + kind
+            + data
+            + struct.pack(">I", zlib.crc32(kind + data) & 0xFFFFFFFF)
+        )
+
+    raw = b"".join(b"\x00" + bytes(row) for row in rows)
+    with open(path, "wb") as f:
+        f.write(b"\x89PNG\r\n\x1a\n")
+        f.write(chunk(b"IHDR", struct.pack(">IIBBBBB", width, height, 8, 2, 0, 0, 0)))
+        f.write(chunk(b"IDAT", zlib.compress(raw, 9)))
+        f.write(chunk(b"IEND", b""))
+
+
+def main():
+    parser = argparse.ArgumentParser(description=__doc__, formatter_class=argparse.RawDescriptionHelpFormatter)
+    parser.add_argument("log", nargs="?", default="-", help="monitor log to read, - for stdin")
+    parser.add_argument("--port", help="serial port to listen on instead of a log")
+    parser.add_argument("--baud", type=int, default=115200)
+    parser.add_argument("-o", "--output", default="screenshot.png")
+    args = parser.parse_args()
+
+    lines = serial_lines(args.port, args.baud) if args.port else file_lines(args.log)
+    width, height, payload = read_capture(lines)
+    write_png(args.output, width, height, decode_rle(payload, width, height))
+    print(f"Saved {width}x{height} screenshot to {args.output}", file=sys.stderr)
+
+
+if __name__ == "__main__":
+    main()
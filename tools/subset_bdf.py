@@ -0,0 +1,62 @@
+#!/usr/bin/env python3
+"""Cut a BDF font down to the characters the firmware can display.
+
+The full misc-fixed fonts cover thousands of glyphs; the build only needs
+Latin, Cyrillic (including Ukrainian letters) and common punctuation.
+
+Usage:
+    tools/subset_bdf.py 10x20.bdf assets/fonts/10x20.bdf
+"""
+
+import sys
+
+RANGES = [
+    (0x0020, 0x007E),  # Basic Latin
+    (0x00A0, 0x00FF),  # Latin-1 supplement
+    (0x0400, 0x045F),  # Cyrillic
+    (0x0490, 0x0491),  # Ґ ґ
+    (0x2010, 0x2026),  # Dashes, quotes, bullet, ellipsis
+    (0x20AC, 0x20AC),  # €
+    (0x2116, 0x2116),  # №
+    (0x2190, 0x2193),  # Arrows
+    (0xFFFD, 0xFFFD),  # Replacement character
+]
+
+
+def wanted(code):
+    return any(lo <= code <= hi for lo, hi in RANGES)
+
+
+def subset(src, dst):
+    with open(src, encoding="latin-1") as f:
+        lines = f.read().splitlines()
+
+    header, glyphs, current = [], [], None
+    for line in lines:
+        if line.startswith("STARTCHAR"):
+            current = [line]
+        elif current is not None:
+            current.append(line)
+            if line.startswith("ENDCHAR"):
+                encoding = next(l for l in current if l.startswith("ENCODING"))
+                if wanted(int(encoding.split()[1])):
+                    glyphs.append(current)
+                current = None
+        elif line.startswith("CHARS "):
+            header.append(None)  # patched below
+        elif line != "ENDFONT":
+            header.append(line)
+
+    with open(dst, "w", encoding="latin-1") as f:
+        for line in header:
+            f.write(f"CHARS {len(glyphs)}\n" if line is None else line + "\n")
+        for glyph in glyphs:
+            f.write("\n".join(glyph) + "\n")
+        f.write("ENDFONT\n")
+    print(f"{dst}: {len(glyphs)} glyphs", file=sys.stderr)
+
+
+if __name__ == "__main__":
+    if len(sys.argv) != 3:
+        raise SystemExit(__doc__)
+    subset(sys.argv[1], sys.argv[2])